@@ -0,0 +1,201 @@
+//! Text analysis behind the paid analyzer. Pure functions over the uploaded
+//! text; the background analysis job stores one `AnalysisReport` per order item,
+//! and the quote page shows an `AnalysisPreview` of the whole order.
+
+mod readability;
+mod stopwords;
+
+use std::collections::HashMap;
+
+use crate::{
+    constants::analysis::{MIN_NGRAM_COUNT, PREVIEW_TOP_WORDS, READING_WORDS_PER_MINUTE, TOP_TERMS_LIMIT},
+    models::analysis::{AnalysisPreview, AnalysisReport, TermCount},
+};
+
+use stopwords::is_stopword;
+
+const SENTENCE_TERMINATORS: &[char] = &['.', '!', '?', '…', '。', '！', '？'];
+
+pub fn analyze(text: &str) -> AnalysisReport {
+    let sentences: Vec<Vec<String>> = split_sentences(text)
+        .map(|sentence| words(sentence).map(str::to_lowercase).collect::<Vec<_>>())
+        .filter(|words| !words.is_empty())
+        .collect();
+
+    let all_words: Vec<&str> = sentences.iter().flatten().map(String::as_str).collect();
+    let word_count = all_words.len();
+    let sentence_count = sentences.len();
+    let letters: usize = all_words.iter().map(|word| word.chars().count()).sum();
+    let syllables: usize = all_words.iter().map(|word| readability::syllables(word)).sum();
+
+    AnalysisReport {
+        character_count: text.chars().count(),
+        word_count,
+        sentence_count,
+        paragraph_count: count_paragraphs(text),
+        average_word_length: ratio(letters, word_count),
+        average_sentence_length: ratio(word_count, sentence_count),
+        reading_time_seconds: (word_count as f64 / READING_WORDS_PER_MINUTE * 60.0).ceil() as u64,
+        flesch_reading_ease: readability::flesch_reading_ease(word_count, sentence_count, syllables),
+        top_words: top_terms(all_words.iter().copied().filter(|word| is_content_word(word)).map(str::to_string), 1),
+        top_bigrams: top_terms(ngrams(&sentences, 2), MIN_NGRAM_COUNT),
+        top_trigrams: top_terms(ngrams(&sentences, 3), MIN_NGRAM_COUNT),
+    }
+}
+
+/// Builds the free preview over an order's files one text at a time, so only
+/// one file's text is held at once.
+#[derive(Default)]
+pub struct PreviewBuilder {
+    word_count: usize,
+    content_words: HashMap<String, usize>,
+}
+
+impl PreviewBuilder {
+    pub fn add(&mut self, text: &str) {
+        for word in words(text) {
+            self.word_count += 1;
+            let word = word.to_lowercase();
+            if is_content_word(&word) {
+                *self.content_words.entry(word).or_default() += 1;
+            }
+        }
+    }
+
+    pub fn finish(self) -> AnalysisPreview {
+        let mut top_words = rank(self.content_words, 1);
+        top_words.truncate(PREVIEW_TOP_WORDS);
+        AnalysisPreview {
+            word_count: self.word_count,
+            top_words,
+        }
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        return 0.0;
+    }
+    numerator as f64 / denominator as f64
+}
+
+/// Splits after terminal punctuation. Runs like "?!" or "..." end a single sentence.
+fn split_sentences(text: &str) -> impl Iterator<Item = &str> {
+    text.split_inclusive(SENTENCE_TERMINATORS)
+        .filter(|segment| segment.chars().any(char::is_alphanumeric))
+}
+
+/// Words are runs of letters and digits; inner apostrophes and hyphens are kept
+/// ("don't", "well-known"), surrounding punctuation is not.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_alphanumeric() || matches!(c, '\'' | '’' | '-')))
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+}
+
+fn count_paragraphs(text: &str) -> usize {
+    let mut paragraphs = 0;
+    let mut in_paragraph = false;
+    for line in text.lines() {
+        let blank = line.trim().is_empty();
+        if !blank && !in_paragraph {
+            paragraphs += 1;
+        }
+        in_paragraph = !blank;
+    }
+    paragraphs
+}
+
+/// Stopwords and bare numbers say nothing about what a text is about.
+fn is_content_word(word: &str) -> bool {
+    !is_stopword(word) && word.chars().any(char::is_alphabetic)
+}
+
+/// N-grams never cross a sentence boundary and skip any window containing a stopword.
+fn ngrams(sentences: &[Vec<String>], n: usize) -> impl Iterator<Item = String> + '_ {
+    sentences.iter().flat_map(move |sentence| {
+        sentence
+            .windows(n)
+            .filter(|window| window.iter().all(|word| is_content_word(word)))
+            .map(|window| window.join(" "))
+    })
+}
+
+fn top_terms(terms: impl Iterator<Item = String>, min_count: usize) -> Vec<TermCount> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for term in terms {
+        *counts.entry(term).or_default() += 1;
+    }
+    rank(counts, min_count)
+}
+
+/// Most frequent terms first, ties broken alphabetically so reports are stable.
+fn rank(counts: HashMap<String, usize>, min_count: usize) -> Vec<TermCount> {
+    let mut ranked: Vec<TermCount> = counts
+        .into_iter()
+        .filter(|(_, count)| *count >= min_count)
+        .map(|(term, count)| TermCount { term, count })
+        .collect();
+    ranked.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.term.cmp(&b.term)));
+    ranked.truncate(TOP_TERMS_LIMIT);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "The quick brown fox jumps. The quick brown fox sleeps!\n\n\
+                          Is the fox quick? Yes... the fox is quick.";
+
+    fn terms(report: &[TermCount]) -> Vec<(&str, usize)> {
+        report.iter().map(|t| (t.term.as_str(), t.count)).collect()
+    }
+
+    #[test]
+    fn test_analyze_counts() {
+        let report = analyze(SAMPLE);
+        assert_eq!(report.word_count, 19);
+        assert_eq!(report.sentence_count, 5);
+        assert_eq!(report.paragraph_count, 2);
+        assert_eq!(report.character_count, SAMPLE.chars().count());
+        assert_eq!(report.average_sentence_length, 3.8);
+        assert_eq!(report.reading_time_seconds, 5);
+    }
+
+    #[test]
+    fn test_analyze_top_terms_skip_stopwords() {
+        let report = analyze(SAMPLE);
+        assert_eq!(&terms(&report.top_words)[..3], &[("fox", 4), ("quick", 4), ("brown", 2)]);
+        assert_eq!(terms(&report.top_bigrams), vec![("brown fox", 2), ("quick brown", 2)]);
+        assert_eq!(terms(&report.top_trigrams), vec![("quick brown fox", 2)]);
+    }
+
+    #[test]
+    fn test_preview_spans_files_and_matches_reports() {
+        let mut builder = PreviewBuilder::default();
+        builder.add(SAMPLE);
+        builder.add("A fox, again.");
+        let preview = builder.finish();
+
+        assert_eq!(preview.word_count, analyze(SAMPLE).word_count + 3);
+        assert_eq!(terms(&preview.top_words), vec![("fox", 5), ("quick", 4), ("brown", 2)]);
+    }
+
+    #[test]
+    fn test_words_keep_inner_apostrophes_and_hyphens() {
+        let found: Vec<&str> = words("\"Don't\" stop -- it's well-known, (really)!").collect();
+        assert_eq!(found, vec!["Don't", "stop", "it's", "well-known", "really"]);
+    }
+
+    #[test]
+    fn test_analyze_empty_text() {
+        let report = analyze("   \n\n ");
+        assert_eq!(report.word_count, 0);
+        assert_eq!(report.sentence_count, 0);
+        assert_eq!(report.paragraph_count, 0);
+        assert_eq!(report.average_word_length, 0.0);
+        assert_eq!(report.flesch_reading_ease, 0.0);
+        assert!(report.top_words.is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+const HANGUL_SYLLABLES: std::ops::RangeInclusive<char> = '\u{AC00}'..='\u{D7A3}';
+
+/// Flesch reading ease. Defined for English; other scripts get a rough figure
+/// from `syllables`. Zero for text with no words or sentences.
+pub fn flesch_reading_ease(words: usize, sentences: usize, syllables: usize) -> f64 {
+    if words == 0 || sentences == 0 {
+        return 0.0;
+    }
+    let words_per_sentence = words as f64 / sentences as f64;
+    let syllables_per_word = syllables as f64 / words as f64;
+    206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word
+}
+
+/// Estimates syllables: one per Hangul block, otherwise vowel groups with a
+/// silent trailing "e". Every word has at least one.
+pub fn syllables(word: &str) -> usize {
+    let hangul = word.chars().filter(|c| HANGUL_SYLLABLES.contains(c)).count();
+    if hangul > 0 {
+        return hangul;
+    }
+
+    let lower = word.to_lowercase();
+    let mut count = 0;
+    let mut previous_was_vowel = false;
+    for c in lower.chars() {
+        let is_vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if is_vowel && !previous_was_vowel {
+            count += 1;
+        }
+        previous_was_vowel = is_vowel;
+    }
+
+    if count > 1 && lower.ends_with('e') && !lower.ends_with("le") {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syllables() {
+        assert_eq!(syllables("cat"), 1);
+        assert_eq!(syllables("make"), 1);
+        assert_eq!(syllables("table"), 2);
+        assert_eq!(syllables("readability"), 5);
+        assert_eq!(syllables("안녕하세요"), 5);
+        assert_eq!(syllables("hmm"), 1);
+    }
+
+    #[test]
+    fn test_flesch_reading_ease() {
+        // 100 words, 10 sentences, 130 syllables: 206.835 - 10.15 - 109.98
+        assert!((flesch_reading_ease(100, 10, 130) - 86.705).abs() < 1e-9);
+        assert_eq!(flesch_reading_ease(0, 0, 0), 0.0);
+    }
+}
@@ -0,0 +1,80 @@
+//! Synchronizer-token CSRF protection. The token lives in the session and is
+//! exposed to views through a task-local for the duration of each request, so
+//! forms and `base_layout` can embed it without threading it through handlers.
+//! It is minted the first time a view embeds it, so only visitors who were
+//! shown a form get a session for it.
+
+use std::cell::RefCell;
+
+use tower_sessions::Session;
+
+use super::token::{constant_time_eq, generate_token};
+
+const SESSION_CSRF_TOKEN_KEY: &str = "csrf_token";
+
+pub const CSRF_HEADER: &str = "x-csrf-token";
+pub const CSRF_FORM_FIELD: &str = "_csrf";
+
+struct TokenSlot {
+    token: Option<String>,
+    /// False on routes that must not write the session.
+    may_mint: bool,
+    minted: bool,
+}
+
+tokio::task_local! {
+    static CSRF_TOKEN: RefCell<TokenSlot>;
+}
+
+/// The session's token, if it has one; never creates one. `sign_in` flushes
+/// the session, so every sign-in rotates the token.
+pub async fn stored_token(session: &Session) -> Result<Option<String>, tower_sessions::session::Error> {
+    session.get::<String>(SESSION_CSRF_TOKEN_KEY).await
+}
+
+pub async fn store_token(session: &Session, token: &str) -> Result<(), tower_sessions::session::Error> {
+    session.insert(SESSION_CSRF_TOKEN_KEY, token).await
+}
+
+pub fn tokens_match(expected: &str, provided: Option<&str>) -> bool {
+    provided.is_some_and(|provided| constant_time_eq(expected.as_bytes(), provided.as_bytes()))
+}
+
+/// Makes `stored` visible to `current_token` while `f` runs. Returns what `f`
+/// did and the token minted meanwhile, if any, for the caller to store.
+pub async fn scope<F: std::future::Future>(stored: Option<String>, may_mint: bool, f: F) -> (F::Output, Option<String>) {
+    let slot = RefCell::new(TokenSlot { token: stored, may_mint, minted: false });
+
+    CSRF_TOKEN
+        .scope(slot, async {
+            let output = f.await;
+            let minted = CSRF_TOKEN.with(|slot| {
+                let slot = slot.borrow();
+                slot.token.clone().filter(|_| slot.minted)
+            });
+            (output, minted)
+        })
+        .await
+}
+
+/// The token for a form being rendered, minted on first use. Empty outside
+/// the csrf middleware, which only happens for responses that never reach a
+/// handler, and on routes that may not mint when the session has none.
+pub fn current_token() -> String {
+    CSRF_TOKEN
+        .try_with(|slot| {
+            let mut slot = slot.borrow_mut();
+            if slot.token.is_none() && slot.may_mint {
+                slot.token = Some(generate_token());
+                slot.minted = true;
+            }
+            slot.token.clone().unwrap_or_default()
+        })
+        .unwrap_or_default()
+}
+
+/// The token if the session has one or a form on this page minted one.
+/// Never mints: a page without forms has nothing to protect.
+pub fn issued_token() -> Option<String> {
+    CSRF_TOKEN.try_with(|slot| slot.borrow().token.clone()).ok().flatten()
+}
@@ -1,6 +1,9 @@
+pub mod csrf;
 mod current_user;
+pub mod passkey;
 pub mod service;
 mod token;
+pub mod two_factor;
 
 pub use current_user::{CurrentUser, SESSION_USER_ID_KEY};
 pub use token::generate_token;
@@ -0,0 +1,165 @@
+//! WebAuthn passkey ceremonies. Challenge state lives in the session between
+//! the start and finish requests, so each ceremony is single-use.
+
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use webauthn_rs::prelude::{
+    CreationChallengeResponse, DiscoverableAuthentication, DiscoverableKey, Passkey,
+    PasskeyRegistration, PublicKeyCredential, RegisterPublicKeyCredential, RequestChallengeResponse,
+    Uuid, Webauthn, WebauthnBuilder, WebauthnError,
+};
+
+use crate::{
+    config::PasskeyConfig,
+    data::{commands, errors::DataError, queries},
+    models::{passkey::PasskeyCredential, UserId},
+};
+
+pub type SharedWebauthn = Arc<Webauthn>;
+
+const REGISTRATION_STATE_KEY: &str = "passkey_registration";
+const AUTHENTICATION_STATE_KEY: &str = "passkey_authentication";
+
+#[derive(Debug, thiserror::Error)]
+pub enum PasskeyError {
+    #[error("WebAuthn error: {0}")]
+    Webauthn(#[from] WebauthnError),
+
+    #[error("Stored passkey is corrupt: {0}")]
+    Corrupt(#[from] serde_json::Error),
+
+    #[error("No passkey ceremony in progress")]
+    MissingState,
+
+    #[error("Credential is not registered")]
+    UnknownCredential,
+
+    #[error("{0}")]
+    Data(#[from] DataError),
+
+    #[error("{0}")]
+    Session(#[from] tower_sessions::session::Error),
+}
+
+pub fn create_webauthn(config: &PasskeyConfig) -> SharedWebauthn {
+    let webauthn = WebauthnBuilder::new(config.rp_id(), config.rp_origin())
+        .and_then(|builder| builder.rp_name(config.rp_name()).build())
+        .expect("Failed to build WebAuthn relying party from BASE_URL");
+
+    Arc::new(webauthn)
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegistrationState {
+    user_handle: Uuid,
+    registration: PasskeyRegistration,
+}
+
+/// Begins registering a new passkey for `user_id`. All of a user's passkeys share
+/// one WebAuthn user handle so authenticators group them under the same account.
+pub async fn start_registration(
+    webauthn: &Webauthn,
+    session: &Session,
+    user_id: &UserId,
+    email: &str,
+) -> Result<CreationChallengeResponse, PasskeyError> {
+    let existing = queries::passkey::get_passkey_credentials_for_user(user_id).await?;
+
+    let user_handle = match existing.first() {
+        Some(credential) => Uuid::parse_str(&credential.user_handle).map_err(|_| WebauthnError::InvalidUserUniqueId)?,
+        None => Uuid::new_v4(),
+    };
+
+    let exclude_credentials = existing
+        .iter()
+        .map(|credential| decode(credential).map(|passkey| passkey.cred_id().clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (challenge, registration) =
+        webauthn.start_passkey_registration(user_handle, email, email, Some(exclude_credentials))?;
+
+    session
+        .insert(REGISTRATION_STATE_KEY, RegistrationState { user_handle, registration })
+        .await?;
+
+    Ok(challenge)
+}
+
+pub async fn finish_registration(
+    webauthn: &Webauthn,
+    session: &Session,
+    user_id: &UserId,
+    name: String,
+    credential: &RegisterPublicKeyCredential,
+) -> Result<(), PasskeyError> {
+    let state: RegistrationState = session
+        .remove(REGISTRATION_STATE_KEY)
+        .await?
+        .ok_or(PasskeyError::MissingState)?;
+
+    let passkey = webauthn.finish_passkey_registration(credential, &state.registration)?;
+
+    commands::passkey::create_passkey(commands::passkey::CreatePasskeyParams {
+        user_id: user_id.clone(),
+        name,
+        credential_id: encode_credential_id(passkey.cred_id().as_ref()),
+        user_handle: state.user_handle.to_string(),
+        credential: serde_json::to_string(&passkey)?,
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Begins a discoverable sign-in — the authenticator chooses the account.
+pub async fn start_authentication(
+    webauthn: &Webauthn,
+    session: &Session,
+) -> Result<RequestChallengeResponse, PasskeyError> {
+    let (challenge, authentication) = webauthn.start_discoverable_authentication()?;
+    session.insert(AUTHENTICATION_STATE_KEY, authentication).await?;
+    Ok(challenge)
+}
+
+/// Verifies the assertion and returns the user it belongs to.
+pub async fn finish_authentication(
+    webauthn: &Webauthn,
+    session: &Session,
+    credential: &PublicKeyCredential,
+) -> Result<UserId, PasskeyError> {
+    let state: DiscoverableAuthentication = session
+        .remove(AUTHENTICATION_STATE_KEY)
+        .await?
+        .ok_or(PasskeyError::MissingState)?;
+
+    let (user_handle, credential_id) = webauthn.identify_discoverable_authentication(credential)?;
+    let credential_id = encode_credential_id(credential_id);
+
+    let stored = queries::passkey::get_passkey_credential(&credential_id)
+        .await?
+        .filter(|stored| stored.user_handle == user_handle.to_string())
+        .ok_or(PasskeyError::UnknownCredential)?;
+
+    let mut passkey = decode(&stored)?;
+    let result = webauthn.finish_discoverable_authentication(
+        credential,
+        state,
+        &[DiscoverableKey::from(&passkey)],
+    )?;
+
+    passkey.update_credential(&result);
+    commands::passkey::record_passkey_use(&credential_id, serde_json::to_string(&passkey)?).await?;
+
+    Ok(stored.user)
+}
+
+fn decode(stored: &PasskeyCredential) -> Result<Passkey, PasskeyError> {
+    Ok(serde_json::from_str(&stored.credential)?)
+}
+
+fn encode_credential_id(credential_id: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(credential_id)
+}
@@ -1,8 +1,17 @@
 //! Auth service layer — decouples middleware from data queries.
 
+use tower_sessions::Session;
+
 use crate::{data::queries, models::UserId};
 
-use super::CurrentUser;
+use super::{CurrentUser, SESSION_USER_ID_KEY};
+
+/// Starts an authenticated session for `user_id`. Shared by every sign-in method;
+/// flushing first issues a fresh session id so a pre-login id can't be fixated.
+pub async fn sign_in(session: &Session, user_id: UserId) -> Result<(), tower_sessions::session::Error> {
+    session.flush().await?;
+    session.insert(SESSION_USER_ID_KEY, user_id).await
+}
 
 /// Loads user context from database. Called by session_context middleware.
 pub async fn load_user_context(user_id: &UserId) -> Result<Option<CurrentUser>, crate::data::errors::DataError> {
@@ -10,6 +10,11 @@ pub fn generate_token() -> String {
     URL_SAFE_NO_PAD.encode(&token_bytes)
 }
 
+/// Compares secrets without short-circuiting on the first differing byte.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,6 +25,13 @@ mod tests {
         assert!(token.len() > 40);
     }
 
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+    }
+
     #[test]
     fn test_generate_token_uniqueness() {
         let token1 = generate_token();
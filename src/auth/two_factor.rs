@@ -0,0 +1,196 @@
+//! TOTP second factor (RFC 6238, SHA-1, 6 digits, 30s steps) with single-use
+//! recovery codes. A session counts as verified only after the second factor
+//! passes; `sign_in` flushes the session, so every new sign-in starts unverified.
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use totp_rs::{Algorithm, Secret, SecretParseError, TotpUrlError, TOTP};
+use tower_sessions::Session;
+
+use super::token::constant_time_eq;
+use crate::{
+    constants::auth::{MAX_SECOND_FACTOR_FAILURES, SECOND_FACTOR_LOCKOUT_MINUTES},
+    data::{
+        commands::{self, two_factor::SecondFactorCode},
+        errors::DataError,
+    },
+    models::{two_factor::TotpCredential, UserId},
+};
+
+pub const SESSION_SECOND_FACTOR_KEY: &str = "second_factor_verified";
+
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP_SECS: u64 = 30;
+/// Accept the previous and next step to tolerate clock drift.
+const TOTP_SKEW_STEPS: u64 = 1;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_HALF_LENGTH: usize = 4;
+/// No 0/o, 1/l/i — recovery codes get read off paper.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+#[derive(Debug, thiserror::Error)]
+pub enum TwoFactorError {
+    #[error("Stored TOTP secret is corrupt: {0}")]
+    CorruptSecret(#[from] SecretParseError),
+
+    #[error("Invalid TOTP parameters: {0}")]
+    Totp(#[from] TotpUrlError),
+
+    #[error("{0}")]
+    Data(#[from] DataError),
+
+    #[error("{0}")]
+    Session(#[from] tower_sessions::session::Error),
+}
+
+/// Fresh random secret, base32 encoded as authenticator apps expect.
+pub fn new_secret() -> String {
+    match Secret::generate_secret().to_encoded() {
+        Secret::Encoded(encoded) => encoded,
+        Secret::Raw(_) => unreachable!("to_encoded always returns Secret::Encoded"),
+    }
+}
+
+fn totp(secret: &str, issuer: &str, account_name: &str) -> Result<TOTP, TwoFactorError> {
+    let bytes = Secret::Encoded(secret.to_string()).to_bytes()?;
+    // The otpauth label is "issuer:account", so neither part may contain ':'.
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        0,
+        TOTP_STEP_SECS,
+        bytes,
+        Some(issuer.replace(':', "")),
+        account_name.replace(':', ""),
+    )?;
+    Ok(totp)
+}
+
+/// The `otpauth://` URI encoded in the enrollment QR code.
+pub fn otpauth_uri(secret: &str, issuer: &str, account_name: &str) -> Result<String, TwoFactorError> {
+    Ok(totp(secret, issuer, account_name)?.get_url())
+}
+
+/// Returns the time step `code` is valid for, if any. The step is what the
+/// replay guard stores, so a code is accepted at most once.
+pub fn matching_step(secret: &str, code: &str, unix_time: u64) -> Result<Option<u64>, TwoFactorError> {
+    let totp = totp(secret, "", "")?;
+    let current = unix_time / TOTP_STEP_SECS;
+
+    let step = (current.saturating_sub(TOTP_SKEW_STEPS)..=current + TOTP_SKEW_STEPS)
+        .find(|step| constant_time_eq(totp.generate(step * TOTP_STEP_SECS).as_bytes(), code.as_bytes()));
+
+    Ok(step)
+}
+
+pub fn current_unix_time() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+/// Plaintext codes in `xxxx-xxxx` form. Shown once; only hashes are stored.
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::rng();
+    let mut half = || -> String {
+        (0..RECOVERY_CODE_HALF_LENGTH)
+            .map(|_| RECOVERY_CODE_ALPHABET[rng.random_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+            .collect()
+    };
+
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| format!("{}-{}", half(), half()))
+        .collect()
+}
+
+/// Hashes a recovery code after normalizing case, spaces and dashes.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+/// How a submitted second-factor code was received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecondFactorCheck {
+    Passed,
+    Failed,
+    /// Too many wrong codes: nothing is checked until the lock runs out.
+    LockedOut,
+}
+
+/// Checks a TOTP code or a recovery code behind the failure count, so a
+/// six-digit code can't be guessed online by whoever holds a signed-in session.
+/// The lock is checked and the failure counted by the same update that accepts
+/// or rejects the code.
+pub async fn check_second_factor(
+    user_id: &UserId,
+    credential: &TotpCredential,
+    code: &str,
+) -> Result<SecondFactorCheck, TwoFactorError> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let code = if code.len() == TOTP_DIGITS && code.chars().all(|c| c.is_ascii_digit()) {
+        let step = matching_step(&credential.secret, &code, current_unix_time())?;
+        SecondFactorCode::Totp { step: step.map(|step| step as i64) }
+    } else {
+        SecondFactorCode::RecoveryCode { hash: hash_recovery_code(&code) }
+    };
+
+    let lock_until = chrono::Utc::now() + chrono::Duration::minutes(SECOND_FACTOR_LOCKOUT_MINUTES);
+    let attempt = commands::two_factor::attempt_second_factor(user_id, code, MAX_SECOND_FACTOR_FAILURES, lock_until).await?;
+
+    Ok(match attempt {
+        Some(attempt) if attempt.accepted => SecondFactorCheck::Passed,
+        Some(attempt) if attempt.locked => {
+            tracing::warn!("Second factor locked for user {} after repeated wrong codes", user_id);
+            SecondFactorCheck::LockedOut
+        }
+        Some(_) => SecondFactorCheck::Failed,
+        None => SecondFactorCheck::LockedOut,
+    })
+}
+
+pub async fn mark_verified(session: &Session) -> Result<(), tower_sessions::session::Error> {
+    session.cycle_id().await?;
+    session.insert(SESSION_SECOND_FACTOR_KEY, true).await
+}
+
+pub async fn is_verified(session: &Session) -> Result<bool, tower_sessions::session::Error> {
+    Ok(session.get::<bool>(SESSION_SECOND_FACTOR_KEY).await?.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, SHA-1 seed "12345678901234567890".
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_matching_step_rfc6238_vector() {
+        assert_eq!(matching_step(RFC_SECRET, "287082", 59).unwrap(), Some(1));
+        assert_eq!(matching_step(RFC_SECRET, "081804", 1111111109).unwrap(), Some(37037036));
+    }
+
+    #[test]
+    fn test_matching_step_tolerates_one_step_of_drift() {
+        assert_eq!(matching_step(RFC_SECRET, "287082", 59 + 30).unwrap(), Some(1));
+        assert_eq!(matching_step(RFC_SECRET, "287082", 59 + 60).unwrap(), None);
+    }
+
+    #[test]
+    fn test_hash_recovery_code_normalizes_input() {
+        assert_eq!(hash_recovery_code("abcd-efgh"), hash_recovery_code(" ABCD EFGH "));
+        assert_ne!(hash_recovery_code("abcd-efgh"), hash_recovery_code("abcd-efgj"));
+    }
+
+    #[test]
+    fn test_generate_recovery_codes_format() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert!(codes.iter().all(|c| c.len() == 9 && c.as_bytes()[4] == b'-'));
+    }
+}
@@ -1,41 +1,348 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
 use axum::extract::FromRef;
+use webauthn_rs::prelude::Url;
 
-use crate::email::EmailConfig;
+use crate::{
+    auth::passkey::{self, SharedWebauthn},
+    email::EmailConfig,
+    payment::{self, MockScenario, SharedPaymentGateway},
+    rate_limit::{RateLimiter, SharedRateLimiter},
+    storage::SharedBlobStore,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
     #[error("Missing required environment variable: {0}")]
     MissingVar(String),
+    #[error("Invalid value for environment variable {0}: {1}")]
+    InvalidVar(String, String),
     #[error("Email configuration error: {0}")]
     Email(#[from] crate::email::EmailError),
 }
 
 #[derive(Clone)]
 pub struct PaymentConfig {
-    toss_client_key: String,
-    toss_secret_key: String,
+    mode: PaymentMode,
+    /// Only some Toss event types are signed; status changes are trusted via a payment lookup instead.
+    webhook_secret: Option<String>,
+}
+
+#[derive(Clone)]
+pub enum PaymentMode {
+    Toss {
+        client_key: String,
+        secret_key: String,
+    },
+    Mock {
+        scenario: MockScenario,
+    },
 }
 
 impl PaymentConfig {
     pub fn from_env() -> Result<Self, ConfigError> {
-        let toss_client_key = dotenvy::var("TOSS_CLIENT_KEY")
-            .map_err(|_| ConfigError::MissingVar("TOSS_CLIENT_KEY".to_string()))?;
+        // Deployments from before the mock gateway set only the Toss keys.
+        let mode_str = dotenvy::var("PAYMENT_MODE").unwrap_or_else(|_| "toss".to_string());
+
+        let mode = match mode_str.as_str() {
+            "toss" => {
+                let client_key = dotenvy::var("TOSS_CLIENT_KEY")
+                    .map_err(|_| ConfigError::MissingVar("TOSS_CLIENT_KEY".to_string()))?;
+                let secret_key = dotenvy::var("TOSS_SECRET_KEY")
+                    .map_err(|_| ConfigError::MissingVar("TOSS_SECRET_KEY".to_string()))?;
+
+                PaymentMode::Toss {
+                    client_key,
+                    secret_key,
+                }
+            }
+            "mock" => {
+                let scenario_str = dotenvy::var("MOCK_PAYMENT_SCENARIO")
+                    .map_err(|_| ConfigError::MissingVar("MOCK_PAYMENT_SCENARIO".to_string()))?;
+                let scenario = MockScenario::parse(&scenario_str).ok_or_else(|| {
+                    ConfigError::InvalidVar(
+                        "MOCK_PAYMENT_SCENARIO".to_string(),
+                        format!("expected success, decline, timeout or amount_mismatch, got '{}'", scenario_str),
+                    )
+                })?;
+
+                PaymentMode::Mock { scenario }
+            }
+            _ => {
+                return Err(ConfigError::InvalidVar(
+                    "PAYMENT_MODE".to_string(),
+                    format!("expected 'toss' or 'mock', got '{}'", mode_str),
+                ));
+            }
+        };
+
+        let webhook_secret = dotenvy::var("PAYMENT_WEBHOOK_SECRET").ok().filter(|secret| !secret.is_empty());
+
+        Ok(Self {
+            mode,
+            webhook_secret,
+        })
+    }
+
+    pub fn mode(&self) -> &PaymentMode {
+        &self.mode
+    }
+
+    pub fn webhook_secret(&self) -> Option<&str> {
+        self.webhook_secret.as_deref()
+    }
+}
+
+/// Seller details printed on invoices.
+#[derive(Clone)]
+pub struct BusinessConfig {
+    name: String,
+    registration_number: String,
+    address: String,
+    contact_email: String,
+}
+
+impl BusinessConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let var = |name: &str| dotenvy::var(name).map_err(|_| ConfigError::MissingVar(name.to_string()));
+
+        Ok(Self {
+            name: var("BUSINESS_NAME")?,
+            registration_number: var("BUSINESS_REGISTRATION_NUMBER")?,
+            address: var("BUSINESS_ADDRESS")?,
+            contact_email: var("BUSINESS_CONTACT_EMAIL")?,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn registration_number(&self) -> &str {
+        &self.registration_number
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn contact_email(&self) -> &str {
+        &self.contact_email
+    }
+}
+
+/// WebAuthn relying party, derived from `BASE_URL` so passkeys are bound to the
+/// same origin the magic links point at.
+#[derive(Clone)]
+pub struct PasskeyConfig {
+    rp_id: String,
+    rp_origin: Url,
+    rp_name: String,
+}
+
+impl PasskeyConfig {
+    pub fn from_env(site_name: &str) -> Result<Self, ConfigError> {
+        let base_url = dotenvy::var("BASE_URL")
+            .map_err(|_| ConfigError::MissingVar("BASE_URL".to_string()))?;
+
+        let rp_origin = Url::parse(&base_url)
+            .map_err(|e| ConfigError::InvalidVar("BASE_URL".to_string(), e.to_string()))?;
+        // WebAuthn relying party ids must be domain names; browsers reject IP addresses.
+        let rp_id = rp_origin
+            .domain()
+            .ok_or_else(|| {
+                ConfigError::InvalidVar(
+                    "BASE_URL".to_string(),
+                    format!("passkeys need a domain name such as http://localhost:8000, got '{}'", base_url),
+                )
+            })?
+            .to_string();
+
+        Ok(Self {
+            rp_id,
+            rp_origin,
+            rp_name: site_name.to_string(),
+        })
+    }
+
+    pub fn rp_id(&self) -> &str {
+        &self.rp_id
+    }
+
+    pub fn rp_origin(&self) -> &Url {
+        &self.rp_origin
+    }
+
+    pub fn rp_name(&self) -> &str {
+        &self.rp_name
+    }
+}
+
+/// Where uploaded text is kept.
+#[derive(Clone)]
+pub struct StorageConfig {
+    backend: StorageBackend,
+}
+
+#[derive(Clone)]
+pub enum StorageBackend {
+    Local { root: PathBuf },
+    Memory,
+}
+
+impl StorageConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let backend_str = dotenvy::var("STORAGE_BACKEND")
+            .map_err(|_| ConfigError::MissingVar("STORAGE_BACKEND".to_string()))?;
 
-        let toss_secret_key = dotenvy::var("TOSS_SECRET_KEY")
-            .map_err(|_| ConfigError::MissingVar("TOSS_SECRET_KEY".to_string()))?;
+        let backend = match backend_str.as_str() {
+            "local" => {
+                let root = dotenvy::var("STORAGE_PATH")
+                    .map_err(|_| ConfigError::MissingVar("STORAGE_PATH".to_string()))?;
+                StorageBackend::Local { root: PathBuf::from(root) }
+            }
+            "memory" => StorageBackend::Memory,
+            _ => {
+                return Err(ConfigError::InvalidVar(
+                    "STORAGE_BACKEND".to_string(),
+                    format!("expected 'local' or 'memory', got '{}'", backend_str),
+                ));
+            }
+        };
 
+        Ok(Self { backend })
+    }
+
+    pub fn backend(&self) -> &StorageBackend {
+        &self.backend
+    }
+}
+
+/// Intervals for the housekeeping jobs run by `jobs::Scheduler`, and how the
+/// analysis queue is worked.
+#[derive(Clone)]
+pub struct JobsConfig {
+    session_cleanup_interval: Duration,
+    magic_link_purge_interval: Duration,
+    stale_order_interval: Duration,
+    pending_order_ttl: Duration,
+    analysis_poll_interval: Duration,
+    analysis_workers: usize,
+}
+
+impl JobsConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
         Ok(Self {
-            toss_client_key,
-            toss_secret_key,
+            session_cleanup_interval: Duration::from_secs(parse_var("JOB_SESSION_CLEANUP_INTERVAL_SECS")?),
+            magic_link_purge_interval: Duration::from_secs(parse_var("JOB_MAGIC_LINK_PURGE_INTERVAL_SECS")?),
+            stale_order_interval: Duration::from_secs(parse_var("JOB_STALE_ORDER_INTERVAL_SECS")?),
+            pending_order_ttl: Duration::from_secs(parse_var::<u64>("PENDING_ORDER_TTL_MINUTES")? * 60),
+            analysis_poll_interval: Duration::from_secs(parse_var("JOB_ANALYSIS_POLL_INTERVAL_SECS")?),
+            analysis_workers: parse_var("ANALYSIS_WORKERS")?,
         })
     }
 
-    pub fn toss_client_key(&self) -> &str {
-        &self.toss_client_key
+    pub fn session_cleanup_interval(&self) -> Duration {
+        self.session_cleanup_interval
+    }
+
+    pub fn magic_link_purge_interval(&self) -> Duration {
+        self.magic_link_purge_interval
+    }
+
+    pub fn stale_order_interval(&self) -> Duration {
+        self.stale_order_interval
+    }
+
+    pub fn pending_order_ttl(&self) -> Duration {
+        self.pending_order_ttl
     }
 
-    pub fn toss_secret_key(&self) -> &str {
-        &self.toss_secret_key
+    pub fn analysis_poll_interval(&self) -> Duration {
+        self.analysis_poll_interval
+    }
+
+    /// How many analysis jobs run at once.
+    pub fn analysis_workers(&self) -> usize {
+        self.analysis_workers
+    }
+}
+
+/// Sliding-window limits for forms that send email (sign-in, contact).
+#[derive(Clone)]
+pub struct RateLimitConfig {
+    window: Duration,
+    max_per_ip: usize,
+    max_per_email: usize,
+    trusted_proxy_hops: usize,
+}
+
+impl RateLimitConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        // Optional: without it the peer address is the client.
+        let trusted_proxy_hops = match dotenvy::var("RATE_LIMIT_TRUSTED_PROXY_HOPS") {
+            Ok(value) => value.parse().map_err(|_| {
+                ConfigError::InvalidVar(
+                    "RATE_LIMIT_TRUSTED_PROXY_HOPS".to_string(),
+                    format!("expected a whole number, got '{}'", value),
+                )
+            })?,
+            Err(_) => 0,
+        };
+
+        Ok(Self {
+            window: Duration::from_secs(parse_var("RATE_LIMIT_WINDOW_SECS")?),
+            max_per_ip: parse_var("RATE_LIMIT_MAX_PER_IP")?,
+            max_per_email: parse_var("RATE_LIMIT_MAX_PER_EMAIL")?,
+            trusted_proxy_hops,
+        })
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn max_per_ip(&self) -> usize {
+        self.max_per_ip
+    }
+
+    pub fn max_per_email(&self) -> usize {
+        self.max_per_email
+    }
+
+    /// Reverse proxies in front of the app that append to `X-Forwarded-For`.
+    pub fn trusted_proxy_hops(&self) -> usize {
+        self.trusted_proxy_hops
+    }
+}
+
+/// Reads a required variable that must be a positive number.
+fn parse_var<T>(name: &str) -> Result<T, ConfigError>
+where
+    T: std::str::FromStr + PartialOrd + Default,
+{
+    let value = dotenvy::var(name).map_err(|_| ConfigError::MissingVar(name.to_string()))?;
+    match value.parse::<T>() {
+        Ok(parsed) if parsed > T::default() => Ok(parsed),
+        _ => Err(ConfigError::InvalidVar(
+            name.to_string(),
+            format!("expected a positive number, got '{}'", value),
+        )),
+    }
+}
+
+/// Reads a required variable that must be exactly "true" or "false".
+fn parse_bool_var(name: &str) -> Result<bool, ConfigError> {
+    match dotenvy::var(name)
+        .map_err(|_| ConfigError::MissingVar(name.to_string()))?
+        .as_str()
+    {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        other => Err(ConfigError::InvalidVar(
+            name.to_string(),
+            format!("expected 'true' or 'false', got '{}'", other),
+        )),
     }
 }
 
@@ -43,9 +350,18 @@ impl PaymentConfig {
 pub struct AppConfig {
     server_addr: String,
     database_url: String,
+    migrate_on_startup: bool,
+    admin_totp_required: bool,
     site_name: String,
+    /// Origin that links handed to people outside the app are built on.
+    base_url: String,
     email: EmailConfig,
     payment: PaymentConfig,
+    business: BusinessConfig,
+    passkey: PasskeyConfig,
+    storage: StorageConfig,
+    jobs: JobsConfig,
+    rate_limit: RateLimitConfig,
 }
 
 impl AppConfig {
@@ -53,21 +369,42 @@ impl AppConfig {
         let database_url = dotenvy::var("DATABASE_URL")
             .map_err(|_| ConfigError::MissingVar("DATABASE_URL".to_string()))?;
 
+        let migrate_on_startup = parse_bool_var("MIGRATE_ON_STARTUP")?;
+        let admin_totp_required = parse_bool_var("ADMIN_TOTP_REQUIRED")?;
+
         let server_addr = dotenvy::var("SERVER_ADDR")
             .map_err(|_| ConfigError::MissingVar("SERVER_ADDR".to_string()))?;
 
         let site_name = dotenvy::var("SITE_NAME")
             .map_err(|_| ConfigError::MissingVar("SITE_NAME".to_string()))?;
 
+        let base_url = dotenvy::var("BASE_URL")
+            .map_err(|_| ConfigError::MissingVar("BASE_URL".to_string()))?
+            .trim_end_matches('/')
+            .to_string();
+
         let email = EmailConfig::from_env()?;
         let payment = PaymentConfig::from_env()?;
+        let business = BusinessConfig::from_env()?;
+        let passkey = PasskeyConfig::from_env(&site_name)?;
+        let storage = StorageConfig::from_env()?;
+        let jobs = JobsConfig::from_env()?;
+        let rate_limit = RateLimitConfig::from_env()?;
 
         Ok(Self {
             server_addr,
             database_url,
+            migrate_on_startup,
+            admin_totp_required,
             site_name,
+            base_url,
             email,
             payment,
+            business,
+            passkey,
+            storage,
+            jobs,
+            rate_limit,
         })
     }
 
@@ -79,10 +416,22 @@ impl AppConfig {
         &self.database_url
     }
 
+    pub fn migrate_on_startup(&self) -> bool {
+        self.migrate_on_startup
+    }
+
+    pub fn admin_totp_required(&self) -> bool {
+        self.admin_totp_required
+    }
+
     pub fn site_name(&self) -> &str {
         &self.site_name
     }
 
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     pub fn email(&self) -> &EmailConfig {
         &self.email
     }
@@ -90,15 +439,48 @@ impl AppConfig {
     pub fn payment(&self) -> &PaymentConfig {
         &self.payment
     }
+
+    pub fn business(&self) -> &BusinessConfig {
+        &self.business
+    }
+
+    pub fn passkey(&self) -> &PasskeyConfig {
+        &self.passkey
+    }
+
+    pub fn storage(&self) -> &StorageConfig {
+        &self.storage
+    }
+
+    pub fn jobs(&self) -> &JobsConfig {
+        &self.jobs
+    }
+
+    pub fn rate_limit(&self) -> &RateLimitConfig {
+        &self.rate_limit
+    }
 }
 
 #[derive(Clone, FromRef)]
 pub struct AppState {
     config: AppConfig,
+    payment_gateway: SharedPaymentGateway,
+    webauthn: SharedWebauthn,
+    rate_limiter: SharedRateLimiter,
+    blob_store: SharedBlobStore,
 }
 
 impl AppState {
-    pub fn new(config: AppConfig) -> Self {
-        Self { config }
+    pub fn new(config: AppConfig, blob_store: SharedBlobStore) -> Self {
+        let payment_gateway = payment::create_gateway(config.payment());
+        let webauthn = passkey::create_webauthn(config.passkey());
+        let rate_limiter = Arc::new(RateLimiter::new(config.rate_limit()));
+        Self {
+            config,
+            payment_gateway,
+            webauthn,
+            rate_limiter,
+            blob_store,
+        }
     }
 }
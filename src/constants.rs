@@ -1,6 +1,9 @@
 pub mod auth {
     pub const MAGIC_LINK_EXPIRY_MINUTES: i64 = 15;
     pub const SESSION_EXPIRY_DAYS: i64 = 1;
+    /// Wrong second-factor codes in a row before the credential locks.
+    pub const MAX_SECOND_FACTOR_FAILURES: i64 = 5;
+    pub const SECOND_FACTOR_LOCKOUT_MINUTES: i64 = 15;
 }
 
 pub mod validation {
@@ -11,12 +14,16 @@ pub mod validation {
         Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$")
             .expect("Email regex pattern is invalid")
     });
+
+    pub const PASSKEY_NAME_MAX_LENGTH: u64 = 64;
 }
 
 pub mod cdn {
     pub const TAILWIND_CSS_URL: &str = "https://cdn.jsdelivr.net/npm/@tailwindcss/browser@4";
     pub const HTMX_URL: &str = "https://cdn.jsdelivr.net/npm/htmx.org@2.0.7/dist/htmx.min.js";
     pub const HTMX_INTEGRITY: &str = "sha384-ZBXiYtYQ6hJ2Y0ZNoYuI+Nq5MqWBr+chMrS/RkXpNzQCApHEhOt2aY8EJgqwHLkJ";
+    pub const HTMX_SSE_URL: &str = "https://cdn.jsdelivr.net/npm/htmx-ext-sse@2.2.2/sse.js";
+    pub const HTMX_SSE_INTEGRITY: &str = "sha384-Y4gc0CK6Kg+hmulDc6rZPJu0tqvk7EWlih0Oh+2OkAi1ZDlCbBDCQEE2uVk472Ky";
     pub const HYPERSCRIPT_URL: &str = "https://unpkg.com/hyperscript.org@0.9.14";
     pub const TOSS_PAYMENTS_SDK_URL: &str = "https://js.tosspayments.com/v1/payment";
 }
@@ -35,39 +42,177 @@ pub mod messages {
     pub const CONTACT_SENT: &str = "Thank you for your message! We'll get back to you soon.";
     pub const PAYMENT_SUCCESS: &str = "Payment successful! Your order is complete.";
     pub const PAYMENT_FAILED: &str = "Payment failed. Please try again.";
+    pub const PAYMENT_UNCONFIRMED: &str =
+        "We couldn't confirm your payment yet. If you were charged, this order updates as soon as the payment provider reports it.";
     pub const SIGN_IN_REQUIRED: &str = "Please sign in to continue";
     pub const ORDER_ALREADY_PROCESSED: &str = "Order already processed";
     pub const ADMIN_ROLE_GRANTED: &str = "Admin role granted";
     pub const ADMIN_ROLE_REVOKED: &str = "Admin role revoked";
+    pub const REFUND_ISSUED: &str = "Refund issued";
+    pub const ANALYSIS_JOB_RETRIED: &str = "Analysis job queued for retry";
+    pub const SHARE_LINK_CREATED: &str = "Share link created. Anyone with the link can view these results.";
+    pub const SHARE_LINK_REVOKED: &str = "Share link revoked";
+    pub const PASSKEY_ADDED: &str = "Passkey added. You can now use it to sign in.";
+    pub const PASSKEY_REGISTRATION_FAILED: &str = "Could not add the passkey. Please try again.";
+    pub const PASSKEY_SIGN_IN_FAILED: &str = "Passkey sign-in failed. Try again or use a magic link.";
+    pub const TWO_FACTOR_SETUP_REQUIRED: &str = "Set up two-factor authentication to access the admin area.";
+    pub const TWO_FACTOR_ALREADY_ENABLED: &str = "Two-factor authentication is already enabled.";
+    pub const TWO_FACTOR_DISABLED: &str = "Two-factor authentication disabled.";
+    pub const TWO_FACTOR_CODE_INVALID: &str = "That code is not valid. Try again.";
+    pub const TWO_FACTOR_LOCKED: &str =
+        "Too many incorrect codes. Two-factor sign-in is locked for 15 minutes; sign in again after that.";
+    pub const PRICING_RULES_PUBLISHED: &str = "New pricing published. It applies to orders created from now on.";
+    pub const COUPON_APPLIED: &str = "Coupon applied";
+    pub const COUPON_REMOVED: &str = "Coupon removed";
+    pub const COUPON_CREATED: &str = "Coupon created";
+    pub const COUPON_DEACTIVATED: &str = "Coupon deactivated. Orders it was already applied to keep their discount.";
+    pub const TOP_UP_SUCCESS: &str = "Top-up complete. Your credit is ready to use.";
+    pub const TOP_UP_UNCONFIRMED: &str =
+        "We couldn't confirm your top-up yet. If you were charged, your balance updates as soon as the payment provider reports it.";
+    pub const PAID_WITH_CREDITS: &str = "Paid from your credit balance. Your analysis is starting.";
+    pub const CREDITS_ADJUSTED: &str = "Credit balance adjusted";
+    pub const RATE_LIMITED: &str = "Too many attempts. Please wait a few minutes and try again.";
 }
 
 pub mod errors {
     pub const USER_NOT_FOUND: &str = "User not found";
     pub const ORDER_NOT_FOUND: &str = "Order not found";
     pub const ORDER_NOT_FOUND_OR_PROCESSED: &str = "Order not found or already processed";
+    pub const PAYMENT_STATUS_CONFLICT: &str = "This order's payment status has already changed";
     pub const TODO_NOT_FOUND: &str = "Todo not found";
     pub const PAYMENT_NOT_COMPLETED: &str = "Payment not completed";
     pub const NOT_YOUR_ORDER: &str = "Not your order";
     pub const NO_FILE_PROVIDED: &str = "No file provided";
     pub const FORBIDDEN: &str = "You don't have permission to access this resource";
+    pub const CSRF_TOKEN_INVALID: &str = "This form has expired. Go back, reload the page and try again.";
     pub const CANNOT_REVOKE_OWN_ADMIN: &str = "Cannot revoke your own admin role";
     pub const USER_CREATION_FAILED: &str = "Failed to create user";
     pub const ORDER_CREATION_FAILED: &str = "Failed to create order";
+    pub const PREVIEW_CREATION_FAILED: &str = "Failed to count the order's text";
+    pub const ORDER_ITEM_NOT_FOUND: &str = "Order item not found";
+    pub const ORDER_ITEM_TEXT_NOT_FOUND: &str = "Order item text not found";
+    pub const ANALYSIS_JOB_CREATION_FAILED: &str = "Failed to queue analysis";
+    pub const REPORT_FORMAT_NOT_FOUND: &str = "Unknown report format";
+    pub const REPORT_PDF_UNSUPPORTED_TEXT: &str =
+        "This report has characters the PDF can't show, such as Japanese or Chinese. Download it as CSV or JSON instead.";
+    pub const REPORT_NOT_READY: &str = "Your analysis is still running. Download the report once it's complete.";
+    pub const ANALYSIS_JOB_NOT_RETRYABLE: &str = "Analysis job not found or already running";
+    pub const ANALYSIS_JOB_LEASE_LOST: &str = "Analysis job lease ran out before the worker reported back";
+    pub const SHARE_LINK_NOT_FOUND: &str = "This link is invalid, has expired or was revoked";
+    pub const SHARE_LINK_NOT_REVOCABLE: &str = "Share link not found or already revoked";
+    pub const SHARE_LINK_CREATION_FAILED: &str = "Failed to create share link";
+    pub const SHARE_LINK_EXPIRY_INVALID: &str = "Choose one of the offered link lifetimes";
+    pub const PRICING_RULES_NOT_FOUND: &str = "No pricing rules have been published";
+    pub const PRICING_RULES_CREATION_FAILED: &str = "Failed to publish pricing rules";
+    pub const COUPON_NOT_FOUND: &str = "That code is not valid";
+    pub const COUPON_NOT_STARTED: &str = "That code is not active yet";
+    pub const COUPON_EXPIRED: &str = "That code has expired";
+    pub const COUPON_USED_UP: &str = "That code has been fully redeemed";
+    pub const COUPON_NO_LONGER_AVAILABLE: &str = "That code is no longer available";
+    pub const COUPON_ALREADY_USED: &str = "You have already used that code";
+    pub const COUPON_CODE_INVALID: &str = "Codes use letters, digits, dashes and underscores, up to 32 characters";
+    pub const COUPON_CODE_TAKEN: &str = "A coupon with that code already exists";
+    pub const COUPON_VALUE_INVALID: &str = "Percentages run from 1 to 100; amounts must be whole won above 0";
+    pub const COUPON_DATES_INVALID: &str = "Dates must be valid, and the last day cannot come before the first";
+    pub const COUPON_LIMIT_INVALID: &str = "Usage limits must be whole numbers above 0, or empty for no limit";
+    pub const COUPON_CREATION_FAILED: &str = "Failed to create coupon";
+    pub const TOP_UP_NOT_FOUND: &str = "Top-up not found";
+    pub const TOP_UP_AMOUNT_INVALID: &str = "Choose one of the offered top-up amounts";
+    pub const TOP_UP_CREATION_FAILED: &str = "Failed to start top-up";
+    pub const INSUFFICIENT_CREDITS: &str = "Your credit balance doesn't cover this order. Top up or pay by card.";
+    pub const CREDIT_PAYMENT_FAILED: &str = "Could not pay from credits. Check your balance and try again.";
+    pub const CREDIT_ADJUSTMENT_INVALID: &str = "Enter a non-zero whole amount and a note of up to 200 characters";
+    pub const CREDIT_ADJUSTMENT_NEGATIVE: &str = "That would take the balance below zero";
     pub const AUTHENTICATION_REQUIRED: &str = "Authentication required";
+    pub const REFUND_NOT_ALLOWED: &str = "Only paid orders with a payment key can be refunded";
+    pub const REFUND_NOT_RECORDED: &str =
+        "The provider issued the refund, but it could not be recorded on the order. Check the timeline and reconcile before refunding again.";
+    pub const PASSKEY_NOT_FOUND: &str = "Passkey not found";
+    pub const PASSKEY_CREATION_FAILED: &str = "Failed to save passkey";
+    pub const REFUND_AMOUNT_INVALID: &str = "Refund amount must be a whole number between 1 and the refundable balance";
 }
 
 pub mod pricing {
-    pub const PRICE_PER_CHARACTER: i32 = 1;
-    pub const MINIMUM_ORDER_AMOUNT: i32 = 100;
+    /// Ceiling for any rate, minimum or surcharge entered in the pricing editor.
+    pub const MAX_AMOUNT: i32 = 1_000_000;
+    /// Past versions listed under the editor.
+    pub const HISTORY_LIMIT: i64 = 20;
+}
+
+pub mod coupons {
+    /// Card payments below this are refused, so no discount takes an order under it.
+    pub const MIN_PAYABLE_AMOUNT: i32 = 100;
+    pub const MAX_CODE_LENGTH: usize = 32;
+}
+
+pub mod credits {
+    /// Top-up amounts offered on the dashboard.
+    pub const TOP_UP_CHOICES: &[i32] = &[10_000, 30_000, 50_000, 100_000];
+    pub const TOP_UP_ORDER_NAME: &str = "Credit Top-up";
+    /// Ledger entries shown on the dashboard and the admin user page.
+    pub const HISTORY_LIMIT: i64 = 20;
+    pub const MAX_NOTE_LENGTH: usize = 200;
 }
 
 pub mod payment {
+    pub const TOSS_API_PAYMENTS_URL: &str = "https://api.tosspayments.com/v1/payments";
     pub const TOSS_API_CONFIRM_URL: &str = "https://api.tosspayments.com/v1/payments/confirm";
+    pub const TOSS_API_TIMEOUT_SECS: u64 = 30;
     pub const ORDER_NAME_PREFIX: &str = "Text Analysis";
+    /// Cancel reason sent when a confirmed charge arrives for an order that can no longer be paid.
+    pub const CHARGE_REVERSED_REASON: &str = "Order was no longer payable when the payment was confirmed";
+    /// Timeline note for a pending order the expiry job cancelled.
+    pub const ORDER_EXPIRED_NOTE: &str = "Expired unpaid";
+    /// Timeline note for a refund the provider made that the order doesn't reflect yet.
+    pub const REFUND_UNRECORDED_NOTE: &str = "Refunded at the provider but not recorded on the order; reconcile by hand";
+
+    pub const PROVIDER_TOSS: &str = "toss";
+    pub const WEBHOOK_SIGNATURE_HEADER: &str = "tosspayments-webhook-signature";
+    pub const WEBHOOK_TRANSMISSION_TIME_HEADER: &str = "tosspayments-webhook-transmission-time";
+    pub const WEBHOOK_SIGNATURE_VERSION_PREFIX: &str = "v1:";
+    pub const WEBHOOK_EVENT_PAYMENT_STATUS_CHANGED: &str = "PAYMENT_STATUS_CHANGED";
+    /// Recorded as the event type of deliveries whose payload can't be parsed.
+    pub const WEBHOOK_EVENT_UNKNOWN: &str = "UNKNOWN";
+    /// How much of a webhook payload is stored with its `payment_event`.
+    pub const WEBHOOK_STORED_PAYLOAD_CHARS: usize = 16 * 1024;
+
+    pub const MOCK_PAYMENT_KEY_PREFIX: &str = "mock_";
+    pub const MOCK_DECLINE_CODE: &str = "REJECT_CARD_PAYMENT";
+    pub const MOCK_DECLINE_MESSAGE: &str = "Card declined by mock gateway";
+    pub const MOCK_NOT_FOUND_CODE: &str = "NOT_FOUND_PAYMENT";
+    pub const MOCK_INVALID_CANCEL_CODE: &str = "INVALID_CANCEL_AMOUNT";
+    pub const MOCK_AMOUNT_MISMATCH_DELTA: i32 = 1;
 }
 
 pub mod file_upload {
     pub const MAX_FILE_SIZE: usize = 10 * 1024 * 1024; // 10MB
+    pub const MAX_BATCH_SIZE: usize = 50 * 1024 * 1024; // 50MB
+    pub const MAX_FILES_PER_ORDER: usize = 20;
+    /// Body limit on the upload route: the largest batch plus room for the
+    /// multipart framing and the CSRF field.
+    pub const MAX_REQUEST_BYTES: usize = MAX_BATCH_SIZE + 64 * 1024;
+    /// Cap on decompressed document XML; DOCX compresses well, zip bombs better.
+    pub const MAX_EXTRACTED_BYTES: usize = 50 * 1024 * 1024;
+    /// Cap on what a PDF's compressed streams may decode to in all, images
+    /// included, before any text is extracted.
+    pub const MAX_PDF_DECODED_BYTES: usize = 4 * MAX_EXTRACTED_BYTES;
+    /// Cap on extracted text across all files of one order.
+    pub const MAX_BATCH_EXTRACTED_CHARS: usize = 50_000_000;
+    pub const ACCEPTED_EXTENSIONS: &str = ".txt,.md,.markdown,.html,.htm,.docx,.pdf";
+}
+
+pub mod csrf {
+    /// URL-encoded forms carry the token in the body, so the middleware buffers
+    /// them. Matches axum's default body limit, which the handlers apply anyway.
+    pub const MAX_BUFFERED_BODY_BYTES: usize = 2 * 1024 * 1024;
+    /// Multipart forms put the token first; only this much of an upload is read
+    /// looking for it.
+    pub const MAX_MULTIPART_PREFIX_BYTES: usize = 16 * 1024;
+}
+
+pub mod rate_limit {
+    /// Matches axum's default body limit, which the form handlers would otherwise apply.
+    pub const MAX_FORM_BODY_BYTES: usize = 2 * 1024 * 1024;
 }
 
 pub mod logging {
@@ -78,6 +223,38 @@ pub mod admin {
     pub const ITEMS_PER_PAGE: i64 = 20;
 }
 
+pub mod analysis {
+    pub const TOP_TERMS_LIMIT: usize = 10;
+    /// Keywords shown free on the quote page; the rest wait for payment.
+    pub const PREVIEW_TOP_WORDS: usize = 3;
+    /// An n-gram must repeat to be worth reporting.
+    pub const MIN_NGRAM_COUNT: usize = 2;
+    /// Average adult silent reading speed for non-fiction.
+    pub const READING_WORDS_PER_MINUTE: f64 = 238.0;
+}
+
+pub mod analysis_jobs {
+    pub const MAX_ATTEMPTS: i32 = 5;
+    /// The first retry waits this long; each one after that waits twice as long.
+    pub const RETRY_BASE_DELAY_SECS: i64 = 30;
+    pub const RETRY_MAX_DELAY_SECS: i64 = 60 * 60;
+    /// A claim lasts this long and is renewed after every file; a job still
+    /// "running" past it belongs to a worker that died and is claimed again.
+    pub const LEASE_SECS: i64 = 10 * 60;
+    /// Jobs one tick claims at most; the rest wait for the next tick, so
+    /// shutdown never waits on more than this.
+    pub const MAX_CLAIMS_PER_TICK: usize = 20;
+    /// How often the confirmation page's event stream re-reads the job.
+    pub const PROGRESS_POLL_INTERVAL_MILLIS: u64 = 500;
+}
+
+pub mod share_links {
+    /// Lifetimes offered when creating a link; it can also never expire.
+    pub const EXPIRY_CHOICES_DAYS: &[i64] = &[1, 7, 30];
+    /// Longer user agents are cut short before they go into the access log.
+    pub const MAX_USER_AGENT_LENGTH: usize = 512;
+}
+
 pub mod dashboard {
     pub const RECENT_ORDERS_LIMIT: i64 = 10;
 }
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use surrealdb::sql::Datetime;
+
+use crate::{
+    constants::errors,
+    data::errors::DataError,
+    db::DB,
+    models::{analysis_job::AnalysisJob, AnalysisJobId, OrderId},
+};
+
+/// Returns the order's job, creating a queued one if it has none yet.
+/// Paying for an order already queues its job; this covers anything that slipped past.
+pub async fn ensure_job_for_order(order_id: &OrderId, items_total: i32) -> Result<AnalysisJob, DataError> {
+    let mut result = DB
+        .query(
+            "BEGIN TRANSACTION;
+             LET $existing = (SELECT * FROM analysis_job WHERE order = $order LIMIT 1)[0];
+             RETURN IF $existing != NONE { $existing } ELSE {
+                 (CREATE ONLY analysis_job CONTENT { order: $order, items_total: $items_total })
+             };
+             COMMIT TRANSACTION;",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .bind(("items_total", items_total))
+        .await?;
+
+    let last = result.num_statements() - 1;
+    let job: Option<AnalysisJob> = result.take(last)?;
+    job.ok_or(DataError::CreationFailed(errors::ANALYSIS_JOB_CREATION_FAILED))
+}
+
+/// Claims the longest-waiting runnable job: a queued job that is due, or a
+/// running job whose worker let its lease lapse. The claim holds until
+/// `locked_until`, under a `lease_owner` token no earlier claim had.
+pub async fn claim_next_job(locked_until: DateTime<Utc>) -> Result<Option<AnalysisJob>, DataError> {
+    let mut result = DB
+        .query(
+            "BEGIN TRANSACTION;
+             LET $candidate = (
+                 SELECT id, run_at FROM analysis_job
+                 WHERE (status = 'queued' AND run_at <= time::now())
+                    OR (status = 'running' AND locked_until < time::now())
+                 ORDER BY run_at
+                 LIMIT 1
+             )[0].id;
+             RETURN IF $candidate != NONE {
+                 (UPDATE ONLY $candidate
+                  SET status = 'running', attempts += 1, locked_until = $locked_until, lease_owner = <string> rand::uuid()
+                  RETURN AFTER)
+             } ELSE { NONE };
+             COMMIT TRANSACTION;",
+        )
+        .bind(("locked_until", Datetime::from(locked_until)))
+        .await?;
+
+    let last = result.num_statements() - 1;
+    let job: Option<AnalysisJob> = result.take(last)?;
+    Ok(job)
+}
+
+/// Only a worker still holding the claim `job` was handed may change it; a
+/// claim that lapsed may already belong to another worker.
+const LEASE_HELD: &str = "lease_owner = $lease_owner AND locked_until > time::now()";
+
+/// Fails with `ANALYSIS_JOB_LEASE_LOST` when the update matched nothing.
+fn require_lease(mut result: surrealdb::Response) -> Result<(), DataError> {
+    let updated: Vec<AnalysisJobId> = result.take(0)?;
+    if updated.is_empty() {
+        return Err(DataError::NotFound(errors::ANALYSIS_JOB_LEASE_LOST));
+    }
+    Ok(())
+}
+
+/// Also renews the worker's claim until `locked_until`.
+pub async fn record_progress(job: &AnalysisJob, items_done: i32, locked_until: DateTime<Utc>) -> Result<(), DataError> {
+    let result = DB
+        .query(format!(
+            "UPDATE $job SET items_done = $items_done, locked_until = $locked_until WHERE {LEASE_HELD} RETURN VALUE id"
+        ))
+        .bind(("job", job.id.clone().into_record_id()))
+        .bind(("lease_owner", job.lease_owner.clone()))
+        .bind(("items_done", items_done))
+        .bind(("locked_until", Datetime::from(locked_until)))
+        .await?;
+
+    require_lease(result)
+}
+
+pub async fn complete_job(job: &AnalysisJob) -> Result<(), DataError> {
+    let result = DB
+        .query(format!(
+            "UPDATE $job SET status = 'completed', items_done = items_total, locked_until = NONE, lease_owner = NONE,
+                 completed_at = time::now()
+             WHERE {LEASE_HELD}
+             RETURN VALUE id"
+        ))
+        .bind(("job", job.id.clone().into_record_id()))
+        .bind(("lease_owner", job.lease_owner.clone()))
+        .await?;
+
+    require_lease(result)
+}
+
+/// Queues the job again at `retry_at`, or marks it failed when there is no retry left.
+pub async fn record_failure(job: &AnalysisJob, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<(), DataError> {
+    let changes = match retry_at {
+        Some(_) => "status = 'queued', run_at = $retry_at",
+        None => "status = 'failed'",
+    };
+
+    let result = DB
+        .query(format!(
+            "UPDATE $job SET {changes}, locked_until = NONE, lease_owner = NONE, last_error = $error
+             WHERE {LEASE_HELD}
+             RETURN VALUE id"
+        ))
+        .bind(("job", job.id.clone().into_record_id()))
+        .bind(("lease_owner", job.lease_owner.clone()))
+        .bind(("retry_at", retry_at.map(Datetime::from)))
+        .bind(("error", error.to_string()))
+        .await?;
+
+    require_lease(result)
+}
+
+/// Admin retry: makes a failed or backed-off job due now with a fresh set of attempts.
+pub async fn retry_job(job_id: &AnalysisJobId) -> Result<AnalysisJob, DataError> {
+    let mut result = DB
+        .query(
+            "UPDATE $job SET status = 'queued', attempts = 0, run_at = time::now(), locked_until = NONE, lease_owner = NONE
+             WHERE status IN ['queued', 'failed']
+             RETURN AFTER",
+        )
+        .bind(("job", job_id.clone().into_record_id()))
+        .await?;
+
+    let job: Option<AnalysisJob> = result.take(0)?;
+    job.ok_or(DataError::NotFound(errors::ANALYSIS_JOB_NOT_RETRYABLE))
+}
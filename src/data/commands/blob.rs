@@ -0,0 +1,36 @@
+use crate::{data::errors::DataError, db::DB, storage::BlobKey};
+
+fn to_strings(keys: &[BlobKey]) -> Vec<String> {
+    keys.iter().map(ToString::to_string).collect()
+}
+
+/// Takes `keys` off the collection list. Call before storing content again, so
+/// housekeeping can't delete a blob a new order is about to point at.
+pub async fn keep_blobs(keys: &[BlobKey]) -> Result<(), DataError> {
+    DB.query("DELETE blob_gc WHERE $keys CONTAINS key")
+        .bind(("keys", to_strings(keys)))
+        .await?
+        .check()?;
+
+    Ok(())
+}
+
+/// Drops `key` from the collection list once its blob is gone or back in use.
+pub async fn forget_blob(key: &BlobKey) -> Result<(), DataError> {
+    DB.query("DELETE type::thing('blob_gc', $key)")
+        .bind(("key", key.to_string()))
+        .await?
+        .check()?;
+
+    Ok(())
+}
+
+/// Counts a failed delete; the key stays listed for the next run.
+pub async fn record_blob_delete_failure(key: &BlobKey) -> Result<(), DataError> {
+    DB.query("UPDATE type::thing('blob_gc', $key) SET failed_attempts += 1")
+        .bind(("key", key.to_string()))
+        .await?
+        .check()?;
+
+    Ok(())
+}
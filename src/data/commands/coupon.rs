@@ -0,0 +1,112 @@
+use surrealdb::sql::Datetime;
+
+use crate::{
+    constants::errors,
+    data::errors::DataError,
+    db::DB,
+    models::{coupon::{Coupon, NewCoupon}, order::Order, CouponId, OrderId, UserId},
+};
+
+/// The unique index on `code` rejects a duplicate that slips past the handler's check.
+pub async fn create_coupon(coupon: NewCoupon, created_by: &UserId) -> Result<Coupon, DataError> {
+    let mut result = DB
+        .query(
+            "CREATE ONLY coupon CONTENT {
+                 code: $coupon.code,
+                 kind: $coupon.kind,
+                 value: $coupon.value,
+                 starts_at: $starts_at,
+                 ends_at: $ends_at,
+                 max_redemptions: $coupon.max_redemptions,
+                 max_redemptions_per_user: $coupon.max_redemptions_per_user,
+                 min_order_amount: $coupon.min_order_amount,
+                 created_by: $user,
+             }",
+        )
+        .bind(("starts_at", coupon.starts_at.map(Datetime::from)))
+        .bind(("ends_at", coupon.ends_at.map(Datetime::from)))
+        .bind(("coupon", coupon))
+        .bind(("user", created_by.clone().into_record_id()))
+        .await?;
+
+    let coupon: Option<Coupon> = result.take(0)?;
+    coupon.ok_or(DataError::CreationFailed(errors::COUPON_CREATION_FAILED))
+}
+
+pub async fn deactivate_coupon(coupon_id: &CouponId) -> Result<(), DataError> {
+    let mut result = DB
+        .query("UPDATE $coupon SET active = false RETURN AFTER")
+        .bind(("coupon", coupon_id.clone().into_record_id()))
+        .await?;
+
+    let coupon: Option<Coupon> = result.take(0)?;
+    coupon.map(|_| ()).ok_or(DataError::NotFound(errors::COUPON_NOT_FOUND))
+}
+
+/// Puts the coupon on a pending order, replacing any earlier one. The usage
+/// limits and the coupon's own state are checked again in the same statement,
+/// so two orders racing for the last use can't both get it and a coupon
+/// deactivated or expired since the handler read it is not applied. Returns
+/// `None` when any of them no longer holds.
+pub async fn apply_coupon_to_order(
+    order_id: &OrderId,
+    user_id: &UserId,
+    coupon: &Coupon,
+    discount_amount: i32,
+) -> Result<Option<Order>, DataError> {
+    let mut result = DB
+        .query(
+            "BEGIN TRANSACTION;
+             LET $uses = SELECT user FROM order
+                 WHERE coupon = $coupon AND id != $order AND payment_status IN ['pending', 'paid', 'partially_refunded'];
+             LET $updated = (
+                 UPDATE $order SET
+                     price_amount = price_amount + discount_amount - $discount,
+                     discount_amount = $discount,
+                     coupon = $coupon,
+                     coupon_code = $code
+                 WHERE user = $user
+                   AND payment_status = 'pending'
+                   AND $coupon.active = true
+                   AND ($coupon.starts_at = NONE OR $coupon.starts_at <= time::now())
+                   AND ($coupon.ends_at = NONE OR $coupon.ends_at > time::now())
+                   AND ($max_total = NONE OR array::len($uses) < $max_total)
+                   AND ($max_per_user = NONE OR array::len($uses[WHERE user = $user]) < $max_per_user)
+                 RETURN AFTER
+             );
+             RETURN $updated[0];
+             COMMIT TRANSACTION;",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .bind(("user", user_id.clone().into_record_id()))
+        .bind(("coupon", coupon.id.clone().into_record_id()))
+        .bind(("code", coupon.code.clone()))
+        .bind(("discount", discount_amount))
+        .bind(("max_total", coupon.max_redemptions))
+        .bind(("max_per_user", coupon.max_redemptions_per_user))
+        .await?;
+
+    let last = result.num_statements() - 1;
+    let order: Option<Order> = result.take(last)?;
+    Ok(order)
+}
+
+/// Restores the undiscounted price of a pending order.
+pub async fn remove_coupon_from_order(order_id: &OrderId, user_id: &UserId) -> Result<(), DataError> {
+    let mut result = DB
+        .query(
+            "UPDATE $order SET
+                 price_amount = price_amount + discount_amount,
+                 discount_amount = 0,
+                 coupon = NONE,
+                 coupon_code = NONE
+             WHERE user = $user AND payment_status = 'pending' AND coupon != NONE
+             RETURN AFTER",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .bind(("user", user_id.clone().into_record_id()))
+        .await?;
+
+    let order: Option<Order> = result.take(0)?;
+    order.map(|_| ()).ok_or(DataError::NotFound(errors::ORDER_NOT_FOUND_OR_PROCESSED))
+}
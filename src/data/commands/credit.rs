@@ -0,0 +1,155 @@
+use chrono::Utc;
+use surrealdb::sql::Datetime;
+
+use crate::{
+    constants::errors,
+    data::{
+        commands::order::{finish_transition, OrderEventData, OrderEventParams, QUEUE_ANALYSIS_JOB, RECORD_ORDER_EVENT},
+        errors::DataError,
+    },
+    db::DB,
+    models::{credit::CreditTopUp, order::PaymentStatus, CreditTopUpId, OrderId, OrderNumber, UserId},
+};
+
+pub async fn create_top_up(user_id: &UserId, amount: i32, order_number: &OrderNumber) -> Result<CreditTopUp, DataError> {
+    let mut result = DB
+        .query("CREATE ONLY credit_top_up CONTENT { user: $user, amount: $amount, order_number: $order_number }")
+        .bind(("user", user_id.clone().into_record_id()))
+        .bind(("amount", amount))
+        .bind(("order_number", order_number.to_string()))
+        .await?;
+
+    let top_up: Option<CreditTopUp> = result.take(0)?;
+    top_up.ok_or(DataError::CreationFailed(errors::TOP_UP_CREATION_FAILED))
+}
+
+/// Moves a pending top-up to `payment_status`. A paid top-up credits the
+/// balance and writes its ledger entry in the same transaction, so it can only
+/// be credited once whether the redirect or the webhook gets here first.
+pub async fn update_top_up_payment(
+    top_up_id: &CreditTopUpId,
+    payment_key: &str,
+    payment_status: PaymentStatus,
+) -> Result<CreditTopUp, DataError> {
+    let paid_at: Option<Datetime> = if payment_status == PaymentStatus::Paid {
+        Some(Datetime::from(Utc::now()))
+    } else {
+        None
+    };
+
+    let mut result = DB
+        .query(
+            "BEGIN TRANSACTION;
+             LET $updated = (
+                 UPDATE $top_up SET payment_key = $payment_key, payment_status = $payment_status, paid_at = $paid_at
+                 WHERE payment_status = 'pending'
+                 RETURN AFTER
+             );
+             IF $payment_status = 'paid' AND array::len($updated) > 0 {
+                 LET $credited = (UPDATE ONLY $updated[0].user SET credit_balance += $updated[0].amount RETURN AFTER);
+                 CREATE credit_transaction CONTENT {
+                     user: $updated[0].user,
+                     amount: $updated[0].amount,
+                     balance_after: $credited.credit_balance,
+                     kind: 'top_up',
+                     top_up: $top_up,
+                 };
+             };
+             RETURN $updated[0];
+             COMMIT TRANSACTION;",
+        )
+        .bind(("top_up", top_up_id.clone().into_record_id()))
+        .bind(("payment_key", payment_key.to_string()))
+        .bind(("payment_status", payment_status.as_str().to_string()))
+        .bind(("paid_at", paid_at))
+        .await?;
+
+    let last = result.num_statements() - 1;
+    let top_up: Option<CreditTopUp> = result.take(last)?;
+    top_up.ok_or(DataError::NotFound(errors::ORDER_NOT_FOUND_OR_PROCESSED))
+}
+
+/// Debits the order's price and marks it paid, queuing its analysis and
+/// recording the event like a card payment would. Returns `false`, changing
+/// nothing, when the order is no longer pending or the balance doesn't cover it.
+pub async fn pay_order_with_credits(order_id: &OrderId, user_id: &UserId) -> Result<bool, DataError> {
+    let event = OrderEventParams::customer(user_id).with_note("Paid from credit balance");
+
+    // Like `transition_order`, but the balance is debited first, guarded by its
+    // own update, and the order only moves if the debit went through. Payments
+    // for two orders can't both spend the same balance.
+    let result = DB
+        .query(format!(
+            "BEGIN TRANSACTION;
+             LET $previous = $order.payment_status;
+             LET $price = $order.price_amount;
+             LET $debited = (
+                 UPDATE $user SET credit_balance -= $price
+                 WHERE credit_balance >= $price AND $order.user = $user AND $previous IN $from
+                 RETURN AFTER
+             );
+             LET $updated = (
+                 UPDATE $order SET payment_status = $to, paid_with_credits = true, paid_at = time::now()
+                 WHERE payment_status IN $from AND user = $user AND array::len($debited) > 0
+                 RETURN AFTER
+             );
+             IF array::len($updated) > 0 {{
+                 {RECORD_ORDER_EVENT}
+                 CREATE credit_transaction CONTENT {{
+                     user: $user,
+                     amount: -$price,
+                     balance_after: $debited[0].credit_balance,
+                     kind: 'order_payment',
+                     order: $order,
+                 }};
+                 {QUEUE_ANALYSIS_JOB}
+             }};
+             RETURN {{ order: $updated[0], previous: $previous }};
+             COMMIT TRANSACTION;"
+        ))
+        .bind(("order", order_id.clone().into_record_id()))
+        .bind(("user", user_id.clone().into_record_id()))
+        .bind(("to", PaymentStatus::Paid))
+        .bind(("from", PaymentStatus::sources_of(PaymentStatus::Paid)))
+        .bind(("event", OrderEventData::from(event)))
+        .await?;
+
+    match finish_transition(result, PaymentStatus::Paid) {
+        Ok(_) => Ok(true),
+        Err(DataError::IllegalTransition(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// A manual change by an admin. Returns `false` when it would leave the
+/// balance negative.
+pub async fn adjust_credits(user_id: &UserId, amount: i32, note: &str, admin_user_id: &UserId) -> Result<bool, DataError> {
+    let mut result = DB
+        .query(
+            "BEGIN TRANSACTION;
+             LET $balance = (SELECT VALUE credit_balance FROM ONLY $user) ?? 0;
+             LET $apply = $balance + $amount >= 0;
+             IF $apply {
+                 LET $adjusted = (UPDATE ONLY $user SET credit_balance += $amount RETURN AFTER);
+                 CREATE credit_transaction CONTENT {
+                     user: $user,
+                     amount: $amount,
+                     balance_after: $adjusted.credit_balance,
+                     kind: 'adjustment',
+                     note: $note,
+                     created_by: $admin,
+                 };
+             };
+             RETURN $apply;
+             COMMIT TRANSACTION;",
+        )
+        .bind(("user", user_id.clone().into_record_id()))
+        .bind(("amount", amount))
+        .bind(("note", note.to_string()))
+        .bind(("admin", admin_user_id.clone().into_record_id()))
+        .await?;
+
+    let last = result.num_statements() - 1;
+    let applied: Option<bool> = result.take(last)?;
+    Ok(applied.unwrap_or_default())
+}
@@ -62,3 +62,13 @@ pub async fn verify_and_consume_magic_link(token: &str) -> Result<String, DataEr
 
     Ok(link.email)
 }
+
+/// Removes links that expired without being used. Returns how many were deleted.
+pub async fn delete_expired_magic_links() -> Result<usize, DataError> {
+    let mut result = DB
+        .query("DELETE magic_link WHERE expires_at < time::now() RETURN BEFORE")
+        .await?;
+
+    let deleted: Vec<MagicLinkRecord> = result.take(0)?;
+    Ok(deleted.len())
+}
@@ -1,5 +1,5 @@
-use chrono::Utc;
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use surrealdb::sql::Datetime;
 
 use crate::{
@@ -7,20 +7,174 @@ use crate::{
     data::errors::DataError,
     db::DB,
     models::{
-        order::{Order, PaymentStatus},
-        OrderId, OrderNumber, UserId,
+        analysis::{AnalysisPreview, AnalysisReport},
+        order::{IllegalTransition, Order, PaymentStatus},
+        order_event::ActorKind,
+        OrderId, OrderItemId, OrderNumber, PricingRuleSetId, UserId,
     },
+    storage::BlobKey,
 };
 
+/// Who is changing an order's payment status, and what they went on.
+pub struct OrderEventParams {
+    pub actor_kind: ActorKind,
+    pub actor: Option<UserId>,
+    pub payload: Option<String>,
+    pub note: Option<String>,
+}
+
+impl OrderEventParams {
+    fn new(actor_kind: ActorKind, actor: Option<UserId>) -> Self {
+        Self {
+            actor_kind,
+            actor,
+            payload: None,
+            note: None,
+        }
+    }
+
+    pub fn customer(user_id: &UserId) -> Self {
+        Self::new(ActorKind::Customer, Some(user_id.clone()))
+    }
+
+    pub fn admin(user_id: &UserId) -> Self {
+        Self::new(ActorKind::Admin, Some(user_id.clone()))
+    }
+
+    pub fn provider() -> Self {
+        Self::new(ActorKind::Provider, None)
+    }
+
+    pub fn system() -> Self {
+        Self::new(ActorKind::System, None)
+    }
+
+    pub fn with_payload(mut self, payload: impl Into<String>) -> Self {
+        self.payload = Some(payload.into());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+#[derive(Serialize)]
+pub(super) struct OrderEventData {
+    actor_kind: ActorKind,
+    actor: Option<surrealdb::RecordId>,
+    payload: Option<String>,
+    note: Option<String>,
+}
+
+impl From<OrderEventParams> for OrderEventData {
+    fn from(params: OrderEventParams) -> Self {
+        Self {
+            actor_kind: params.actor_kind,
+            actor: params.actor.map(UserId::into_record_id),
+            payload: params.payload,
+            note: params.note,
+        }
+    }
+}
+
+/// Appends the move from `$previous` to `$to`, made by `$event`, to `$order`'s timeline.
+pub(super) const RECORD_ORDER_EVENT: &str = "CREATE order_event CONTENT {
+                     order: $order,
+                     from_status: $previous,
+                     to_status: $to,
+                     actor_kind: $event.actor_kind,
+                     actor: $event.actor,
+                     actor_email: $event.actor.email,
+                     payload: $event.payload,
+                     note: $event.note,
+                 };";
+
+pub(super) const QUEUE_ANALYSIS_JOB: &str = "CREATE analysis_job CONTENT { order: $order, items_total: $updated[0].item_count };";
+
+/// Moves `order_id` to `to` — applying `changes`, extra `SET` assignments — if
+/// its status allows it and `guard`, extra `WHERE` conditions, holds, then
+/// records the event and runs `then`, all in one transaction. Finish with
+/// [`finish_transition`] once the caller's own variables are bound.
+pub(super) fn transition_order(
+    order_id: &OrderId,
+    to: PaymentStatus,
+    event: OrderEventParams,
+    changes: &str,
+    guard: &str,
+    then: &str,
+) -> surrealdb::method::Query<'static, surrealdb::engine::any::Any> {
+    DB.query(format!(
+        "BEGIN TRANSACTION;
+         LET $previous = $order.payment_status;
+         LET $updated = (
+             UPDATE $order SET payment_status = $to, {changes}
+             WHERE payment_status IN $from {guard}
+             RETURN AFTER
+         );
+         IF array::len($updated) > 0 {{
+             {RECORD_ORDER_EVENT}
+             {then}
+         }};
+         RETURN {{ order: $updated[0], previous: $previous }};
+         COMMIT TRANSACTION;"
+    ))
+    .bind(("order", order_id.clone().into_record_id()))
+    .bind(("to", to))
+    .bind(("from", PaymentStatus::sources_of(to)))
+    .bind(("event", OrderEventData::from(event)))
+}
+
+#[derive(Deserialize)]
+struct TransitionOutcome {
+    order: Option<Order>,
+    previous: Option<PaymentStatus>,
+}
+
+/// The moved order, or why it didn't move. A failed `guard` reads as an
+/// illegal transition from the order's current status.
+pub(super) fn finish_transition(mut result: surrealdb::Response, to: PaymentStatus) -> Result<Order, DataError> {
+    let last = result.num_statements() - 1;
+    let outcome: Option<TransitionOutcome> = result.take(last)?;
+
+    match outcome {
+        Some(TransitionOutcome { order: Some(order), .. }) => Ok(order),
+        Some(TransitionOutcome { previous: Some(from), .. }) => Err(IllegalTransition { from, to }.into()),
+        _ => Err(DataError::NotFound(errors::ORDER_NOT_FOUND)),
+    }
+}
+
 pub struct CreateOrderParams {
     pub user_id: UserId,
     pub user_email: String,
+    pub label: String,
+    pub price_amount: i32,
+    pub order_number: OrderNumber,
+    /// The pricing version `price_amount` was worked out with.
+    pub pricing_rule_set: PricingRuleSetId,
+    pub pricing_version: i32,
+    pub items: Vec<NewOrderItem>,
+}
+
+pub struct NewOrderItem {
     pub filename: String,
     pub file_size: i32,
-    pub text_content: String,
+    pub text_blob: BlobKey,
+    pub text_encoding: Option<String>,
     pub text_length: i32,
     pub price_amount: i32,
-    pub order_number: OrderNumber,
+}
+
+#[derive(Serialize)]
+struct OrderItemData {
+    position: i32,
+    filename: String,
+    file_size: i32,
+    text_blob: BlobKey,
+    text_encoding: Option<String>,
+    text_length: i32,
+    price_amount: i32,
 }
 
 #[derive(Serialize)]
@@ -29,105 +183,298 @@ struct OrderData {
     user_email: String,
     filename: String,
     file_size: i32,
-    text_content: String,
     text_length: i32,
     price_amount: i32,
+    item_count: i32,
     payment_status: PaymentStatus,
     order_number: OrderNumber,
+    pricing_rule_set: surrealdb::RecordId,
+    pricing_version: i32,
 }
 
+/// Creates the order and its items in one transaction; items are numbered in upload order.
 pub async fn create_order(params: CreateOrderParams) -> Result<Order, DataError> {
-    let order: Option<Order> = DB
-        .create("order")
-        .content(OrderData {
-            user: params.user_id.into_record_id(),
-            user_email: params.user_email,
-            filename: params.filename,
-            file_size: params.file_size,
-            text_content: params.text_content,
-            text_length: params.text_length,
-            price_amount: params.price_amount,
-            payment_status: PaymentStatus::Pending,
-            order_number: params.order_number,
+    let order = OrderData {
+        user: params.user_id.into_record_id(),
+        user_email: params.user_email,
+        filename: params.label,
+        file_size: params.items.iter().map(|item| item.file_size).sum(),
+        text_length: params.items.iter().map(|item| item.text_length).sum(),
+        price_amount: params.price_amount,
+        item_count: params.items.len() as i32,
+        payment_status: PaymentStatus::Pending,
+        order_number: params.order_number,
+        pricing_rule_set: params.pricing_rule_set.into_record_id(),
+        pricing_version: params.pricing_version,
+    };
+    let items: Vec<OrderItemData> = params
+        .items
+        .into_iter()
+        .enumerate()
+        .map(|(position, item)| OrderItemData {
+            position: position as i32,
+            filename: item.filename,
+            file_size: item.file_size,
+            text_blob: item.text_blob,
+            text_encoding: item.text_encoding,
+            text_length: item.text_length,
+            price_amount: item.price_amount,
         })
-        .await?;
-
-    Ok(order.ok_or(DataError::CreationFailed(errors::ORDER_CREATION_FAILED))?)
-}
+        .collect();
 
-#[derive(Serialize)]
-struct TossPaymentConfirmationRequest {
-    #[serde(rename = "paymentKey")]
-    payment_key: String,
-    #[serde(rename = "orderId")]
-    order_id: String,
-    amount: i32,
-}
-
-pub struct ConfirmPaymentParams {
-    pub secret_key: String,
-    pub order_number: OrderNumber,
-    pub payment_key: String,
-    pub amount: i32,
-}
-
-pub async fn confirm_payment_with_toss(params: ConfirmPaymentParams) -> PaymentStatus {
-    let confirm_request = TossPaymentConfirmationRequest {
-        payment_key: params.payment_key,
-        order_id: params.order_number.to_string(),
-        amount: params.amount,
-    };
+    let mut result = DB
+        .query(
+            "BEGIN TRANSACTION;
+             LET $created = CREATE ONLY order CONTENT $order;
+             FOR $item IN $items {
+                 CREATE order_item CONTENT {
+                     order: $created.id,
+                     position: $item.position,
+                     filename: $item.filename,
+                     file_size: $item.file_size,
+                     text_blob: $item.text_blob,
+                     text_encoding: $item.text_encoding,
+                     text_length: $item.text_length,
+                     price_amount: $item.price_amount,
+                 };
+             };
+             DELETE blob_gc WHERE $items.text_blob CONTAINS key;
+             CREATE order_event CONTENT {
+                 order: $created.id,
+                 to_status: $created.payment_status,
+                 actor_kind: 'customer',
+                 actor: $created.user,
+                 actor_email: $created.user_email,
+             };
+             RETURN $created;
+             COMMIT TRANSACTION;",
+        )
+        .bind(("order", order))
+        .bind(("items", items))
+        .await?;
 
-    let response = reqwest::Client::new()
-        .post(payment::TOSS_API_CONFIRM_URL)
-        .basic_auth(&params.secret_key, Some(""))
-        .json(&confirm_request)
-        .send()
-        .await;
-
-    match response {
-        Ok(resp) if resp.status().is_success() => PaymentStatus::Paid,
-        Ok(resp) => {
-            let error_body = match resp.text().await {
-                Ok(body) => body,
-                Err(e) => {
-                    tracing::error!("Failed to decode Toss API error response: {}", e);
-                    "Failed to decode response".to_string()
-                }
-            };
-            tracing::error!("Toss payment confirmation failed: {}", error_body);
-            PaymentStatus::Failed
-        }
-        Err(e) => {
-            tracing::error!("Failed to call Toss API: {}", e);
-            PaymentStatus::Failed
-        }
-    }
+    let last = result.num_statements() - 1;
+    let order: Option<Order> = result.take(last)?;
+    order.ok_or(DataError::CreationFailed(errors::ORDER_CREATION_FAILED))
 }
 
-pub async fn update_order_payment(
+/// Settles a pending order as paid, failed or cancelled. Marking it paid also
+/// queues its analysis job, in the same transaction, so no paid order is left
+/// without one.
+pub async fn settle_order_payment(
     order_id: &OrderId,
     payment_key: &str,
-    payment_status: PaymentStatus,
+    to: PaymentStatus,
+    event: OrderEventParams,
 ) -> Result<Order, DataError> {
-    let paid_at: Option<Datetime> = if payment_status == PaymentStatus::Paid {
+    let paid_at: Option<Datetime> = if to == PaymentStatus::Paid {
         Some(Datetime::from(Utc::now()))
     } else {
         None
     };
+    let then = if to == PaymentStatus::Paid { QUEUE_ANALYSIS_JOB } else { "" };
+
+    let result = transition_order(order_id, to, event, "payment_key = $payment_key, paid_at = $paid_at", "", then)
+        .bind(("payment_key", payment_key.to_string()))
+        .bind(("paid_at", paid_at))
+        .await?;
 
+    finish_transition(result, to)
+}
+
+/// Puts the payment key on a pending order before its charge is confirmed, so
+/// the expiry job leaves the order alone while the outcome is unknown; it is
+/// settled by the confirmation or, failing that, by the provider's webhook.
+/// Returns `false` when the order is no longer pending.
+pub async fn record_payment_attempt(order_id: &OrderId, payment_key: &str) -> Result<bool, DataError> {
     let mut result = DB
-        .query(
-            "UPDATE $order SET payment_key = $payment_key, payment_status = $payment_status, paid_at = $paid_at
-             WHERE payment_status = 'pending'
-             RETURN *",
-        )
+        .query("UPDATE $order SET payment_key = $payment_key WHERE payment_status = 'pending' RETURN VALUE id")
         .bind(("order", order_id.clone().into_record_id()))
         .bind(("payment_key", payment_key.to_string()))
-        .bind(("payment_status", payment_status.as_str().to_string()))
-        .bind(("paid_at", paid_at))
         .await?;
 
-    let order: Option<Order> = result.take(0)?;
-    order.ok_or(DataError::NotFound(errors::ORDER_NOT_FOUND_OR_PROCESSED))
+    let updated: Vec<OrderId> = result.take(0)?;
+    Ok(!updated.is_empty())
+}
+
+/// Notes something on the order's timeline without moving its status, such as
+/// a charge reversed because the order could no longer take it.
+pub async fn record_order_event(order_id: &OrderId, event: OrderEventParams) -> Result<(), DataError> {
+    DB.query(format!(
+        "LET $previous = $order.payment_status;
+         LET $to = $previous;
+         {RECORD_ORDER_EVENT}"
+    ))
+    .bind(("order", order_id.clone().into_record_id()))
+    .bind(("event", OrderEventData::from(event)))
+    .await?
+    .check()?;
+
+    Ok(())
+}
+
+/// Puts a failed order back to pending so it can be paid again; the next
+/// attempt brings its own payment key, and the order gets a fresh time to live.
+pub async fn retry_failed_payment(order_id: &OrderId, event: OrderEventParams) -> Result<Order, DataError> {
+    let changes = "payment_key = NONE, pending_since = time::now()";
+    let result = transition_order(order_id, PaymentStatus::Pending, event, changes, "", "").await?;
+    finish_transition(result, PaymentStatus::Pending)
+}
+
+/// Stores the report unless one already exists, so a retried job keeps the first result.
+pub async fn save_analysis_report(item_id: &OrderItemId, report: &AnalysisReport) -> Result<(), DataError> {
+    DB.query("UPDATE $item SET analysis_report = $report WHERE analysis_report = NONE")
+        .bind(("item", item_id.clone().into_record_id()))
+        .bind(("report", report.clone()))
+        .await?
+        .check()?;
+
+    Ok(())
+}
+
+/// Keeps the first preview computed, should two quote views race to store one.
+pub async fn save_order_preview(order_id: &OrderId, preview: &AnalysisPreview) -> Result<(), DataError> {
+    DB.query("UPDATE $order SET analysis_preview = $preview WHERE analysis_preview = NONE")
+        .bind(("order", order_id.clone().into_record_id()))
+        .bind(("preview", preview.clone()))
+        .await?
+        .check()?;
+
+    Ok(())
+}
+
+/// Points an item whose text was stored inline at its blob instead.
+pub async fn move_text_to_blob(item_id: &OrderItemId, text_blob: &BlobKey) -> Result<(), DataError> {
+    DB.query("UPDATE $item SET text_blob = $text_blob, text_content = NONE")
+        .bind(("item", item_id.clone().into_record_id()))
+        .bind(("text_blob", text_blob.to_string()))
+        .await?
+        .check()?;
+
+    Ok(())
+}
+
+pub struct RecordRefundParams {
+    pub amount: i32,
+    pub reason: String,
+    pub refunded_by: UserId,
+    pub refunded_by_email: String,
+    /// The provider's response to the cancel request; `None` for credit-paid orders.
+    pub provider_payload: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RefundData {
+    amount: i32,
+    reason: String,
+    refunded_by: surrealdb::RecordId,
+    refunded_by_email: String,
+    refunded_at: Datetime,
+}
+
+/// Gives the customer back credit balance, with its ledger entry, when the order was paid from credits.
+const CREDIT_BACK_REFUND: &str = "IF $updated[0].paid_with_credits {
+                 LET $credited = (UPDATE ONLY $updated[0].user SET credit_balance += $amount RETURN AFTER);
+                 CREATE credit_transaction CONTENT {
+                     user: $updated[0].user,
+                     amount: $amount,
+                     balance_after: $credited.credit_balance,
+                     kind: 'refund',
+                     order: $order,
+                     note: $refund.reason,
+                     created_by: $refund.refunded_by,
+                 };
+             };";
+
+/// Records a refund the provider has made or, for orders paid from credits,
+/// makes it by crediting the balance in the same transaction. Refunds never
+/// exceed the price, and whether this one leaves anything to refund is read
+/// off the order as it is updated, so concurrent refunds can't both pass a
+/// stale check.
+pub async fn record_refund(order_id: &OrderId, params: RecordRefundParams) -> Result<Order, DataError> {
+    let mut event = OrderEventParams::admin(&params.refunded_by).with_note(params.reason.clone());
+    if let Some(payload) = params.provider_payload {
+        event = event.with_payload(payload);
+    }
+
+    // Like `transition_order`, but the target status depends on the order's amounts.
+    let result = DB
+        .query(format!(
+            "BEGIN TRANSACTION;
+             LET $previous = $order.payment_status;
+             LET $updated = (
+                 UPDATE $order SET
+                     payment_status = IF refunded_amount + $amount = price_amount {{ 'refunded' }} ELSE {{ 'partially_refunded' }},
+                     refunds += $refund,
+                     refunded_amount += $amount
+                 WHERE payment_status IN $from AND refunded_amount + $amount <= price_amount
+                 RETURN AFTER
+             );
+             IF array::len($updated) > 0 {{
+                 LET $to = $updated[0].payment_status;
+                 {RECORD_ORDER_EVENT}
+                 {CREDIT_BACK_REFUND}
+             }};
+             RETURN {{ order: $updated[0], previous: $previous }};
+             COMMIT TRANSACTION;"
+        ))
+        .bind(("order", order_id.clone().into_record_id()))
+        .bind(("from", PaymentStatus::sources_of(PaymentStatus::PartiallyRefunded)))
+        .bind(("event", OrderEventData::from(event)))
+        .bind(("amount", params.amount))
+        .bind(("refund", RefundData {
+            amount: params.amount,
+            reason: params.reason,
+            refunded_by: params.refunded_by.into_record_id(),
+            refunded_by_email: params.refunded_by_email,
+            refunded_at: Datetime::from(Utc::now()),
+        }))
+        .await?;
+
+    finish_transition(result, PaymentStatus::PartiallyRefunded)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ExpiredOrders {
+    pub expired: usize,
+}
+
+/// Cancels orders that have been awaiting payment since before `pending_before`,
+/// counting from when they last entered `pending`, marking them expired and
+/// dropping their items' text and the cached preview. The blobs that held the
+/// text go on the collection list for housekeeping. Orders with a payment key
+/// have a charge in flight and are left for it to settle.
+pub async fn expire_stale_pending_orders(pending_before: DateTime<Utc>) -> Result<ExpiredOrders, DataError> {
+    let event = OrderEventParams::system().with_note(payment::ORDER_EXPIRED_NOTE);
+    let mut result = DB
+        .query(format!(
+            "BEGIN TRANSACTION;
+             LET $expired = (
+                 UPDATE order SET payment_status = 'cancelled', expired_at = time::now(), analysis_preview = NONE
+                 WHERE payment_status = 'pending' AND payment_key = NONE AND pending_since < $pending_before
+                 RETURN VALUE id
+             );
+             FOR $order IN $expired {{
+                 {RECORD_ORDER_EVENT}
+             }};
+             LET $released = (
+                 UPDATE order_item SET text_blob = NONE, text_content = NONE
+                 WHERE $expired CONTAINS order AND (text_blob != NONE OR text_content != NONE)
+                 RETURN BEFORE
+             );
+             FOR $key IN array::distinct($released[WHERE text_blob != NONE].text_blob) {{
+                 UPSERT type::thing('blob_gc', $key) SET key = $key, released_at = time::now();
+             }};
+             RETURN {{ expired: array::len($expired) }};
+             COMMIT TRANSACTION;"
+        ))
+        .bind(("pending_before", Datetime::from(pending_before)))
+        .bind(("previous", PaymentStatus::Pending))
+        .bind(("to", PaymentStatus::Cancelled))
+        .bind(("event", OrderEventData::from(event)))
+        .await?;
+
+    let last = result.num_statements() - 1;
+    let expired: Option<ExpiredOrders> = result.take(last)?;
+    Ok(expired.unwrap_or_default())
 }
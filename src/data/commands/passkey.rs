@@ -0,0 +1,71 @@
+use chrono::Utc;
+use serde::Serialize;
+use surrealdb::sql::Datetime;
+
+use crate::{
+    constants::errors,
+    data::errors::DataError,
+    db::DB,
+    models::{passkey::Passkey, PasskeyId, UserId},
+};
+
+pub struct CreatePasskeyParams {
+    pub user_id: UserId,
+    pub name: String,
+    pub credential_id: String,
+    pub user_handle: String,
+    pub credential: String,
+}
+
+#[derive(Serialize)]
+struct PasskeyData {
+    user: surrealdb::RecordId,
+    name: String,
+    credential_id: String,
+    user_handle: String,
+    credential: String,
+}
+
+pub async fn create_passkey(params: CreatePasskeyParams) -> Result<(), DataError> {
+    let passkey: Option<Passkey> = DB
+        .create("passkey")
+        .content(PasskeyData {
+            user: params.user_id.into_record_id(),
+            name: params.name,
+            credential_id: params.credential_id,
+            user_handle: params.user_handle,
+            credential: params.credential,
+        })
+        .await?;
+
+    passkey.ok_or(DataError::CreationFailed(errors::PASSKEY_CREATION_FAILED))?;
+    Ok(())
+}
+
+/// Persists the credential after a sign-in (signature counter / backup state may change).
+pub async fn record_passkey_use(credential_id: &str, credential: String) -> Result<(), DataError> {
+    DB.query(
+        "UPDATE passkey SET credential = $credential, last_used_at = $now
+         WHERE credential_id = $credential_id",
+    )
+    .bind(("credential_id", credential_id.to_string()))
+    .bind(("credential", credential))
+    .bind(("now", Datetime::from(Utc::now())))
+    .await?
+    .check()?;
+
+    Ok(())
+}
+
+pub async fn delete_passkey(user_id: &UserId, passkey_id: &PasskeyId) -> Result<(), DataError> {
+    let mut result = DB
+        .query("DELETE $passkey WHERE user = $user RETURN BEFORE")
+        .bind(("passkey", passkey_id.clone().into_record_id()))
+        .bind(("user", user_id.clone().into_record_id()))
+        .await?;
+
+    let deleted: Option<Passkey> = result.take(0)?;
+    deleted.ok_or(DataError::NotFound(errors::PASSKEY_NOT_FOUND))?;
+
+    Ok(())
+}
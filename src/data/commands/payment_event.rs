@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::payment,
+    data::errors::DataError,
+    db::DB,
+    models::{payment_event::WebhookOutcome, OrderId, OrderNumber},
+};
+
+pub struct RecordPaymentEventParams {
+    pub provider: &'static str,
+    pub event_type: String,
+    pub order_id: Option<OrderId>,
+    pub order_number: Option<OrderNumber>,
+    pub payment_key: Option<String>,
+    pub provider_status: Option<String>,
+    pub payload: String,
+    /// `None` when the delivery wasn't signed or no secret is configured.
+    pub signature_valid: Option<bool>,
+    pub outcome: WebhookOutcome,
+}
+
+#[derive(Serialize)]
+struct PaymentEventData {
+    provider: &'static str,
+    event_type: String,
+    order: Option<surrealdb::RecordId>,
+    order_number: Option<OrderNumber>,
+    payment_key: Option<String>,
+    provider_status: Option<String>,
+    payload: String,
+    payload_truncated: bool,
+    signature_valid: Option<bool>,
+    outcome: WebhookOutcome,
+}
+
+#[derive(Deserialize)]
+struct PaymentEventRecord {
+    #[allow(dead_code)]
+    event_type: String,
+}
+
+/// Anyone can post to the webhook, so the stored payload is cut to
+/// `WEBHOOK_STORED_PAYLOAD_CHARS` on a character boundary.
+pub async fn record_payment_event(params: RecordPaymentEventParams) -> Result<(), DataError> {
+    let mut payload = params.payload;
+    let payload_truncated = match payload.char_indices().nth(payment::WEBHOOK_STORED_PAYLOAD_CHARS) {
+        Some((end, _)) => {
+            payload.truncate(end);
+            true
+        }
+        None => false,
+    };
+
+    let _: Option<PaymentEventRecord> = DB
+        .create("payment_event")
+        .content(PaymentEventData {
+            provider: params.provider,
+            event_type: params.event_type,
+            order: params.order_id.map(OrderId::into_record_id),
+            order_number: params.order_number,
+            payment_key: params.payment_key,
+            provider_status: params.provider_status,
+            payload,
+            payload_truncated,
+            signature_valid: params.signature_valid,
+            outcome: params.outcome,
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::{
+    constants::errors,
+    data::errors::DataError,
+    db::DB,
+    models::{pricing::{PricingRuleSet, PricingRules}, UserId},
+};
+
+/// Publishes `rules` as the next version. The unique index on `version` makes
+/// the later of two concurrent publishes fail rather than share a number.
+pub async fn publish_pricing_rules(
+    rules: PricingRules,
+    user_id: &UserId,
+    user_email: &str,
+) -> Result<PricingRuleSet, DataError> {
+    let mut result = DB
+        .query(
+            "BEGIN TRANSACTION;
+             LET $latest = math::max(SELECT VALUE version FROM pricing_rule_set) ?? 0;
+             LET $created = CREATE ONLY pricing_rule_set CONTENT {
+                 version: $latest + 1,
+                 rules: $rules,
+                 created_by: $user,
+                 created_by_email: $email,
+             };
+             RETURN $created;
+             COMMIT TRANSACTION;",
+        )
+        .bind(("rules", rules))
+        .bind(("user", user_id.clone().into_record_id()))
+        .bind(("email", user_email.to_string()))
+        .await?;
+
+    let last = result.num_statements() - 1;
+    let rule_set: Option<PricingRuleSet> = result.take(last)?;
+    rule_set.ok_or(DataError::CreationFailed(errors::PRICING_RULES_CREATION_FAILED))
+}
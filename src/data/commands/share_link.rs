@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use surrealdb::sql::Datetime;
+
+use crate::{
+    constants::errors,
+    data::errors::DataError,
+    db::DB,
+    models::{share_link::ShareLink, OrderId, ShareLinkId, UserId},
+};
+
+pub async fn create_share_link(
+    order_id: &OrderId,
+    user_id: &UserId,
+    token: &str,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<ShareLink, DataError> {
+    let mut result = DB
+        .query(
+            "CREATE ONLY share_link CONTENT {
+                 order: $order,
+                 created_by: $user,
+                 token: $share_token,
+                 expires_at: $expires_at,
+             }",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .bind(("user", user_id.clone().into_record_id()))
+        .bind(("share_token", token.to_string()))
+        .bind(("expires_at", expires_at.map(Datetime::from)))
+        .await?;
+
+    let link: Option<ShareLink> = result.take(0)?;
+    link.ok_or(DataError::CreationFailed(errors::SHARE_LINK_CREATION_FAILED))
+}
+
+/// Only the owner of the link's order can revoke it. Returns the revoked link.
+pub async fn revoke_share_link(link_id: &ShareLinkId, user_id: &UserId) -> Result<ShareLink, DataError> {
+    let mut result = DB
+        .query(
+            "UPDATE $link SET revoked_at = time::now()
+             WHERE order.user = $user AND revoked_at = NONE
+             RETURN AFTER",
+        )
+        .bind(("link", link_id.clone().into_record_id()))
+        .bind(("user", user_id.clone().into_record_id()))
+        .await?;
+
+    let link: Option<ShareLink> = result.take(0)?;
+    link.ok_or(DataError::NotFound(errors::SHARE_LINK_NOT_REVOCABLE))
+}
+
+pub async fn record_share_link_access(
+    link_id: &ShareLinkId,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> Result<(), DataError> {
+    DB.query("CREATE share_link_access CONTENT { share_link: $link, ip_address: $ip_address, user_agent: $user_agent }")
+        .bind(("link", link_id.clone().into_record_id()))
+        .bind(("ip_address", ip_address))
+        .bind(("user_agent", user_agent))
+        .await?
+        .check()?;
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use surrealdb::sql::Datetime;
+
+use crate::{
+    data::errors::DataError,
+    db::DB,
+    models::UserId,
+};
+
+/// Replaces any unconfirmed enrollment with a fresh secret. Callers must check the
+/// user has no enabled credential first; the unique user index rejects a second one.
+pub async fn start_totp_enrollment(user_id: &UserId, secret: &str) -> Result<(), DataError> {
+    DB.query(
+        "BEGIN TRANSACTION;
+         DELETE totp_credential WHERE user = $user AND enabled_at = NONE;
+         CREATE totp_credential CONTENT { user: $user, secret: $secret };
+         COMMIT TRANSACTION;",
+    )
+    .bind(("user", user_id.clone().into_record_id()))
+    .bind(("secret", secret.to_string()))
+    .await?
+    .check()?;
+
+    Ok(())
+}
+
+/// Confirms the pending enrollment and replaces the user's recovery codes.
+pub async fn enable_totp(user_id: &UserId, step: i64, recovery_code_hashes: Vec<String>) -> Result<(), DataError> {
+    DB.query(
+        "BEGIN TRANSACTION;
+         UPDATE totp_credential SET enabled_at = time::now(), last_used_step = $step
+             WHERE user = $user AND enabled_at = NONE;
+         DELETE recovery_code WHERE user = $user;
+         FOR $hash IN $hashes { CREATE recovery_code CONTENT { user: $user, code_hash: $hash }; };
+         COMMIT TRANSACTION;",
+    )
+    .bind(("user", user_id.clone().into_record_id()))
+    .bind(("step", step))
+    .bind(("hashes", recovery_code_hashes))
+    .await?
+    .check()?;
+
+    Ok(())
+}
+
+/// A submitted second-factor code, reduced to what the database can check: the
+/// TOTP time step it matches, if any, or the hash of a recovery code.
+pub enum SecondFactorCode {
+    Totp { step: Option<i64> },
+    RecoveryCode { hash: String },
+}
+
+/// How an attempt the lock let through went.
+#[derive(Debug, Deserialize)]
+pub struct SecondFactorAttempt {
+    pub accepted: bool,
+    /// This wrong code was the `max_failures`-th in a row.
+    pub locked: bool,
+}
+
+/// Whether the attempt is accepted, evaluated against the credential as it was
+/// before the update: a TOTP step newer than the last accepted one, so a code
+/// can't be replayed within its validity window, or an unused recovery code.
+const CODE_ACCEPTED: &str = "(($step != NONE AND last_used_step < $step) OR $recovery != NONE)";
+
+/// Wrong codes so far; a lock that has run out starts the count over.
+const FAILURES_SO_FAR: &str = "(IF locked_until = NONE { failed_attempts } ELSE { 0 })";
+
+/// Checks the lock, accepts or rejects the code and counts the failure in one
+/// update of the credential, so parallel attempts can't all pass on a stale
+/// count and no code, right or wrong, gets past an active lock. Acceptance
+/// clears the count; the `max_failures`-th wrong code in a row locks the
+/// credential until `lock_until`. Returns `None` when the attempt wasn't let
+/// through: the credential is locked, or there is no enabled one.
+pub async fn attempt_second_factor(
+    user_id: &UserId,
+    code: SecondFactorCode,
+    max_failures: i64,
+    lock_until: DateTime<Utc>,
+) -> Result<Option<SecondFactorAttempt>, DataError> {
+    let (step, code_hash) = match code {
+        SecondFactorCode::Totp { step } => (step, None),
+        SecondFactorCode::RecoveryCode { hash } => (None, Some(hash)),
+    };
+
+    let mut result = DB
+        .query(format!(
+            "BEGIN TRANSACTION;
+             LET $recovery = IF $code_hash != NONE {{
+                 (SELECT VALUE id FROM recovery_code WHERE user = $user AND code_hash = $code_hash AND used_at = NONE)[0]
+             }};
+             LET $attempt = (
+                 UPDATE totp_credential MERGE {{
+                     last_used_step: IF {CODE_ACCEPTED} AND $step != NONE {{ $step }} ELSE {{ last_used_step }},
+                     failed_attempts: IF {CODE_ACCEPTED} {{ 0 }} ELSE {{ {FAILURES_SO_FAR} + 1 }},
+                     locked_until: IF !{CODE_ACCEPTED} AND {FAILURES_SO_FAR} + 1 >= $max_failures {{ $lock_until }} ELSE {{ NONE }},
+                 }}
+                 WHERE user = $user AND enabled_at != NONE AND (locked_until = NONE OR locked_until <= time::now())
+                 RETURN VALUE {{ accepted: failed_attempts = 0, locked: locked_until != NONE }}
+             )[0];
+             IF $attempt.accepted AND $recovery != NONE {{
+                 UPDATE $recovery SET used_at = time::now();
+             }};
+             RETURN $attempt;
+             COMMIT TRANSACTION;"
+        ))
+        .bind(("user", user_id.clone().into_record_id()))
+        .bind(("step", step))
+        .bind(("code_hash", code_hash))
+        .bind(("max_failures", max_failures))
+        .bind(("lock_until", Datetime::from(lock_until)))
+        .await?;
+
+    let last = result.num_statements() - 1;
+    let attempt: Option<SecondFactorAttempt> = result.take(last)?;
+    Ok(attempt)
+}
+
+pub async fn disable_totp(user_id: &UserId) -> Result<(), DataError> {
+    DB.query(
+        "BEGIN TRANSACTION;
+         DELETE totp_credential WHERE user = $user;
+         DELETE recovery_code WHERE user = $user;
+         COMMIT TRANSACTION;",
+    )
+    .bind(("user", user_id.clone().into_record_id()))
+    .await?
+    .check()?;
+
+    Ok(())
+}
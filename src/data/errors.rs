@@ -1,10 +1,13 @@
 use thiserror::Error;
 
+use crate::models::order::IllegalTransition;
+
 /// Separates DB errors from semantic errors (not found, unauthorized).
 #[derive(Error, Debug)]
 pub enum DataError {
+    /// Boxed — `surrealdb::Error` would otherwise bloat every `Result<_, DataError>`.
     #[error("Database error")]
-    Database(#[from] surrealdb::Error),
+    Database(Box<surrealdb::Error>),
 
     #[error("{0}")]
     NotFound(&'static str),
@@ -17,4 +20,13 @@ pub enum DataError {
 
     #[error("{0}")]
     CreationFailed(&'static str),
+
+    #[error("{0}")]
+    IllegalTransition(#[from] IllegalTransition),
+}
+
+impl From<surrealdb::Error> for DataError {
+    fn from(e: surrealdb::Error) -> Self {
+        Self::Database(Box::new(e))
+    }
 }
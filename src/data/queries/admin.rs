@@ -28,12 +28,12 @@ async fn get_user_order_stats(user_record_id: &RecordId) -> Result<UserOrderStat
     let mut result = DB
         .query(
             r#"
-            SELECT count() as count FROM order WHERE user = $user AND payment_status = $paid_status GROUP ALL;
-            SELECT math::sum(price_amount) as total FROM order WHERE user = $user AND payment_status = $paid_status GROUP ALL;
+            SELECT count() as count FROM order WHERE user = $user AND payment_status IN $paid_statuses GROUP ALL;
+            SELECT math::sum(price_amount) as total FROM order WHERE user = $user AND payment_status IN $paid_statuses GROUP ALL;
             "#,
         )
         .bind(("user", user_record_id.clone()))
-        .bind(("paid_status", PaymentStatus::Paid.as_str()))
+        .bind(("paid_statuses", PaymentStatus::PAID))
         .await?;
 
     let order_count: Option<CountResult> = result.take(0)?;
@@ -50,12 +50,12 @@ pub async fn get_admin_stats() -> Result<AdminStats, DataError> {
         .query(
             r#"
             SELECT count() as count FROM user GROUP ALL;
-            SELECT count() as count FROM order WHERE payment_status = $paid_status GROUP ALL;
-            SELECT math::sum(price_amount) as total FROM order WHERE payment_status = $paid_status GROUP ALL;
-            SELECT count() as count FROM order WHERE payment_status = $paid_status AND created_at >= time::now() - 7d GROUP ALL;
+            SELECT count() as count FROM order WHERE payment_status IN $paid_statuses GROUP ALL;
+            SELECT math::sum(price_amount) as total FROM order WHERE payment_status IN $paid_statuses GROUP ALL;
+            SELECT count() as count FROM order WHERE payment_status IN $paid_statuses AND created_at >= time::now() - 7d GROUP ALL;
             "#,
         )
-        .bind(("paid_status", PaymentStatus::Paid.as_str()))
+        .bind(("paid_statuses", PaymentStatus::PAID))
         .await?;
 
     let total_users: Option<CountResult> = result.take(0)?;
@@ -222,7 +222,7 @@ pub async fn get_total_order_count(status_filter: Option<PaymentStatus>) -> Resu
 pub async fn get_order_detail(order_id: &OrderId) -> Result<OrderDetail, DataError> {
     let mut result = DB
         .query(
-            "SELECT id, order_number, user, user_email, price_amount, payment_status, created_at, paid_at, payment_key, filename, text_length
+            "SELECT id, order_number, user, user_email, price_amount, payment_status, created_at, paid_at, expired_at, payment_key, filename, text_length, pricing_version, coupon_code, discount_amount, paid_with_credits, refunded_amount, refunds
              FROM order
              WHERE id = $order_id",
         )
@@ -0,0 +1,55 @@
+use crate::{
+    data::errors::DataError,
+    db::DB,
+    models::{
+        analysis_job::{AnalysisJob, AnalysisJobListItem},
+        pagination, OrderId,
+    },
+};
+
+use super::shared::CountResult;
+
+pub async fn get_job_for_order(order_id: &OrderId) -> Result<Option<AnalysisJob>, DataError> {
+    let mut result = DB
+        .query("SELECT * FROM analysis_job WHERE order = $order LIMIT 1")
+        .bind(("order", order_id.clone().into_record_id()))
+        .await?;
+
+    let job: Option<AnalysisJob> = result.take(0)?;
+    Ok(job)
+}
+
+/// Jobs that have failed at least once and haven't since completed: those
+/// waiting to retry and those out of attempts.
+pub async fn get_failing_jobs_paginated(page: i64, per_page: i64) -> Result<Vec<AnalysisJobListItem>, DataError> {
+    let offset = pagination::offset(page, per_page);
+
+    let mut result = DB
+        .query(
+            "SELECT id, order, order.order_number AS order_number, order.user_email AS user_email,
+                    status, attempts, run_at, last_error, updated_at
+             FROM analysis_job
+             WHERE last_error != NONE AND status != 'completed'
+             ORDER BY updated_at DESC
+             LIMIT $limit START $offset",
+        )
+        .bind(("limit", per_page))
+        .bind(("offset", offset))
+        .await?;
+
+    let jobs: Vec<AnalysisJobListItem> = result.take(0)?;
+    Ok(jobs)
+}
+
+pub async fn get_failing_job_count() -> Result<i64, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT count() as count FROM analysis_job
+             WHERE last_error != NONE AND status != 'completed'
+             GROUP ALL",
+        )
+        .await?;
+
+    let count: Option<CountResult> = result.take(0)?;
+    Ok(CountResult::unwrap_or_zero(count))
+}
@@ -0,0 +1,25 @@
+use crate::{data::errors::DataError, db::DB, storage::BlobKey};
+
+/// Keys on the collection list, longest waiting first.
+pub async fn get_listed_blob_keys() -> Result<Vec<BlobKey>, DataError> {
+    let mut result = DB
+        .query("(SELECT key, released_at FROM blob_gc ORDER BY released_at).key")
+        .await?;
+
+    let keys: Vec<BlobKey> = result.take(0)?;
+    Ok(keys)
+}
+
+/// Whether `key`'s blob may be deleted now: still listed, and no item points at it.
+pub async fn is_blob_collectable(key: &BlobKey) -> Result<bool, DataError> {
+    let mut result = DB
+        .query(
+            "RETURN record::exists(type::thing('blob_gc', $key))
+                 AND array::len(SELECT VALUE id FROM order_item WHERE text_blob = $key LIMIT 1) = 0",
+        )
+        .bind(("key", key.to_string()))
+        .await?;
+
+    let collectable: Option<bool> = result.take(0)?;
+    Ok(collectable.unwrap_or_default())
+}
@@ -0,0 +1,51 @@
+use crate::{
+    data::errors::DataError,
+    db::DB,
+    models::{coupon::{Coupon, CouponUsage}, CouponId, OrderId, UserId},
+};
+
+pub async fn get_coupon_by_code(code: &str) -> Result<Option<Coupon>, DataError> {
+    let mut result = DB
+        .query("SELECT * FROM coupon WHERE code = $code LIMIT 1")
+        .bind(("code", code.to_string()))
+        .await?;
+
+    let coupon: Option<Coupon> = result.take(0)?;
+    Ok(coupon)
+}
+
+/// Pending and paid orders using the coupon, other than `order_id`.
+pub async fn get_coupon_usage(coupon_id: &CouponId, user_id: &UserId, order_id: &OrderId) -> Result<CouponUsage, DataError> {
+    let mut result = DB
+        .query(
+            "LET $uses = SELECT user FROM order
+                 WHERE coupon = $coupon AND id != $order AND payment_status IN ['pending', 'paid', 'partially_refunded'];
+             RETURN {
+                 total: array::len($uses),
+                 by_user: array::len($uses[WHERE user = $user]),
+             };",
+        )
+        .bind(("coupon", coupon_id.clone().into_record_id()))
+        .bind(("user", user_id.clone().into_record_id()))
+        .bind(("order", order_id.clone().into_record_id()))
+        .await?;
+
+    let usage: Option<CouponUsage> = result.take(1)?;
+    Ok(usage.unwrap_or_default())
+}
+
+/// Every coupon with how often it has been used, newest first.
+pub async fn get_all_coupons() -> Result<Vec<Coupon>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT *,
+                    count(SELECT id FROM order
+                          WHERE coupon = $parent.id AND payment_status IN ['pending', 'paid', 'partially_refunded']) AS redemption_count
+             FROM coupon
+             ORDER BY created_at DESC",
+        )
+        .await?;
+
+    let coupons: Vec<Coupon> = result.take(0)?;
+    Ok(coupons)
+}
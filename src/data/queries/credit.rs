@@ -0,0 +1,67 @@
+use crate::{
+    constants::errors,
+    data::errors::DataError,
+    db::DB,
+    models::{credit::{CreditTopUp, CreditTransaction}, CreditTopUpId, OrderNumber, UserId},
+};
+
+pub async fn get_credit_balance(user_id: &UserId) -> Result<i32, DataError> {
+    let mut result = DB
+        .query("RETURN (SELECT VALUE credit_balance FROM ONLY $user) ?? 0")
+        .bind(("user", user_id.clone().into_record_id()))
+        .await?;
+
+    let balance: Option<i32> = result.take(0)?;
+    Ok(balance.unwrap_or_default())
+}
+
+/// Newest first.
+pub async fn get_credit_transactions(user_id: &UserId, limit: i64) -> Result<Vec<CreditTransaction>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT * FROM credit_transaction
+             WHERE user = $user
+             ORDER BY created_at DESC
+             LIMIT $limit",
+        )
+        .bind(("user", user_id.clone().into_record_id()))
+        .bind(("limit", limit))
+        .await?;
+
+    let transactions: Vec<CreditTransaction> = result.take(0)?;
+    Ok(transactions)
+}
+
+pub async fn get_top_up_for_user(top_up_id: &CreditTopUpId, user_id: &UserId) -> Result<CreditTopUp, DataError> {
+    let top_up: Option<CreditTopUp> = DB.select(top_up_id.clone().into_record_id()).await?;
+    let top_up = top_up.ok_or(DataError::NotFound(errors::TOP_UP_NOT_FOUND))?;
+
+    if top_up.user != *user_id {
+        return Err(DataError::NotFound(errors::TOP_UP_NOT_FOUND));
+    }
+    Ok(top_up)
+}
+
+pub async fn get_top_up_by_order_number(order_number: &OrderNumber) -> Result<Option<CreditTopUp>, DataError> {
+    let mut result = DB
+        .query("SELECT * FROM credit_top_up WHERE order_number = $order_number LIMIT 1")
+        .bind(("order_number", order_number.to_string()))
+        .await?;
+
+    let top_up: Option<CreditTopUp> = result.take(0)?;
+    Ok(top_up)
+}
+
+pub async fn get_top_up_by_order_number_for_user(
+    order_number: &OrderNumber,
+    user_id: &UserId,
+) -> Result<CreditTopUp, DataError> {
+    let top_up = get_top_up_by_order_number(order_number)
+        .await?
+        .ok_or(DataError::NotFound(errors::TOP_UP_NOT_FOUND))?;
+
+    if top_up.user != *user_id {
+        return Err(DataError::NotFound(errors::TOP_UP_NOT_FOUND));
+    }
+    Ok(top_up)
+}
@@ -1,5 +1,15 @@
 pub mod admin;
+pub mod analysis_job;
+pub mod blob;
+pub mod coupon;
+pub mod credit;
 pub mod order;
+pub mod order_event;
+pub mod passkey;
+pub mod payment_event;
+pub mod pricing;
 pub(crate) mod shared;
+pub mod share_link;
 pub mod todo;
+pub mod two_factor;
 pub mod user;
@@ -1,10 +1,13 @@
+use serde::Deserialize;
+
 use crate::{
     constants::errors,
     data::errors::DataError,
     db::DB,
     models::{
-        order::{Order, OrderSummary},
-        OrderId, OrderNumber, UserId,
+        analysis::{AnalysisPreview, FileReport},
+        order::{Order, OrderItem, OrderItemSummary, OrderSummary},
+        OrderId, OrderItemId, OrderNumber, UserId,
     },
 };
 
@@ -23,6 +26,97 @@ pub async fn get_order_by_order_number(order_number: &OrderNumber) -> Result<Opt
     Ok(order)
 }
 
+pub async fn get_order_item(item_id: &OrderItemId) -> Result<Option<OrderItem>, DataError> {
+    let item: Option<OrderItem> = DB.select(item_id.clone().into_record_id()).await?;
+    Ok(item)
+}
+
+#[derive(Deserialize)]
+struct ItemIdRow {
+    id: OrderItemId,
+}
+
+#[derive(Deserialize)]
+struct PreviewRow {
+    analysis_preview: Option<AnalysisPreview>,
+}
+
+/// The quote page's cached preview; `None` until the quote is first viewed.
+pub async fn get_order_preview(order_id: &OrderId) -> Result<Option<AnalysisPreview>, DataError> {
+    let mut result = DB
+        .query("SELECT analysis_preview FROM $order")
+        .bind(("order", order_id.clone().into_record_id()))
+        .await?;
+
+    let row: Option<PreviewRow> = result.take(0)?;
+    Ok(row.and_then(|row| row.analysis_preview))
+}
+
+/// Text stored on an item from before blob storage.
+#[derive(Deserialize)]
+pub struct InlineText {
+    pub id: OrderItemId,
+    pub text_content: String,
+}
+
+pub async fn get_items_with_inline_text(limit: i64) -> Result<Vec<InlineText>, DataError> {
+    let mut result = DB
+        .query("SELECT id, text_content FROM order_item WHERE text_content != NONE LIMIT $limit")
+        .bind(("limit", limit))
+        .await?;
+
+    let items: Vec<InlineText> = result.take(0)?;
+    Ok(items)
+}
+
+/// Items still waiting for a report, in upload order. Ids only, so a batch's
+/// text isn't loaded all at once.
+pub async fn get_unanalyzed_item_ids(order_id: &OrderId) -> Result<Vec<OrderItemId>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT id, position FROM order_item
+             WHERE order = $order AND analysis_report = NONE
+             ORDER BY position",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .await?;
+
+    let rows: Vec<ItemIdRow> = result.take(0)?;
+    Ok(rows.into_iter().map(|row| row.id).collect())
+}
+
+/// Reports generated so far, in upload order. Text is left behind.
+pub async fn get_order_reports(order_id: &OrderId) -> Result<Vec<FileReport>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT filename, analysis_report AS report, position
+             FROM order_item
+             WHERE order = $order AND analysis_report != NONE
+             ORDER BY position",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .await?;
+
+    let reports: Vec<FileReport> = result.take(0)?;
+    Ok(reports)
+}
+
+/// Items without their text, for quotes and order details.
+pub async fn get_order_item_summaries(order_id: &OrderId) -> Result<Vec<OrderItemSummary>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT id, position, filename, file_size, text_blob, text_encoding, text_length, price_amount
+             FROM order_item
+             WHERE order = $order
+             ORDER BY position",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .await?;
+
+    let items: Vec<OrderItemSummary> = result.take(0)?;
+    Ok(items)
+}
+
 pub async fn get_orders_for_user(user_id: &UserId, limit: i64) -> Result<Vec<OrderSummary>, DataError> {
     let mut result = DB
         .query(
@@ -0,0 +1,21 @@
+use crate::{
+    data::errors::DataError,
+    db::DB,
+    models::{order_event::OrderEvent, OrderId},
+};
+
+/// The order's payment status history, oldest first.
+pub async fn get_order_events(order_id: &OrderId) -> Result<Vec<OrderEvent>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT from_status, to_status, actor_kind, actor, actor_email, payload, note, created_at
+             FROM order_event
+             WHERE order = $order
+             ORDER BY created_at",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .await?;
+
+    let events: Vec<OrderEvent> = result.take(0)?;
+    Ok(events)
+}
@@ -0,0 +1,43 @@
+use crate::{
+    data::errors::DataError,
+    db::DB,
+    models::{
+        passkey::{Passkey, PasskeyCredential},
+        UserId,
+    },
+};
+
+pub async fn get_passkeys_for_user(user_id: &UserId) -> Result<Vec<Passkey>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT id, name, created_at, last_used_at
+             FROM passkey
+             WHERE user = $user
+             ORDER BY created_at DESC",
+        )
+        .bind(("user", user_id.clone().into_record_id()))
+        .await?;
+
+    let passkeys: Vec<Passkey> = result.take(0)?;
+    Ok(passkeys)
+}
+
+pub async fn get_passkey_credentials_for_user(user_id: &UserId) -> Result<Vec<PasskeyCredential>, DataError> {
+    let mut result = DB
+        .query("SELECT user, user_handle, credential FROM passkey WHERE user = $user")
+        .bind(("user", user_id.clone().into_record_id()))
+        .await?;
+
+    let credentials: Vec<PasskeyCredential> = result.take(0)?;
+    Ok(credentials)
+}
+
+pub async fn get_passkey_credential(credential_id: &str) -> Result<Option<PasskeyCredential>, DataError> {
+    let mut result = DB
+        .query("SELECT user, user_handle, credential FROM passkey WHERE credential_id = $credential_id")
+        .bind(("credential_id", credential_id.to_string()))
+        .await?;
+
+    let credential: Option<PasskeyCredential> = result.take(0)?;
+    Ok(credential)
+}
@@ -0,0 +1,21 @@
+use crate::{
+    data::errors::DataError,
+    db::DB,
+    models::{payment_event::PaymentEvent, OrderId},
+};
+
+pub async fn get_payment_events_for_order(order_id: &OrderId) -> Result<Vec<PaymentEvent>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT event_type, payment_key, provider_status, outcome, signature_valid, payload, payload_truncated, received_at
+             FROM payment_event
+             WHERE order = $order
+             ORDER BY received_at DESC",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .await?;
+
+    let events: Vec<PaymentEvent> = result.take(0)?;
+    Ok(events)
+}
+
@@ -0,0 +1,27 @@
+use crate::{
+    constants::errors,
+    data::errors::DataError,
+    db::DB,
+    models::pricing::PricingRuleSet,
+};
+
+/// The newest version; new orders are priced with it.
+pub async fn get_current_pricing_rules() -> Result<PricingRuleSet, DataError> {
+    let mut result = DB
+        .query("SELECT * FROM pricing_rule_set ORDER BY version DESC LIMIT 1")
+        .await?;
+
+    let rule_set: Option<PricingRuleSet> = result.take(0)?;
+    rule_set.ok_or(DataError::NotFound(errors::PRICING_RULES_NOT_FOUND))
+}
+
+/// Published versions, newest first.
+pub async fn get_pricing_history(limit: i64) -> Result<Vec<PricingRuleSet>, DataError> {
+    let mut result = DB
+        .query("SELECT * FROM pricing_rule_set ORDER BY version DESC LIMIT $limit")
+        .bind(("limit", limit))
+        .await?;
+
+    let history: Vec<PricingRuleSet> = result.take(0)?;
+    Ok(history)
+}
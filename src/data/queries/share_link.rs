@@ -0,0 +1,43 @@
+use crate::{
+    data::errors::DataError,
+    db::DB,
+    models::{share_link::ShareLink, OrderId},
+};
+
+/// Every link made for the order, newest first, revoked and expired ones included.
+pub async fn get_share_links_for_order(order_id: &OrderId) -> Result<Vec<ShareLink>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT *,
+                    count(SELECT id FROM share_link_access WHERE share_link = $parent.id) AS access_count,
+                    (SELECT VALUE accessed_at FROM share_link_access
+                     WHERE share_link = $parent.id
+                     ORDER BY accessed_at DESC
+                     LIMIT 1)[0] AS last_accessed_at
+             FROM share_link
+             WHERE order = $order
+             ORDER BY created_at DESC",
+        )
+        .bind(("order", order_id.clone().into_record_id()))
+        .await?;
+
+    let links: Vec<ShareLink> = result.take(0)?;
+    Ok(links)
+}
+
+/// The link for `token`, unless it has been revoked or has expired.
+pub async fn get_active_share_link(token: &str) -> Result<Option<ShareLink>, DataError> {
+    let mut result = DB
+        .query(
+            "SELECT * FROM share_link
+             WHERE token = $share_token
+               AND revoked_at = NONE
+               AND (expires_at = NONE OR expires_at > time::now())
+             LIMIT 1",
+        )
+        .bind(("share_token", token.to_string()))
+        .await?;
+
+    let link: Option<ShareLink> = result.take(0)?;
+    Ok(link)
+}
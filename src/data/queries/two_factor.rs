@@ -0,0 +1,25 @@
+use crate::{
+    data::{errors::DataError, queries::shared::CountResult},
+    db::DB,
+    models::{two_factor::TotpCredential, UserId},
+};
+
+pub async fn get_totp_credential(user_id: &UserId) -> Result<Option<TotpCredential>, DataError> {
+    let mut result = DB
+        .query("SELECT secret, enabled_at FROM totp_credential WHERE user = $user")
+        .bind(("user", user_id.clone().into_record_id()))
+        .await?;
+
+    let credential: Option<TotpCredential> = result.take(0)?;
+    Ok(credential)
+}
+
+pub async fn count_unused_recovery_codes(user_id: &UserId) -> Result<i64, DataError> {
+    let mut result = DB
+        .query("SELECT count() as count FROM recovery_code WHERE user = $user AND used_at = NONE GROUP ALL")
+        .bind(("user", user_id.clone().into_record_id()))
+        .await?;
+
+    let count: Option<CountResult> = result.take(0)?;
+    Ok(CountResult::unwrap_or_zero(count))
+}
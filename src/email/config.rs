@@ -5,7 +5,11 @@ use lettre::{
 };
 
 use super::templates;
-use crate::paths;
+use crate::{
+    models::order::{Order, PaymentStatus},
+    paths,
+    views::helpers::{format_datetime, format_price},
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum EmailError {
@@ -161,3 +165,79 @@ pub async fn send_contact_inquiry(
         }
     }
 }
+
+/// Sends `html_body` to a customer. Console mode logs the subject and `summary` instead.
+async fn send_to_customer(
+    config: &EmailConfig,
+    to_email: &str,
+    subject: &str,
+    html_body: String,
+    summary: &str,
+) -> Result<(), EmailError> {
+    let from_mailbox: Mailbox = format!("{} <{}>", config.from_name, config.from_address).parse()?;
+    let to_mailbox: Mailbox = to_email.parse()?;
+
+    let email = Message::builder()
+        .from(from_mailbox)
+        .to(to_mailbox)
+        .subject(subject)
+        .header(ContentType::TEXT_HTML)
+        .body(html_body)?;
+
+    match &config.mode {
+        EmailMode::Console => {
+            tracing::info!("\n\n========== {} ==========", subject.to_uppercase());
+            tracing::info!("To: {}", to_email);
+            tracing::info!("{}", summary);
+            tracing::info!("======================================\n");
+            Ok(())
+        }
+        EmailMode::Smtp { .. } => {
+            let mailer = config.create_smtp_transport()?;
+            mailer.send(&email)?;
+            tracing::info!("\"{}\" email sent to {}", subject, to_email);
+            Ok(())
+        }
+    }
+}
+
+/// Tells the customer how their payment ended: a receipt once the order is
+/// paid, a notice when it failed. Other statuses send nothing; refunds have
+/// their own notice.
+pub async fn send_payment_update(config: &EmailConfig, order: &Order) -> Result<(), EmailError> {
+    let amount = format!("₩{}", format_price(order.price_amount));
+
+    match order.payment_status {
+        PaymentStatus::Paid => {
+            let paid_at = order.paid_at.map(format_datetime).unwrap_or_default();
+            let result_link = format!("{}{}", config.base_url, paths::helpers::payment_confirmation_path(&order.id));
+            let invoice_link = format!("{}{}", config.base_url, paths::helpers::invoice_path(&order.id));
+            let body = templates::payment_receipt(order.order_number.as_str(), &amount, &paid_at, &result_link, &invoice_link);
+            let summary = format!("Order {} paid {} at {}: {}", order.order_number, amount, paid_at, result_link);
+
+            send_to_customer(config, &order.user_email, "Your payment receipt", body, &summary).await
+        }
+        PaymentStatus::Failed => {
+            let quote_link = format!("{}{}", config.base_url, paths::helpers::quote_path(&order.id));
+            let body = templates::payment_failed(order.order_number.as_str(), &amount, &quote_link);
+            let summary = format!("Payment of {} for order {} failed", amount, order.order_number);
+
+            send_to_customer(config, &order.user_email, "Your payment didn't go through", body, &summary).await
+        }
+        PaymentStatus::Pending
+        | PaymentStatus::Cancelled
+        | PaymentStatus::PartiallyRefunded
+        | PaymentStatus::Refunded => Ok(()),
+    }
+}
+
+/// `order` is the order after the refund was recorded.
+pub async fn send_refund_notice(config: &EmailConfig, order: &Order, refund_amount: i32) -> Result<(), EmailError> {
+    let refund = format!("₩{}", format_price(refund_amount));
+    let total_refunded = format!("₩{}", format_price(order.refunded_amount));
+    let invoice_link = format!("{}{}", config.base_url, paths::helpers::invoice_path(&order.id));
+    let body = templates::refund_issued(order.order_number.as_str(), &refund, &total_refunded, &invoice_link);
+    let summary = format!("Refunded {} on order {} ({} in total)", refund, order.order_number, total_refunded);
+
+    send_to_customer(config, &order.user_email, "Your refund has been issued", body, &summary).await
+}
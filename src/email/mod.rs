@@ -1,4 +1,6 @@
 mod config;
 mod templates;
 
-pub use config::{EmailConfig, EmailError, send_contact_inquiry, send_magic_link};
+pub use config::{
+    EmailConfig, EmailError, send_contact_inquiry, send_magic_link, send_payment_update, send_refund_notice,
+};
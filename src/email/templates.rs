@@ -42,3 +42,82 @@ pub fn contact_inquiry(email: &str, message: &str) -> String {
         email, message
     )
 }
+
+/// Wraps the shared layout of the order emails around `content`.
+fn order_email(title: &str, content: &str) -> String {
+    format!(
+        r#"
+        <html>
+            <body style="font-family: sans-serif; max-width: 600px; margin: 0 auto; padding: 20px;">
+                <h2>{}</h2>
+                {}
+            </body>
+        </html>
+        "#,
+        title, content
+    )
+}
+
+fn button(href: &str, label: &str) -> String {
+    format!(
+        r#"<a href="{}" style="background-color: #4F46E5; color: white; padding: 12px 24px; text-decoration: none; border-radius: 6px; display: inline-block;">{}</a>"#,
+        href, label
+    )
+}
+
+pub fn payment_receipt(order_number: &str, amount: &str, paid_at: &str, result_link: &str, invoice_link: &str) -> String {
+    order_email(
+        "Thank you for your payment",
+        &format!(
+            r#"
+                <table style="margin: 20px 0; border-collapse: collapse;">
+                    <tr><td style="color: #666; padding: 4px 16px 4px 0;">Order</td><td>{}</td></tr>
+                    <tr><td style="color: #666; padding: 4px 16px 4px 0;">Amount</td><td>{}</td></tr>
+                    <tr><td style="color: #666; padding: 4px 16px 4px 0;">Paid</td><td>{} UTC</td></tr>
+                </table>
+                <p>Your analysis is running and the results will be ready at the link below.</p>
+                <p style="margin: 30px 0;">{}</p>
+                <p style="color: #666; font-size: 14px;">
+                    Need a document for your records? <a href="{}">View the invoice</a>.
+                </p>
+            "#,
+            order_number,
+            amount,
+            paid_at,
+            button(result_link, "View Results"),
+            invoice_link
+        ),
+    )
+}
+
+pub fn payment_failed(order_number: &str, amount: &str, quote_link: &str) -> String {
+    order_email(
+        "Your payment didn't go through",
+        &format!(
+            r#"
+                <p>The payment of {} for order {} failed, and you have not been charged.</p>
+                <p>Your quote is still open. Retry the payment with the same or another card.</p>
+                <p style="margin: 30px 0;">{}</p>
+            "#,
+            amount,
+            order_number,
+            button(quote_link, "Retry Payment")
+        ),
+    )
+}
+
+pub fn refund_issued(order_number: &str, refund: &str, total_refunded: &str, invoice_link: &str) -> String {
+    order_email(
+        "Your refund has been issued",
+        &format!(
+            r#"
+                <p>We refunded {} for order {}. The total refunded on this order is now {}.</p>
+                <p style="color: #666; font-size: 14px;">
+                    Card refunds usually reach your statement within a few business days.
+                    The <a href="{}">invoice</a> shows the updated amounts.
+                </p>
+            "#,
+            refund, order_number, total_refunded, invoice_link
+        ),
+    )
+}
@@ -0,0 +1,87 @@
+//! One long table — `file,section,name,value` — so summary figures and the
+//! word-frequency tables of every file filter and pivot the same way.
+
+use std::fmt::Display;
+
+use crate::models::analysis::{AnalysisReport, TermCount};
+
+use super::ReportExport;
+
+const HEADER: &str = "file,section,name,value";
+/// Lets Excel detect UTF-8; without it non-ASCII terms open garbled.
+const BYTE_ORDER_MARK: &str = "\u{feff}";
+
+pub(super) fn render(export: &ReportExport) -> Vec<u8> {
+    let mut out = String::from(BYTE_ORDER_MARK);
+    out.push_str(HEADER);
+    out.push_str("\r\n");
+
+    for file in &export.files {
+        for (name, value) in summary(&file.report) {
+            push_row(&mut out, &file.filename, "summary", name, &value);
+        }
+        push_terms(&mut out, &file.filename, "top_words", &file.report.top_words);
+        push_terms(&mut out, &file.filename, "top_bigrams", &file.report.top_bigrams);
+        push_terms(&mut out, &file.filename, "top_trigrams", &file.report.top_trigrams);
+    }
+    out.into_bytes()
+}
+
+fn summary(report: &AnalysisReport) -> [(&'static str, String); 8] {
+    [
+        ("character_count", report.character_count.to_string()),
+        ("word_count", report.word_count.to_string()),
+        ("sentence_count", report.sentence_count.to_string()),
+        ("paragraph_count", report.paragraph_count.to_string()),
+        ("average_word_length", format!("{:.2}", report.average_word_length)),
+        ("average_sentence_length", format!("{:.2}", report.average_sentence_length)),
+        ("reading_time_seconds", report.reading_time_seconds.to_string()),
+        ("flesch_reading_ease", format!("{:.1}", report.flesch_reading_ease)),
+    ]
+}
+
+fn push_terms(out: &mut String, filename: &str, section: &str, terms: &[TermCount]) {
+    for term in terms {
+        push_row(out, filename, section, &term.term, &term.count);
+    }
+}
+
+fn push_row(out: &mut String, filename: &str, section: &str, name: &str, value: &impl Display) {
+    out.push_str(&field(filename));
+    out.push(',');
+    out.push_str(section);
+    out.push(',');
+    out.push_str(&field(name));
+    out.push(',');
+    out.push_str(&value.to_string());
+    out.push_str("\r\n");
+}
+
+/// Quotes per RFC 4180, and defuses text a spreadsheet would run as a
+/// formula — filenames and terms come straight from the upload.
+fn field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_quotes_and_defuses_formulas() {
+        assert_eq!(field("plain"), "plain");
+        assert_eq!(field("a, \"b\""), "\"a, \"\"b\"\"\"");
+        assert_eq!(field("=SUM(A1:A9)"), "'=SUM(A1:A9)");
+        assert_eq!(field("-1,2"), "\"'-1,2\"");
+    }
+}
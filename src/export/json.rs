@@ -0,0 +1,5 @@
+use super::{ExportError, ReportExport};
+
+pub(super) fn render(export: &ReportExport) -> Result<Vec<u8>, ExportError> {
+    Ok(serde_json::to_vec_pretty(export)?)
+}
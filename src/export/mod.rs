@@ -0,0 +1,88 @@
+//! Downloadable copies of a paid order's analysis. Every format renders the
+//! same `ReportExport`: one report per file, in upload order.
+
+mod csv;
+mod json;
+mod pdf;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::models::{analysis::FileReport, OrderNumber};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub const ALL: [Self; 3] = [Self::Pdf, Self::Csv, Self::Json];
+
+    pub fn parse(extension: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.extension() == extension)
+    }
+
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Csv => "CSV",
+            Self::Pdf => "PDF",
+        }
+    }
+
+    /// Also the format's path segment in download URLs.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Pdf => "pdf",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Pdf => "application/pdf",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportExport {
+    pub order_number: OrderNumber,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub files: Vec<FileReport>,
+}
+
+impl ReportExport {
+    pub fn filename(&self, format: ExportFormat) -> String {
+        format!("analysis-{}.{}", self.order_number, format.extension())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    #[error("Failed to serialize report: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to build PDF: {0}")]
+    Pdf(String),
+
+    /// The report has characters none of the PDF's fonts can draw.
+    #[error("Report text is not covered by the PDF fonts")]
+    UnsupportedText,
+
+    #[error("Report rendering task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+pub fn render(format: ExportFormat, export: &ReportExport) -> Result<Vec<u8>, ExportError> {
+    match format {
+        ExportFormat::Json => json::render(export),
+        ExportFormat::Csv => Ok(csv::render(export)),
+        ExportFormat::Pdf => pdf::render(export),
+    }
+}
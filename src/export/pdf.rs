@@ -0,0 +1,522 @@
+//! Printable A4 report. The document embeds subsets holding only the glyphs
+//! it draws, so it prints the same in any viewer without fetching fonts.
+//! Text is set in DejaVu Sans; characters it has no glyph for fall back to
+//! NanumBarunGothic, which covers Hangul. Neither has kana or kanji, so a
+//! report with Japanese or Chinese text is refused rather than printed with
+//! empty boxes; the JSON and CSV exports carry it intact.
+
+use std::collections::BTreeMap;
+
+use pdf_writer::{
+    types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap},
+    Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr,
+};
+use subsetter::GlyphRemapper;
+use ttf_parser::{Face, GlyphId};
+
+use crate::{
+    models::analysis::{AnalysisReport, FileReport, TermCount},
+    views::helpers::format_datetime,
+};
+
+use super::{ExportError, ReportExport};
+
+/// A TrueType font the report can embed a subset of.
+struct FontSource {
+    data: &'static [u8],
+    /// Subset fonts are tagged with six capital letters ahead of the real name.
+    base_name: Name<'static>,
+    resource: Name<'static>,
+}
+
+/// Tried in order for each character; the first font with a glyph draws it.
+const FONTS: [FontSource; 2] = [
+    FontSource {
+        data: include_bytes!("../../assets/fonts/DejaVuSans.ttf"),
+        base_name: Name(b"RPTSUB+DejaVuSans"),
+        resource: Name(b"F1"),
+    },
+    FontSource {
+        data: include_bytes!("../../assets/fonts/NanumBarunGothic.ttf"),
+        base_name: Name(b"RPTSUB+NanumBarunGothic"),
+        resource: Name(b"F2"),
+    },
+];
+const IDENTITY: SystemInfo = SystemInfo {
+    registry: Str(b"Adobe"),
+    ordering: Str(b"Identity"),
+    supplement: 0,
+};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const LINE_SPACING: f32 = 1.4;
+
+const TITLE_SIZE: f32 = 20.0;
+const HEADING_SIZE: f32 = 14.0;
+const SUBHEADING_SIZE: f32 = 11.0;
+const BODY_SIZE: f32 = 10.0;
+const FOOTER_SIZE: f32 = 8.0;
+
+const TEXT_GRAY: f32 = 0.0;
+const MUTED_GRAY: f32 = 0.4;
+const RULE_GRAY: f32 = 0.75;
+
+pub(super) fn render(export: &ReportExport) -> Result<Vec<u8>, ExportError> {
+    let mut writer = Writer::new()?;
+    if !writer.covers(&export.order_number.to_string()) || !export.files.iter().all(|file| file_covered(&writer, file)) {
+        return Err(ExportError::UnsupportedText);
+    }
+
+    writer.paragraph("Text Analysis Report", TITLE_SIZE, TEXT_GRAY);
+    let mut subtitle = format!("Order {}", export.order_number);
+    if let Some(paid_at) = export.paid_at {
+        subtitle.push_str(&format!(" · Paid {} UTC", format_datetime(paid_at)));
+    }
+    writer.paragraph(&subtitle, BODY_SIZE, MUTED_GRAY);
+
+    for file in &export.files {
+        writer.gap(BODY_SIZE * 2.0);
+        // Keep a heading together with at least the start of its figures.
+        writer.ensure_space(HEADING_SIZE * LINE_SPACING + BODY_SIZE * LINE_SPACING * 4.0);
+        writer.paragraph(&file.filename, HEADING_SIZE, TEXT_GRAY);
+        writer.rule();
+        report_body(&mut writer, &file.report);
+    }
+
+    writer.finish(&format!("Text Analysis Report {}", export.order_number))
+}
+
+/// Only the filename and the counted terms come from the upload; the rest is our own text.
+fn file_covered(writer: &Writer, file: &FileReport) -> bool {
+    let report = &file.report;
+    writer.covers(&file.filename)
+        && [&report.top_words, &report.top_bigrams, &report.top_trigrams]
+            .into_iter()
+            .flatten()
+            .all(|term| writer.covers(&term.term))
+}
+
+fn report_body(writer: &mut Writer, report: &AnalysisReport) {
+    let figures = [
+        ("Characters", report.character_count.to_string()),
+        ("Words", report.word_count.to_string()),
+        ("Sentences", report.sentence_count.to_string()),
+        ("Paragraphs", report.paragraph_count.to_string()),
+        ("Avg. word length", format!("{:.1}", report.average_word_length)),
+        ("Avg. words / sentence", format!("{:.1}", report.average_sentence_length)),
+        ("Reading time", format!("{} min", report.reading_time_minutes())),
+        (
+            "Readability (Flesch)",
+            format!("{:.0} · {}", report.flesch_reading_ease, report.readability_label()),
+        ),
+    ];
+    for (label, value) in &figures {
+        writer.row(label, value, BODY_SIZE);
+    }
+
+    term_table(writer, "Top words", &report.top_words);
+    term_table(writer, "Top phrases", &report.top_bigrams);
+    term_table(writer, "Top 3-word phrases", &report.top_trigrams);
+}
+
+fn term_table(writer: &mut Writer, title: &str, terms: &[TermCount]) {
+    writer.gap(BODY_SIZE);
+    writer.ensure_space(SUBHEADING_SIZE * LINE_SPACING + BODY_SIZE * LINE_SPACING * 2.0);
+    writer.paragraph(title, SUBHEADING_SIZE, TEXT_GRAY);
+
+    if terms.is_empty() {
+        writer.paragraph("None repeated", BODY_SIZE, MUTED_GRAY);
+    }
+    for (rank, term) in terms.iter().enumerate() {
+        writer.row(&format!("{}. {}", rank + 1, term.term), &term.count.to_string(), BODY_SIZE);
+    }
+}
+
+/// One embedded font and the subset drawn from it so far.
+struct Font {
+    source: &'static FontSource,
+    face: Face<'static>,
+    glyphs: GlyphRemapper,
+    /// The character each subset glyph was first drawn for, so viewers can
+    /// copy and search the text.
+    characters: BTreeMap<u16, char>,
+}
+
+impl Font {
+    fn new(source: &'static FontSource) -> Result<Self, ExportError> {
+        let face = Face::parse(source.data, 0).map_err(|e| ExportError::Pdf(format!("embedded font: {}", e)))?;
+        Ok(Self {
+            source,
+            face,
+            glyphs: GlyphRemapper::new(),
+            characters: BTreeMap::new(),
+        })
+    }
+
+    /// Fallback fonts are only embedded once something is drawn with them.
+    fn is_used(&self) -> bool {
+        !self.characters.is_empty()
+    }
+
+    fn advance(&self, glyph: GlyphId, size: f32) -> f32 {
+        f32::from(self.face.glyph_hor_advance(glyph).unwrap_or(0)) * size / f32::from(self.face.units_per_em())
+    }
+}
+
+/// Lays text out top to bottom over as many pages as it takes, remapping
+/// every glyph it draws into its font's subset as it goes.
+struct Writer {
+    fonts: Vec<Font>,
+    pages: Vec<Content>,
+    page: Content,
+    y: f32,
+}
+
+impl Writer {
+    fn new() -> Result<Self, ExportError> {
+        Ok(Self {
+            fonts: FONTS.iter().map(Font::new).collect::<Result<_, _>>()?,
+            pages: Vec::new(),
+            page: Content::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    /// The first font with a glyph for `c`, or the primary font's .notdef.
+    fn glyph(&self, c: char) -> (usize, GlyphId) {
+        self.fonts
+            .iter()
+            .enumerate()
+            .find_map(|(index, font)| font.face.glyph_index(c).map(|glyph| (index, glyph)))
+            .unwrap_or((0, GlyphId(0)))
+    }
+
+    /// Whether some font has a glyph for every visible character of `text`.
+    fn covers(&self, text: &str) -> bool {
+        text.chars()
+            .filter(|c| !c.is_whitespace() && !c.is_control())
+            .all(|c| self.fonts.iter().any(|font| font.face.glyph_index(c).is_some()))
+    }
+
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        text.chars()
+            .map(|c| {
+                let (font, glyph) = self.glyph(c);
+                self.fonts[font].advance(glyph, size)
+            })
+            .sum()
+    }
+
+    /// Words that fit on a line together; a word wider than a whole line is
+    /// broken between characters.
+    fn wrap(&self, text: &str, size: f32, width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+
+        for word in text.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if self.text_width(&candidate, size) <= width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                line.push(c);
+                if self.text_width(&line, size) > width && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, c.to_string()));
+                }
+            }
+        }
+        if !line.is_empty() || lines.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Splits `text` into runs set in the same font, two bytes per glyph: the
+    /// fonts are written with the Identity-H encoding.
+    fn encode(&mut self, text: &str) -> Vec<(Name<'static>, Vec<u8>)> {
+        let mut runs: Vec<(Name<'static>, Vec<u8>)> = Vec::new();
+        for c in text.chars() {
+            let (index, glyph) = self.glyph(c);
+            let font = &mut self.fonts[index];
+            let glyph = font.glyphs.remap(glyph.0);
+            // Every character no font has shares .notdef; it can't stand for any one of them.
+            if glyph != 0 {
+                font.characters.entry(glyph).or_insert(c);
+            }
+            match runs.last_mut() {
+                Some((resource, encoded)) if *resource == font.source.resource => {
+                    encoded.extend_from_slice(&glyph.to_be_bytes());
+                }
+                _ => runs.push((font.source.resource, glyph.to_be_bytes().to_vec())),
+            }
+        }
+        runs
+    }
+
+    fn draw_text(&mut self, x: f32, baseline: f32, size: f32, gray: f32, text: &str) {
+        let runs = self.encode(text);
+        show_text(&mut self.page, x, baseline, size, gray, &runs);
+    }
+
+    fn new_page(&mut self) {
+        let finished = std::mem::replace(&mut self.page, Content::new());
+        self.pages.push(finished);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn line(&mut self, size: f32) -> f32 {
+        let height = size * LINE_SPACING;
+        self.ensure_space(height);
+        self.y -= height;
+        self.y + (height - size)
+    }
+
+    fn paragraph(&mut self, text: &str, size: f32, gray: f32) {
+        for line in self.wrap(text, size, CONTENT_WIDTH) {
+            let baseline = self.line(size);
+            self.draw_text(MARGIN, baseline, size, gray, &line);
+        }
+    }
+
+    /// Label on the left, value right-aligned on the label's first line.
+    fn row(&mut self, label: &str, value: &str, size: f32) {
+        let value_width = self.text_width(value, size);
+        let label_width = CONTENT_WIDTH - value_width - size;
+
+        for (index, line) in self.wrap(label, size, label_width).iter().enumerate() {
+            let baseline = self.line(size);
+            self.draw_text(MARGIN, baseline, size, TEXT_GRAY, line);
+            if index == 0 {
+                self.draw_text(PAGE_WIDTH - MARGIN - value_width, baseline, size, MUTED_GRAY, value);
+            }
+        }
+    }
+
+    fn rule(&mut self) {
+        self.gap(BODY_SIZE * 0.4);
+        self.page
+            .set_stroke_gray(RULE_GRAY)
+            .set_line_width(0.5)
+            .move_to(MARGIN, self.y)
+            .line_to(PAGE_WIDTH - MARGIN, self.y)
+            .stroke();
+        self.gap(BODY_SIZE * 0.2);
+    }
+
+    fn finish(mut self, title: &str) -> Result<Vec<u8>, ExportError> {
+        self.new_page();
+        let pages = std::mem::take(&mut self.pages);
+        let page_count = pages.len();
+
+        let mut pages_with_footers = Vec::with_capacity(page_count);
+        for (index, mut page) in pages.into_iter().enumerate() {
+            let footer = format!("Page {} of {}", index + 1, page_count);
+            let runs = self.encode(&footer);
+            let x = PAGE_WIDTH - MARGIN - self.text_width(&footer, FOOTER_SIZE);
+            show_text(&mut page, x, MARGIN / 2.0, FOOTER_SIZE, MUTED_GRAY, &runs);
+            pages_with_footers.push(page);
+        }
+
+        let mut next_id = Ref::new(1);
+        let mut alloc = || next_id.bump();
+        let catalog_id = alloc();
+        let page_tree_id = alloc();
+        let info_id = alloc();
+        // Type0 font, CID font, descriptor, font file and ToUnicode map for each font drawn with.
+        let fonts: Vec<(&Font, [Ref; 5])> = self
+            .fonts
+            .iter()
+            .filter(|font| font.is_used())
+            .map(|font| (font, [alloc(), alloc(), alloc(), alloc(), alloc()]))
+            .collect();
+        let page_ids: Vec<(Ref, Ref)> = (0..page_count).map(|_| (alloc(), alloc())).collect();
+
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.document_info(info_id).title(TextStr(title));
+        pdf.pages(page_tree_id)
+            .kids(page_ids.iter().map(|(page_id, _)| *page_id))
+            .count(page_count as i32);
+
+        for ((page_id, content_id), content) in page_ids.iter().zip(pages_with_footers) {
+            let mut page = pdf.page(*page_id);
+            page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+            page.parent(page_tree_id);
+            page.contents(*content_id);
+            let mut resources = page.resources();
+            let mut font_resources = resources.fonts();
+            for (font, [type0_id, ..]) in &fonts {
+                font_resources.pair(font.source.resource, *type0_id);
+            }
+            font_resources.finish();
+            resources.finish();
+            page.finish();
+            pdf.stream(*content_id, &content.finish());
+        }
+
+        for (font, [type0_id, cid_font_id, descriptor_id, font_file_id, cmap_id]) in fonts {
+            font.write(&mut pdf, type0_id, cid_font_id, descriptor_id, font_file_id, cmap_id)?;
+        }
+        Ok(pdf.finish())
+    }
+}
+
+fn show_text(page: &mut Content, x: f32, baseline: f32, size: f32, gray: f32, runs: &[(Name, Vec<u8>)]) {
+    page.set_fill_gray(gray).begin_text().next_line(x, baseline);
+    for (resource, encoded) in runs {
+        page.set_font(*resource, size).show(Str(encoded));
+    }
+    page.end_text();
+}
+
+impl Font {
+    fn write(
+        &self,
+        pdf: &mut Pdf,
+        type0_id: Ref,
+        cid_font_id: Ref,
+        descriptor_id: Ref,
+        font_file_id: Ref,
+        cmap_id: Ref,
+    ) -> Result<(), ExportError> {
+        let base_name = self.source.base_name;
+        let subset = subsetter::subset(self.source.data, 0, &self.glyphs)
+            .map_err(|e| ExportError::Pdf(format!("font subsetting: {}", e)))?;
+        let scale = 1000.0 / f32::from(self.face.units_per_em());
+        let to_pdf_units = |units: i16| f32::from(units) * scale;
+
+        pdf.type0_font(type0_id)
+            .base_font(base_name)
+            .encoding_predefined(Name(b"Identity-H"))
+            .descendant_font(cid_font_id)
+            .to_unicode(cmap_id);
+
+        let widths: Vec<f32> = self
+            .glyphs
+            .remapped_gids()
+            .map(|old| f32::from(self.face.glyph_hor_advance(GlyphId(old)).unwrap_or(0)) * scale)
+            .collect();
+        let mut cid_font = pdf.cid_font(cid_font_id);
+        cid_font
+            .subtype(CidFontType::Type2)
+            .base_font(base_name)
+            .system_info(IDENTITY)
+            .font_descriptor(descriptor_id)
+            .cid_to_gid_map_predefined(Name(b"Identity"));
+        cid_font.widths().consecutive(0, widths);
+        cid_font.finish();
+
+        let bbox = self.face.global_bounding_box();
+        pdf.font_descriptor(descriptor_id)
+            .name(base_name)
+            .flags(FontFlags::SYMBOLIC)
+            .bbox(Rect::new(
+                to_pdf_units(bbox.x_min),
+                to_pdf_units(bbox.y_min),
+                to_pdf_units(bbox.x_max),
+                to_pdf_units(bbox.y_max),
+            ))
+            .italic_angle(self.face.italic_angle())
+            .ascent(to_pdf_units(self.face.ascender()))
+            .descent(to_pdf_units(self.face.descender()))
+            .cap_height(to_pdf_units(self.face.capital_height().unwrap_or(self.face.ascender())))
+            .stem_v(80.0)
+            .font_file2(font_file_id);
+
+        pdf.stream(font_file_id, &subset);
+
+        let mut cmap = UnicodeCmap::new(Name(b"Custom"), IDENTITY);
+        for (glyph, c) in &self.characters {
+            cmap.pair(*glyph, *c);
+        }
+        pdf.cmap(cmap_id, &cmap.finish());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::OrderNumber;
+
+    use super::*;
+
+    #[test]
+    fn test_render_pdf_spans_pages_and_embeds_subset() {
+        let report = crate::analysis::analyze("Alpha beta gamma. Alpha beta gamma. Delta epsilon alpha.");
+        let export = ReportExport {
+            order_number: OrderNumber::from("ORD-TEST".to_string()),
+            paid_at: None,
+            files: (0..6)
+                .map(|index| FileReport {
+                    filename: format!("chapter-{}.txt", index),
+                    report: report.clone(),
+                })
+                .collect(),
+        };
+
+        let bytes = render(&export).unwrap();
+        assert!(bytes.starts_with(b"%PDF-"));
+        assert!(bytes.len() < FONTS[0].data.len() / 4, "font should be subset, got {} bytes", bytes.len());
+        assert!(!contains(&bytes, FONTS[1].base_name.0), "unused fallback font should not be embedded");
+
+        let text = pdf_extract::extract_text_from_mem(&bytes).unwrap();
+        assert!(text.contains("chapter-5.txt"));
+        assert!(text.contains("Page 2 of"));
+    }
+
+    #[test]
+    fn test_render_pdf_sets_hangul_in_fallback_font() {
+        let export = ReportExport {
+            order_number: OrderNumber::from("ORD-TEST".to_string()),
+            paid_at: None,
+            files: vec![FileReport {
+                filename: "보고서.txt".to_string(),
+                report: crate::analysis::analyze("한국어 문장입니다. 한국어 문장입니다."),
+            }],
+        };
+
+        let bytes = render(&export).unwrap();
+        assert!(contains(&bytes, FONTS[1].base_name.0));
+        assert!(bytes.len() < FONTS[1].data.len() / 4, "font should be subset, got {} bytes", bytes.len());
+
+        let text = pdf_extract::extract_text_from_mem(&bytes).unwrap();
+        assert!(text.contains("보고서.txt"), "extracted: {}", text);
+        assert!(text.contains("한국어"));
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+
+    #[test]
+    fn test_render_pdf_refuses_text_no_font_covers() {
+        let export = ReportExport {
+            order_number: OrderNumber::from("ORD-TEST".to_string()),
+            paid_at: None,
+            files: vec![FileReport {
+                filename: "report.txt".to_string(),
+                report: crate::analysis::analyze("日本語の文章です。日本語の文章です。"),
+            }],
+        };
+
+        assert!(matches!(render(&export), Err(ExportError::UnsupportedText)));
+    }
+}
@@ -0,0 +1,120 @@
+use std::io::{Cursor, Read};
+
+use quick_xml::{events::Event, Reader};
+use zip::ZipArchive;
+
+use super::{ExtractError, FileKind};
+use crate::constants::file_upload::MAX_EXTRACTED_BYTES;
+
+const DOCUMENT_PART: &str = "word/document.xml";
+
+/// `None` if the bytes aren't a readable zip archive at all.
+pub(super) fn is_docx(bytes: &[u8]) -> Option<bool> {
+    let archive = ZipArchive::new(Cursor::new(bytes)).ok()?;
+    Some(archive.index_for_name(DOCUMENT_PART).is_some())
+}
+
+pub(super) fn extract(bytes: &[u8]) -> Result<String, ExtractError> {
+    let xml = read_document_part(bytes)?;
+    document_text(&xml).map_err(|e| {
+        tracing::warn!("Malformed DOCX document XML: {}", e);
+        ExtractError::Corrupt(FileKind::Docx)
+    })
+}
+
+/// Reads the main document part, refusing to inflate more than
+/// `MAX_EXTRACTED_BYTES` so a zip bomb can't exhaust memory.
+fn read_document_part(bytes: &[u8]) -> Result<String, ExtractError> {
+    let corrupt = |e: &dyn std::fmt::Display| {
+        tracing::warn!("Failed to read DOCX archive: {}", e);
+        ExtractError::Corrupt(FileKind::Docx)
+    };
+
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| corrupt(&e))?;
+    let part = archive.by_name(DOCUMENT_PART).map_err(|e| corrupt(&e))?;
+
+    let mut xml = String::new();
+    part.take(MAX_EXTRACTED_BYTES as u64 + 1)
+        .read_to_string(&mut xml)
+        .map_err(|e| corrupt(&e))?;
+    if xml.len() > MAX_EXTRACTED_BYTES {
+        return Err(ExtractError::TooLarge(FileKind::Docx));
+    }
+    Ok(xml)
+}
+
+/// Text runs (`w:t`), tabs and breaks in document order; each paragraph
+/// (`w:p`) ends with a blank line.
+fn document_text(xml: &str) -> Result<String, quick_xml::Error> {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::with_capacity(xml.len() / 4);
+    let mut in_text_run = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(tag) if tag.local_name().as_ref() == "t" => in_text_run = true,
+            Event::End(tag) => match tag.local_name().as_ref() {
+                "t" => in_text_run = false,
+                "p" => text.push_str("\n\n"),
+                _ => {}
+            },
+            Event::Empty(tag) => match tag.local_name().as_ref() {
+                "tab" => text.push('\t'),
+                "br" | "cr" => text.push('\n'),
+                "p" => text.push_str("\n\n"),
+                _ => {}
+            },
+            Event::Text(content) if in_text_run => text.push_str(&content.xml10_content()),
+            Event::CData(content) if in_text_run => text.push_str(&content.xml10_content()),
+            Event::GeneralRef(reference) if in_text_run => {
+                if let Some(c) = reference.resolve_char_ref()? {
+                    text.push(c);
+                } else if let Some(resolved) = quick_xml::escape::resolve_predefined_entity(&reference.xml10_content()) {
+                    text.push_str(resolved);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+
+    fn docx_with_body(body: &str) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file(DOCUMENT_PART, SimpleFileOptions::default()).unwrap();
+        write!(
+            writer,
+            r#"<?xml version="1.0" encoding="UTF-8"?><w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{body}</w:body></w:document>"#
+        )
+        .unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_paragraphs_tabs_and_entities() {
+        let bytes = docx_with_body(
+            "<w:p><w:r><w:t>Fish &amp; chips</w:t><w:tab/><w:t xml:space=\"preserve\"> &#8212; cheap</w:t></w:r></w:p>\
+             <w:p><w:r><w:t>Second</w:t><w:br/><w:t>line</w:t></w:r></w:p>",
+        );
+        assert_eq!(is_docx(&bytes), Some(true));
+        assert_eq!(extract(&bytes).unwrap(), "Fish & chips\t — cheap\n\nSecond\nline\n\n");
+    }
+
+    #[test]
+    fn test_is_docx_rejects_other_archives() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file("notes.txt", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"hello").unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+        assert_eq!(is_docx(&bytes), Some(false));
+    }
+}
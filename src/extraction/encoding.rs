@@ -0,0 +1,118 @@
+//! Charset detection for text uploads. Checked in order: byte order mark,
+//! BOM-less UTF-16, strict UTF-8, then a statistical guess among legacy
+//! encodings (EUC-KR/CP949, Shift_JIS, …).
+
+use std::borrow::Cow;
+
+use chardetng::{EncodingDetector, Iso2022JpDetection, Utf8Detection};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+use super::ExtractError;
+
+/// BOM-less UTF-16 is guessed from where the NUL bytes fall in this much input.
+const UTF16_SAMPLE_BYTES: usize = 4096;
+/// Share of code units whose high byte is NUL before text counts as UTF-16.
+/// Hangul has no NUL bytes, but spaces, digits and line breaks in Korean text do.
+const UTF16_MIN_NUL_RATIO: f64 = 0.1;
+/// The other side may have at most a quarter as many NULs.
+const UTF16_MAX_OTHER_SIDE_SHARE: usize = 4;
+
+pub(super) struct Decoded<'a> {
+    pub text: Cow<'a, str>,
+    pub encoding: &'static Encoding,
+}
+
+pub(super) fn decode(bytes: &[u8]) -> Result<Decoded<'_>, ExtractError> {
+    let (encoding, bom_length) = Encoding::for_bom(bytes)
+        .or_else(|| guess_utf16(bytes).map(|encoding| (encoding, 0)))
+        .unwrap_or_else(|| (guess_legacy(bytes), 0));
+
+    let text = encoding
+        .decode_without_bom_handling_and_without_replacement(&bytes[bom_length..])
+        .ok_or(ExtractError::Undecodable(encoding.name()))?;
+
+    Ok(Decoded { text, encoding })
+}
+
+/// UTF-16 text has NUL bytes on one side of each code unit (ASCII, line
+/// breaks) and almost none on the other.
+fn guess_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(UTF16_SAMPLE_BYTES) & !1];
+    let units = sample.len() / 2;
+    if units == 0 {
+        return None;
+    }
+
+    let (mut even_nuls, mut odd_nuls) = (0usize, 0usize);
+    for unit in sample.chunks_exact(2) {
+        even_nuls += usize::from(unit[0] == 0);
+        odd_nuls += usize::from(unit[1] == 0);
+    }
+
+    // A few low bytes are legitimately NUL too (U+AC00 "가" is AC 00).
+    let dominant = |nuls: usize, other: usize| {
+        nuls as f64 / units as f64 >= UTF16_MIN_NUL_RATIO && other * UTF16_MAX_OTHER_SIDE_SHARE <= nuls
+    };
+    if dominant(odd_nuls, even_nuls) {
+        Some(UTF_16LE)
+    } else if dominant(even_nuls, odd_nuls) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+fn guess_legacy(bytes: &[u8]) -> &'static Encoding {
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+    let mut detector = EncodingDetector::new(Iso2022JpDetection::Deny);
+    detector.feed(bytes, true);
+    detector.guess(None, Utf8Detection::Allow)
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{EUC_KR, SHIFT_JIS};
+
+    use super::*;
+
+    const KOREAN: &str = "안녕하세요. 오늘은 날씨가 정말 좋습니다. 우리는 공원에서 산책을 하고 점심을 먹었습니다.";
+    const JAPANESE: &str = "こんにちは。今日はとても良い天気ですね。公園で散歩をして、昼ご飯を食べました。";
+
+    fn encode(encoding: &'static Encoding, text: &str) -> Vec<u8> {
+        encoding.encode(text).0.into_owned()
+    }
+
+    fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|unit| if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() })
+            .collect()
+    }
+
+    fn decoded(bytes: &[u8]) -> (&'static Encoding, String) {
+        let decoded = decode(bytes).unwrap();
+        (decoded.encoding, decoded.text.into_owned())
+    }
+
+    #[test]
+    fn test_decode_legacy_encodings() {
+        assert_eq!(decoded(&encode(EUC_KR, KOREAN)), (EUC_KR, KOREAN.to_string()));
+        assert_eq!(decoded(&encode(SHIFT_JIS, JAPANESE)), (SHIFT_JIS, JAPANESE.to_string()));
+    }
+
+    #[test]
+    fn test_decode_utf16_with_and_without_bom() {
+        let mut with_bom = vec![0xFF, 0xFE];
+        with_bom.extend(utf16(KOREAN, true));
+        assert_eq!(decoded(&with_bom), (UTF_16LE, KOREAN.to_string()));
+        assert_eq!(decoded(&utf16(KOREAN, false)), (UTF_16BE, KOREAN.to_string()));
+    }
+
+    #[test]
+    fn test_decode_utf8_borrows_input() {
+        let decoded = decode("plain ascii, 그리고 한글".as_bytes()).unwrap();
+        assert_eq!(decoded.encoding, UTF_8);
+        assert!(matches!(decoded.text, Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,56 @@
+use scraper::{ElementRef, Html, Node};
+
+/// Elements whose content is never visible text.
+const SKIPPED_ELEMENTS: &[&str] = &["script", "style", "noscript", "template", "head"];
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "br", "dd", "div", "dl", "dt", "figcaption", "footer",
+    "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "li", "main", "nav", "ol", "p", "pre", "section",
+    "table", "td", "th", "tr", "ul",
+];
+
+pub(super) fn extract(source: &str) -> String {
+    let document = Html::parse_document(source);
+    let mut text = String::with_capacity(source.len() / 2);
+    collect_text(document.root_element(), &mut text);
+    text
+}
+
+fn collect_text(element: ElementRef<'_>, text: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(content) => text.push_str(content),
+            Node::Element(tag) => {
+                let name = tag.name();
+                if SKIPPED_ELEMENTS.contains(&name) {
+                    continue;
+                }
+                let is_block = BLOCK_ELEMENTS.contains(&name);
+                if is_block {
+                    text.push_str("\n\n");
+                }
+                if let Some(child) = ElementRef::wrap(child) {
+                    collect_text(child, text);
+                }
+                if is_block {
+                    text.push_str("\n\n");
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_skips_scripts_and_separates_blocks() {
+        let text = extract(
+            "<html><head><title>Ignored</title><style>p { color: red }</style></head>\
+             <body><h1>Title</h1><p>First &amp; <b>bold</b></p><script>alert(1)</script><p>Second</p></body></html>",
+        );
+        let paragraphs: Vec<&str> = text.split("\n\n").filter(|p| !p.trim().is_empty()).collect();
+        assert_eq!(paragraphs, vec!["Title", "First & bold", "Second"]);
+    }
+}
@@ -0,0 +1,36 @@
+use pulldown_cmark::{Event, Parser, TagEnd};
+
+/// Drops markup but keeps link text and code; inline HTML is ignored.
+pub(super) fn extract(source: &str) -> String {
+    let mut text = String::with_capacity(source.len());
+
+    for event in Parser::new(source) {
+        match event {
+            Event::Text(content) | Event::Code(content) => text.push_str(&content),
+            Event::SoftBreak => text.push(' '),
+            Event::HardBreak => text.push('\n'),
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::CodeBlock
+                | TagEnd::Item
+                | TagEnd::TableRow
+                | TagEnd::TableHead,
+            ) => text.push_str("\n\n"),
+            Event::End(TagEnd::TableCell) => text.push(' '),
+            _ => {}
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_strips_markup() {
+        let text = extract("# Title\n\nSome *emphasis* and [a link](https://example.com).\n\n- one\n- `two`\n");
+        assert_eq!(text, "Title\n\nSome emphasis and a link.\n\none\n\ntwo\n\n");
+    }
+}
@@ -0,0 +1,260 @@
+//! Plain-text extraction for analyzer uploads. The file type is detected from
+//! the content, not the filename, and pricing is based on the extracted text.
+
+mod docx;
+mod encoding;
+mod html;
+mod markdown;
+mod pdf;
+
+use std::fmt;
+
+use encoding::Decoded;
+
+const PDF_MAGIC: &[u8] = b"%PDF-";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const OLE_MAGIC: &[u8] = b"\xD0\xCF\x11\xE0";
+/// How much of a text file to look at when telling HTML and Markdown apart.
+const SNIFF_BYTES: usize = 1024;
+/// At most one control character per this many characters.
+const MAX_CONTROL_CHAR_SHARE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    PlainText,
+    Markdown,
+    Html,
+    Docx,
+    Pdf,
+}
+
+impl fmt::Display for FileKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::PlainText => "plain text",
+            Self::Markdown => "Markdown",
+            Self::Html => "HTML",
+            Self::Docx => "DOCX",
+            Self::Pdf => "PDF",
+        })
+    }
+}
+
+impl FileKind {
+    /// The upload extensions that name this kind, as pricing surcharges are keyed.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::PlainText => &["txt"],
+            Self::Markdown => &["md", "markdown"],
+            Self::Html => &["html", "htm"],
+            Self::Docx => &["docx"],
+            Self::Pdf => &["pdf"],
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExtractError {
+    #[error("Legacy Word (.doc) files are not supported. Save the document as .docx and upload it again.")]
+    LegacyWord,
+
+    #[error("This archive is not a Word document. Upload a .docx, .pdf, .md, .html or .txt file.")]
+    UnsupportedArchive,
+
+    #[error("Unsupported file type. Upload a .docx, .pdf, .md, .html or .txt file.")]
+    Unsupported,
+
+    #[error("The file looks like {0} text but contains invalid characters. Save it as UTF-8 and upload it again.")]
+    Undecodable(&'static str),
+
+    #[error("The file could not be read. Try saving it in another format.")]
+    Unreadable,
+
+    #[error("The {0} file appears to be corrupt and could not be read.")]
+    Corrupt(FileKind),
+
+    #[error("The {0} file is encrypted. Save a copy without a password or restrictions and upload it again.")]
+    Encrypted(FileKind),
+
+    #[error("The {0} file contains more text than can be analyzed.")]
+    TooLarge(FileKind),
+
+    #[error("No text could be found in the {0} file. Scanned documents are not supported.")]
+    NoText(FileKind),
+}
+
+#[derive(Debug)]
+pub struct Extracted {
+    pub kind: FileKind,
+    /// Charset the upload was decoded from; `None` for DOCX and PDF, which
+    /// carry their own encoding.
+    pub encoding: Option<&'static str>,
+    pub text: String,
+}
+
+/// Detects the file type and extracts its text. CPU-bound; call it from
+/// `spawn_blocking`.
+pub fn extract(bytes: &[u8]) -> Result<Extracted, ExtractError> {
+    let (kind, decoded) = classify(bytes)?;
+
+    let raw = match (kind, &decoded) {
+        (FileKind::Pdf, _) => pdf::extract(bytes)?,
+        (FileKind::Docx, _) => docx::extract(bytes)?,
+        (FileKind::Html, Some(decoded)) => html::extract(&decoded.text),
+        (FileKind::Markdown, Some(decoded)) => markdown::extract(&decoded.text),
+        (FileKind::PlainText, Some(decoded)) => decoded.text.to_string(),
+        (_, None) => unreachable!("text kinds are always classified with decoded text"),
+    };
+
+    let text = normalize_whitespace(&raw);
+    if text.is_empty() {
+        return Err(ExtractError::NoText(kind));
+    }
+    Ok(Extracted {
+        kind,
+        encoding: decoded.map(|decoded| decoded.encoding.name()),
+        text,
+    })
+}
+
+/// Binary formats by magic bytes; anything else must decode as text, which is
+/// then sniffed for HTML or Markdown.
+fn classify(bytes: &[u8]) -> Result<(FileKind, Option<Decoded<'_>>), ExtractError> {
+    if bytes.starts_with(PDF_MAGIC) {
+        return Ok((FileKind::Pdf, None));
+    }
+    if bytes.starts_with(ZIP_MAGIC) {
+        return match docx::is_docx(bytes) {
+            Some(true) => Ok((FileKind::Docx, None)),
+            Some(false) => Err(ExtractError::UnsupportedArchive),
+            None => Err(ExtractError::Corrupt(FileKind::Docx)),
+        };
+    }
+    if bytes.starts_with(OLE_MAGIC) {
+        return Err(ExtractError::LegacyWord);
+    }
+
+    let decoded = encoding::decode(bytes)?;
+    if !looks_like_text(&decoded.text) {
+        return Err(ExtractError::Unsupported);
+    }
+
+    let kind = if looks_like_html(&head_lowercase(&decoded.text)) {
+        FileKind::Html
+    } else if looks_like_markdown(&decoded.text) {
+        FileKind::Markdown
+    } else {
+        FileKind::PlainText
+    };
+    Ok((kind, Some(decoded)))
+}
+
+/// Binary data can decode without error (as UTF-16 in particular); real text
+/// has no NULs and hardly any other control characters.
+fn looks_like_text(text: &str) -> bool {
+    let mut chars = 0usize;
+    let mut controls = 0usize;
+    for c in text.chars() {
+        if c == '\0' {
+            return false;
+        }
+        chars += 1;
+        controls += usize::from(c.is_control() && !matches!(c, '\t' | '\n' | '\r' | '\x0C'));
+    }
+    controls * MAX_CONTROL_CHAR_SHARE <= chars
+}
+
+fn head_lowercase(text: &str) -> String {
+    let end = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| i >= SNIFF_BYTES)
+        .unwrap_or(text.len());
+    text[..end].trim_start().to_lowercase()
+}
+
+fn looks_like_html(head: &str) -> bool {
+    head.starts_with("<!doctype html")
+        || head.starts_with("<html")
+        || head.contains("<body")
+        || head.contains("<head")
+}
+
+/// Needs at least two Markdown constructs, so prose with a stray "#" stays plain text.
+fn looks_like_markdown(text: &str) -> bool {
+    let mut signals = 0;
+    for line in text.lines().take(200) {
+        let line = line.trim_start();
+        let is_heading = line.starts_with('#')
+            && line.trim_start_matches('#').starts_with(' ')
+            && line.len() - line.trim_start_matches('#').len() <= 6;
+        let is_fence = line.starts_with("```") || line.starts_with("~~~");
+        let is_link = line.contains("](");
+        signals += [is_heading, is_fence, is_link].into_iter().filter(|s| *s).count();
+        if signals >= 2 {
+            return true;
+        }
+    }
+    false
+}
+
+/// Collapses runs of spaces within lines and keeps at most one blank line
+/// between paragraphs, so extraction artifacts don't inflate the price.
+fn normalize_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_pending = false;
+
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank_pending = !out.is_empty();
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank_pending { "\n\n" } else { "\n" });
+        }
+        out.push_str(&line);
+        blank_pending = false;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(bytes: &[u8]) -> Result<FileKind, ExtractError> {
+        classify(bytes).map(|(kind, _)| kind)
+    }
+
+    #[test]
+    fn test_detect_by_content() {
+        assert_eq!(detect(b"%PDF-1.7\n...").unwrap(), FileKind::Pdf);
+        assert_eq!(detect(b"  <!DOCTYPE html><html></html>").unwrap(), FileKind::Html);
+        assert_eq!(detect(b"# Title\n\nSee [docs](https://example.com).").unwrap(), FileKind::Markdown);
+        assert_eq!(detect(b"Issue #42 was fixed.\nNothing else.").unwrap(), FileKind::PlainText);
+        assert!(matches!(detect(b"\xD0\xCF\x11\xE0\xA1\xB1"), Err(ExtractError::LegacyWord)));
+        assert!(matches!(detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x01\0"), Err(ExtractError::Unsupported)));
+        assert!(matches!(detect(b"PK\x03\x04garbage"), Err(ExtractError::Corrupt(FileKind::Docx))));
+    }
+
+    #[test]
+    fn test_extract_records_source_encoding() {
+        let euc_kr = b"\xC7\xD1\xB1\xDB \xC5\xD8\xBD\xBA\xC6\xAE\xC0\xD4\xB4\xCF\xB4\xD9.";
+        let extracted = extract(euc_kr).unwrap();
+        assert_eq!((extracted.encoding, extracted.text.as_str()), (Some("EUC-KR"), "한글 텍스트입니다."));
+        assert_eq!(extract(b"%PDF-1.4 broken").unwrap_err().to_string(), ExtractError::Corrupt(FileKind::Pdf).to_string());
+    }
+
+    #[test]
+    fn test_extract_plain_text_normalizes_whitespace() {
+        let extracted = extract(b"\xEF\xBB\xBFHello   world\n\n\n\n  second\tline \n").unwrap();
+        assert_eq!(extracted.kind, FileKind::PlainText);
+        assert_eq!(extracted.text, "Hello world\n\nsecond line");
+    }
+
+    #[test]
+    fn test_extract_rejects_empty_documents() {
+        assert!(matches!(extract(b"<html><body> </body></html>"), Err(ExtractError::NoText(FileKind::Html))));
+    }
+}
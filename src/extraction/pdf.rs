@@ -0,0 +1,233 @@
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    io::{self, Read, Write},
+    panic::{self, AssertUnwindSafe},
+};
+
+use flate2::read::{DeflateDecoder, ZlibDecoder};
+use pdf_extract::{Dictionary, Document, LoadOptions, Object, ObjectId, OutputError, PlainTextOutput, Stream};
+
+use super::{ExtractError, FileKind};
+use crate::constants::file_upload::{MAX_EXTRACTED_BYTES, MAX_PDF_DECODED_BYTES};
+
+const ENCRYPT_KEY: &[u8] = b"/Encrypt";
+
+thread_local! {
+    /// Decoded bytes the PDF being loaded on this thread may still expand to;
+    /// `None` once it went over. A load filter is a plain `fn`, so this is the
+    /// only way to carry the count across streams. lopdf runs the filter on the
+    /// loading thread as long as its `rayon` feature is off.
+    static DECODE_BUDGET: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Compressed streams can expand far past the upload size, so each stream is
+/// decoded into a counter as lopdf reads it, before anything is decompressed
+/// for real, and the file is refused once they add up to more than
+/// `MAX_PDF_DECODED_BYTES`. The text pdf-extract writes is cut off at
+/// `MAX_EXTRACTED_BYTES` like a DOCX's XML. Encrypted files are refused: lopdf
+/// decrypts and unpacks them without the filter, so they can't be measured.
+///
+/// pdf-extract panics on some malformed files instead of returning an error.
+pub(super) fn extract(bytes: &[u8]) -> Result<String, ExtractError> {
+    if bytes.windows(ENCRYPT_KEY.len()).any(|window| window == ENCRYPT_KEY) {
+        return Err(ExtractError::Encrypted(FileKind::Pdf));
+    }
+
+    let mut text = CappedText::default();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| -> Result<bool, OutputError> {
+        let Some(document) = load_within_budget(bytes)? else {
+            return Ok(false);
+        };
+        pdf_extract::output_doc(&document, &mut PlainTextOutput::new(&mut text as &mut dyn Write))?;
+        Ok(true)
+    }));
+
+    match result {
+        Ok(Ok(false)) => {
+            tracing::warn!("PDF streams decode to more than the decoding limit");
+            Err(ExtractError::TooLarge(FileKind::Pdf))
+        }
+        _ if text.over_limit => {
+            tracing::warn!("PDF text is over the extraction limit");
+            Err(ExtractError::TooLarge(FileKind::Pdf))
+        }
+        Ok(Ok(true)) => String::from_utf8(text.bytes).map_err(|_| ExtractError::Corrupt(FileKind::Pdf)),
+        Ok(Err(e)) => {
+            tracing::warn!("Failed to extract PDF text: {}", e);
+            Err(ExtractError::Corrupt(FileKind::Pdf))
+        }
+        Err(_) => {
+            tracing::warn!("PDF text extraction panicked");
+            Err(ExtractError::Corrupt(FileKind::Pdf))
+        }
+    }
+}
+
+/// `None` when the streams would decode to more than the budget.
+fn load_within_budget(bytes: &[u8]) -> Result<Option<Document>, OutputError> {
+    DECODE_BUDGET.set(Some(MAX_PDF_DECODED_BYTES));
+    let loaded = Document::load_mem_with_options(bytes, LoadOptions::with_filter(measure_stream));
+    // Once the filter starts dropping objects the load may fail as well.
+    if DECODE_BUDGET.take().is_none() {
+        return Ok(None);
+    }
+    Ok(Some(loaded?))
+}
+
+/// Load filter: charges each stream's decoded size to the budget, and drops
+/// everything once it has run out so the rest of the load does no real work.
+fn measure_stream(id: ObjectId, object: &mut Object) -> Option<(ObjectId, Object)> {
+    let remaining = DECODE_BUDGET.get()?;
+    if let Object::Stream(stream) = object {
+        let Some(decoded) = decoded_len(stream, remaining) else {
+            DECODE_BUDGET.set(None);
+            return None;
+        };
+        DECODE_BUDGET.set(Some(remaining - decoded));
+        // lopdf keeps the object it passed in and only uses the returned one for
+        // objects unpacked from object streams, which are never streams.
+        return Some((id, Object::Null));
+    }
+    Some((id, object.clone()))
+}
+
+/// How much the stream expands to when its filters run in order, as lopdf
+/// would run them, or `None` past `limit`. Filters lopdf can't decode leave the
+/// stream as it is. LZW can't be decoded with a bound here, so it counts as
+/// over; it is rare outside very old files.
+fn decoded_len(stream: &Stream, limit: usize) -> Option<usize> {
+    let Ok(filters) = stream.filters() else {
+        return Some(0);
+    };
+
+    let mut data = Cow::Borrowed(stream.content.as_slice());
+    let mut total = 0;
+    for (index, filter) in filters.iter().enumerate() {
+        let last = index == filters.len() - 1;
+        let remaining = limit - total;
+        let decoded = match *filter {
+            b"FlateDecode" if last => return inflated_len(&data, remaining).map(|len| total + len),
+            b"FlateDecode" => inflate(&data, remaining)?,
+            b"ASCII85Decode" => {
+                let mut dict = Dictionary::new();
+                dict.set("Filter", Object::Name(filter.to_vec()));
+                let Ok(decoded) = Stream::new(dict, data.into_owned()).decompressed_content() else {
+                    return Some(total);
+                };
+                decoded
+            }
+            b"LZWDecode" => return None,
+            _ => return Some(total),
+        };
+        total += decoded.len();
+        if total > limit {
+            return None;
+        }
+        data = Cow::Owned(decoded);
+    }
+    Some(total)
+}
+
+/// Inflates into a counter. Like lopdf, falls back to raw deflate past the
+/// two-byte zlib header when the zlib stream yields nothing.
+fn inflated_len(data: &[u8], limit: usize) -> Option<usize> {
+    let cap = limit as u64 + 1;
+    let mut counter = ByteCounter::default();
+    let result = io::copy(&mut ZlibDecoder::new(data).take(cap), &mut counter);
+    if result.is_err() && counter.0 == 0 && data.len() > 2 {
+        let _ = io::copy(&mut DeflateDecoder::new(&data[2..]).take(cap), &mut counter);
+    }
+    (counter.0 <= limit).then_some(counter.0)
+}
+
+/// [`inflated_len`], keeping the output for the next filter.
+fn inflate(data: &[u8], limit: usize) -> Option<Vec<u8>> {
+    let cap = limit as u64 + 1;
+    let mut output = Vec::new();
+    let result = ZlibDecoder::new(data).take(cap).read_to_end(&mut output);
+    if result.is_err() && output.is_empty() && data.len() > 2 {
+        let _ = DeflateDecoder::new(&data[2..]).take(cap).read_to_end(&mut output);
+    }
+    (output.len() <= limit).then_some(output)
+}
+
+#[derive(Default)]
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Extracted text that refuses to grow past `MAX_EXTRACTED_BYTES`; the failed
+/// write ends pdf-extract's run instead of letting it finish the document.
+#[derive(Default)]
+struct CappedText {
+    bytes: Vec<u8>,
+    over_limit: bool,
+}
+
+impl Write for CappedText {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.bytes.len() + buf.len() > MAX_EXTRACTED_BYTES {
+            self.over_limit = true;
+            return Err(io::Error::other("extracted text is over the limit"));
+        }
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::{write::ZlibEncoder, Compression};
+
+    use super::*;
+
+    fn flate_stream(len: usize) -> Stream {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&vec![b' '; len]).unwrap();
+        let mut dict = Dictionary::new();
+        dict.set("Filter", Object::Name(b"FlateDecode".to_vec()));
+        Stream::new(dict, encoder.finish().unwrap())
+    }
+
+    #[test]
+    fn test_decoded_len_counts_flate_output() {
+        assert_eq!(decoded_len(&flate_stream(4096), 4096), Some(4096));
+        assert_eq!(decoded_len(&flate_stream(4097), 4096), None);
+    }
+
+    #[test]
+    fn test_decoded_len_follows_filter_chains() {
+        let mut stream = flate_stream(4096);
+        let inner = stream.content.clone();
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&inner).unwrap();
+        stream.set_content(encoder.finish().unwrap());
+        stream.dict.set(
+            "Filter",
+            Object::Array(vec![Object::Name(b"FlateDecode".to_vec()), Object::Name(b"FlateDecode".to_vec())]),
+        );
+
+        assert_eq!(decoded_len(&stream, 4096 + inner.len()), Some(4096 + inner.len()));
+        assert_eq!(decoded_len(&stream, 4096), None);
+    }
+
+    #[test]
+    fn test_encrypted_pdfs_are_refused() {
+        let bytes = b"%PDF-1.7\ntrailer << /Encrypt 5 0 R >>";
+        assert!(matches!(extract(bytes), Err(ExtractError::Encrypted(FileKind::Pdf))));
+    }
+}
@@ -3,7 +3,7 @@ use serde::Deserialize;
 use tower_sessions::Session;
 
 use crate::{
-    auth::SESSION_USER_ID_KEY,
+    auth::service,
     constants::messages,
     data::commands,
     session::FlashMessage,
@@ -33,8 +33,7 @@ pub async fn get_actions_auth_verify(
 
     let user_id = commands::user::get_or_create_user(&email).await?;
 
-    session.flush().await?;
-    session.insert(SESSION_USER_ID_KEY, user_id).await?;
+    service::sign_in(&session, user_id).await?;
 
     Ok(FlashMessage::success(messages::SIGNED_IN)
         .set_and_redirect(&session, paths::pages::ROOT)
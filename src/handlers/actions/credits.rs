@@ -0,0 +1,138 @@
+use axum::{Extension, Form, extract::{Query, State}};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+use crate::{
+    auth::CurrentUser,
+    config::AppConfig,
+    constants::{errors, messages},
+    data::{commands, errors::DataError, queries},
+    email,
+    session::FlashMessage,
+    handlers::errors::HandlerResult,
+    models::{OrderId, OrderNumber, order::PaymentStatus},
+    paths,
+    payment::{confirm_or_look_up, ConfirmOutcome, ConfirmRequest, SharedPaymentGateway},
+};
+
+#[derive(Deserialize)]
+pub struct CreditPayForm {
+    order_id: String,
+}
+
+pub async fn post_actions_credits_pay(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<CreditPayForm>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let order_id = OrderId::parse_or_not_found(&form.order_id, errors::ORDER_NOT_FOUND)?;
+
+    let order = queries::order::get_order_for_user(&order_id, user_id).await?;
+    let quote_path = paths::helpers::quote_path(&order.id);
+
+    if !matches!(order.payment_status, PaymentStatus::Pending) {
+        return Ok(FlashMessage::error(messages::ORDER_ALREADY_PROCESSED)
+            .set_and_redirect(&session, &quote_path)
+            .await?);
+    }
+
+    if queries::credit::get_credit_balance(user_id).await? < order.price_amount {
+        return Ok(FlashMessage::error(errors::INSUFFICIENT_CREDITS)
+            .set_and_redirect(&session, &quote_path)
+            .await?);
+    }
+
+    // The balance check above can go stale; the debit repeats it atomically.
+    if !commands::credit::pay_order_with_credits(&order.id, user_id).await? {
+        return Ok(FlashMessage::error(errors::CREDIT_PAYMENT_FAILED)
+            .set_and_redirect(&session, &quote_path)
+            .await?);
+    }
+    tracing::info!("Order {} paid with ₩{} of credit", order.order_number, order.price_amount);
+
+    let order = queries::order::get_order_for_user(&order.id, user_id).await?;
+    if let Err(e) = email::send_payment_update(config.email(), &order).await {
+        tracing::error!("Failed to send payment email for order {}: {}", order.order_number, e);
+    }
+
+    Ok(FlashMessage::success(messages::PAID_WITH_CREDITS)
+        .set_and_redirect(&session, &paths::helpers::payment_confirmation_path(&order.id))
+        .await?)
+}
+
+#[derive(Deserialize)]
+pub struct CreditVerifyQuery {
+    #[serde(rename = "orderId")]
+    order_number: OrderNumber,
+    #[serde(rename = "paymentKey")]
+    payment_key: String,
+    amount: i32,
+}
+
+async fn redirect_with_error(session: &Session) -> HandlerResult {
+    Ok(FlashMessage::error(messages::PAYMENT_FAILED)
+        .set_and_redirect(session, paths::pages::DASHBOARD)
+        .await?)
+}
+
+pub async fn get_actions_credits_verify(
+    State(gateway): State<SharedPaymentGateway>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Query(query): Query<CreditVerifyQuery>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let top_up = queries::credit::get_top_up_by_order_number_for_user(&query.order_number, user_id).await?;
+
+    // The webhook may have settled it before the customer got back here.
+    if top_up.payment_status != PaymentStatus::Pending {
+        return top_up_outcome(&session, top_up.payment_status).await;
+    }
+
+    if query.amount != top_up.amount {
+        tracing::error!("Top-up amount mismatch: expected {}, got {}", top_up.amount, query.amount);
+        return redirect_with_error(&session).await;
+    }
+
+    let confirm = ConfirmRequest {
+        order_number: query.order_number.clone(),
+        payment_key: query.payment_key.clone(),
+        amount: query.amount,
+    };
+    let status = match confirm_or_look_up(&gateway, confirm).await {
+        ConfirmOutcome::Paid(_) => PaymentStatus::Paid,
+        ConfirmOutcome::Declined(_) => PaymentStatus::Failed,
+        ConfirmOutcome::Unsettled => {
+            return Ok(FlashMessage::error(messages::TOP_UP_UNCONFIRMED)
+                .set_and_redirect(&session, paths::pages::DASHBOARD)
+                .await?);
+        }
+    };
+
+    // Losing the race to the webhook means it has already been settled; its
+    // outcome, not this attempt's, is what the customer should see.
+    let status = match commands::credit::update_top_up_payment(&top_up.id, &query.payment_key, status).await {
+        Ok(updated) => updated.payment_status,
+        Err(DataError::NotFound(_)) => {
+            queries::credit::get_top_up_by_order_number_for_user(&query.order_number, user_id)
+                .await?
+                .payment_status
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    top_up_outcome(&session, status).await
+}
+
+async fn top_up_outcome(session: &Session, status: PaymentStatus) -> HandlerResult {
+    match status {
+        PaymentStatus::Paid => {
+            Ok(FlashMessage::success(messages::TOP_UP_SUCCESS)
+                .set_and_redirect(session, paths::pages::DASHBOARD)
+                .await?)
+        }
+        _ => redirect_with_error(session).await,
+    }
+}
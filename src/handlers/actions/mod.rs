@@ -1,11 +1,19 @@
 pub mod admin;
 mod auth;
+mod credits;
+mod passkeys;
 mod payment;
 mod sign_out;
 mod todos;
 
 pub use auth::get_actions_auth_verify;
-pub use payment::{get_actions_payment_verify, post_actions_payment_initiate};
+pub use credits::{get_actions_credits_verify, post_actions_credits_pay};
+pub use passkeys::{
+    delete_actions_passkeys_passkey_id, post_actions_passkeys_authenticate_finish,
+    post_actions_passkeys_authenticate_start, post_actions_passkeys_register_finish,
+    post_actions_passkeys_register_start,
+};
+pub use payment::{get_actions_payment_verify, post_actions_payment_initiate, post_actions_payment_retry};
 pub use sign_out::post_actions_sign_out;
 pub use todos::delete_actions_todos_todo_id;
 pub use todos::patch_actions_todos_todo_id_toggle;
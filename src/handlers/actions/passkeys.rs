@@ -0,0 +1,124 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use tower_sessions::Session;
+use validator::Validate;
+use webauthn_rs::prelude::PublicKeyCredential;
+
+use crate::{
+    auth::{
+        CurrentUser,
+        passkey::{self, SharedWebauthn},
+        service,
+    },
+    constants::messages,
+    data::commands,
+    handlers::{errors::HandlerResult, forms::parse_validation_errors},
+    models::{
+        PasskeyId,
+        passkey::{FIELD_NAME, FinishRegistrationRequest},
+    },
+    paths,
+    session::FlashMessage,
+    views::response as htmx,
+};
+
+pub async fn post_actions_passkeys_register_start(
+    State(webauthn): State<SharedWebauthn>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let CurrentUser::Authenticated { email, .. } = &current_user else {
+        unreachable!("require_authenticated rejects guests");
+    };
+
+    match passkey::start_registration(&webauthn, &session, user_id, email).await {
+        Ok(challenge) => Ok(Json(challenge).into_response()),
+        Err(e) => {
+            tracing::error!("Passkey registration start failed: {}", e);
+            registration_failed(&session, messages::PASSKEY_REGISTRATION_FAILED).await
+        }
+    }
+}
+
+pub async fn post_actions_passkeys_register_finish(
+    State(webauthn): State<SharedWebauthn>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Json(request): Json<FinishRegistrationRequest>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+
+    if let Err(errors) = request.validate() {
+        let message = parse_validation_errors(&errors)
+            .remove(FIELD_NAME)
+            .unwrap_or_else(|| messages::PASSKEY_REGISTRATION_FAILED.to_string());
+        return registration_failed(&session, message).await;
+    }
+
+    let name = request.name.trim().to_string();
+    if let Err(e) = passkey::finish_registration(&webauthn, &session, user_id, name, &request.credential).await {
+        tracing::warn!("Passkey registration failed: {}", e);
+        return registration_failed(&session, messages::PASSKEY_REGISTRATION_FAILED).await;
+    }
+
+    FlashMessage::success(messages::PASSKEY_ADDED).set(&session).await?;
+    Ok(htmx::client_redirect(paths::pages::ACCOUNT))
+}
+
+async fn registration_failed(session: &Session, message: impl Into<String>) -> HandlerResult {
+    FlashMessage::error(message).set(session).await?;
+    Ok(htmx::client_redirect(paths::pages::ACCOUNT))
+}
+
+pub async fn delete_actions_passkeys_passkey_id(
+    Extension(current_user): Extension<CurrentUser>,
+    Path(raw_passkey_id): Path<String>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let passkey_id = PasskeyId::parse_or_invalid(&raw_passkey_id)?;
+
+    commands::passkey::delete_passkey(user_id, &passkey_id).await?;
+
+    Ok(htmx::empty_ok_response())
+}
+
+pub async fn post_actions_passkeys_authenticate_start(
+    State(webauthn): State<SharedWebauthn>,
+    session: Session,
+) -> HandlerResult {
+    match passkey::start_authentication(&webauthn, &session).await {
+        Ok(challenge) => Ok(Json(challenge).into_response()),
+        Err(e) => {
+            tracing::error!("Passkey sign-in start failed: {}", e);
+            sign_in_failed(&session).await
+        }
+    }
+}
+
+pub async fn post_actions_passkeys_authenticate_finish(
+    State(webauthn): State<SharedWebauthn>,
+    session: Session,
+    Json(credential): Json<PublicKeyCredential>,
+) -> HandlerResult {
+    let user_id = match passkey::finish_authentication(&webauthn, &session, &credential).await {
+        Ok(user_id) => user_id,
+        Err(e) => {
+            tracing::warn!("Passkey sign-in failed: {}", e);
+            return sign_in_failed(&session).await;
+        }
+    };
+
+    service::sign_in(&session, user_id).await?;
+
+    FlashMessage::success(messages::SIGNED_IN).set(&session).await?;
+    Ok(htmx::client_redirect(paths::pages::ROOT))
+}
+
+async fn sign_in_failed(session: &Session) -> HandlerResult {
+    FlashMessage::error(messages::PASSKEY_SIGN_IN_FAILED).set(session).await?;
+    Ok(htmx::client_redirect(paths::pages::SIGN_IN))
+}
@@ -5,12 +5,14 @@ use tower_sessions::Session;
 use crate::{
     auth::CurrentUser,
     config::AppConfig,
-    constants::messages,
-    data::{commands::{self, order::ConfirmPaymentParams}, queries},
+    constants::{errors, messages},
+    data::{commands::{self, order::OrderEventParams}, errors::DataError, queries},
+    email,
     session::FlashMessage,
     handlers::errors::HandlerResult,
     models::{OrderId, OrderNumber, order::PaymentStatus},
     paths,
+    payment::{confirm_or_look_up, reverse_charge, ConfirmOutcome, ConfirmRequest, SharedPaymentGateway},
 };
 
 #[derive(Deserialize)]
@@ -37,6 +39,30 @@ pub async fn post_actions_payment_initiate(
     Ok(Redirect::to(&checkout_url).into_response())
 }
 
+#[derive(Deserialize)]
+pub struct PaymentRetryForm {
+    order_id: String,
+}
+
+/// Reopens a failed order and sends the customer straight back to checkout.
+pub async fn post_actions_payment_retry(
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<PaymentRetryForm>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let order_id = OrderId::parse_or_not_found(&form.order_id, errors::ORDER_NOT_FOUND)?;
+    let order = queries::order::get_order_for_user(&order_id, user_id).await?;
+
+    match commands::order::retry_failed_payment(&order.id, OrderEventParams::customer(user_id)).await {
+        Ok(order) => Ok(Redirect::to(&paths::helpers::checkout_path(&order.id)).into_response()),
+        Err(DataError::IllegalTransition(_)) => Ok(FlashMessage::error(messages::ORDER_ALREADY_PROCESSED)
+            .set_and_redirect(&session, &paths::helpers::quote_path(&order.id))
+            .await?),
+        Err(e) => Err(e.into()),
+    }
+}
+
 #[derive(Deserialize)]
 pub struct PaymentVerifyQuery {
     #[serde(rename = "orderId")]
@@ -54,6 +80,7 @@ async fn redirect_with_error(session: &Session, order_id: &OrderId) -> HandlerRe
 
 pub async fn get_actions_payment_verify(
     State(config): State<AppConfig>,
+    State(gateway): State<SharedPaymentGateway>,
     Extension(current_user): Extension<CurrentUser>,
     session: Session,
     Query(query): Query<PaymentVerifyQuery>,
@@ -61,19 +88,59 @@ pub async fn get_actions_payment_verify(
     let user_id = current_user.require_authenticated()?;
     let order = queries::order::get_order_by_order_number_for_user(&query.order_number, user_id).await?;
 
+    // Confirming is what captures the money, so an order that expired or was
+    // settled while the customer sat in the payment window is never charged.
+    if order.payment_status != PaymentStatus::Pending {
+        tracing::warn!("Refusing to confirm payment for order {}, already {}", order.order_number, order.payment_status.as_str());
+        return Ok(FlashMessage::error(messages::ORDER_ALREADY_PROCESSED)
+            .set_and_redirect(&session, &paths::helpers::quote_path(&order.id))
+            .await?);
+    }
+
     if query.amount != order.price_amount {
         tracing::error!("Payment amount mismatch: expected {}, got {}", order.price_amount, query.amount);
         return redirect_with_error(&session, &order.id).await;
     }
 
-    let status = commands::order::confirm_payment_with_toss(ConfirmPaymentParams {
-        secret_key: config.payment().toss_secret_key().to_string(),
+    // Keeps the expiry job off the order until the charge settles either way.
+    if !commands::order::record_payment_attempt(&order.id, &query.payment_key).await? {
+        return Ok(FlashMessage::error(messages::ORDER_ALREADY_PROCESSED)
+            .set_and_redirect(&session, &paths::helpers::quote_path(&order.id))
+            .await?);
+    }
+
+    let confirm = ConfirmRequest {
         order_number: query.order_number.clone(),
         payment_key: query.payment_key.clone(),
         amount: query.amount,
-    }).await;
+    };
+    let event = OrderEventParams::customer(user_id);
+    let (status, event) = match confirm_or_look_up(&gateway, confirm).await {
+        ConfirmOutcome::Paid(record) => (PaymentStatus::Paid, event.with_payload(record.raw)),
+        ConfirmOutcome::Declined(e) => (PaymentStatus::Failed, event.with_note(e.to_string())),
+        ConfirmOutcome::Unsettled => {
+            return Ok(FlashMessage::error(messages::PAYMENT_UNCONFIRMED)
+                .set_and_redirect(&session, &paths::helpers::quote_path(&order.id))
+                .await?);
+        }
+    };
 
-    commands::order::update_order_payment(&order.id, &query.payment_key, status).await?;
+    match commands::order::settle_order_payment(&order.id, &query.payment_key, status, event).await {
+        Ok(order) => {
+            if let Err(e) = email::send_payment_update(config.email(), &order).await {
+                tracing::error!("Failed to send payment email for order {}: {}", order.order_number, e);
+            }
+        }
+        // The webhook settled it the same way first, and sent the email.
+        Err(DataError::IllegalTransition(e)) if e.from == status => {}
+        // Charged, but the order left `pending` after the check above.
+        Err(e) if status == PaymentStatus::Paid => {
+            tracing::error!("Order {} was charged but could not be marked paid: {}", order.order_number, e);
+            reverse_charge(&gateway, &order, &query.payment_key).await;
+            return redirect_with_error(&session, &order.id).await;
+        }
+        Err(e) => return Err(e.into()),
+    }
 
     match status {
         PaymentStatus::Paid => {
@@ -4,7 +4,7 @@ use axum::{
 };
 use thiserror::Error;
 
-use crate::{auth::CurrentUser, constants::error_pages, data::errors::DataError, views::pages};
+use crate::{auth::{two_factor::TwoFactorError, CurrentUser}, constants::{error_pages, errors}, data::errors::DataError, export::ExportError, storage::StorageError, views::pages};
 
 pub type HandlerResult<T = Response> = Result<T, HandlerError>;
 
@@ -16,6 +16,15 @@ pub enum HandlerError {
 
     #[error("{0}")]
     Session(#[from] tower_sessions::session::Error),
+
+    #[error("{0}")]
+    TwoFactor(#[from] TwoFactorError),
+
+    #[error("{0}")]
+    Export(#[from] ExportError),
+
+    #[error("{0}")]
+    Storage(#[from] StorageError),
 }
 
 impl IntoResponse for HandlerError {
@@ -32,10 +41,26 @@ impl IntoResponse for HandlerError {
                 tracing::error!("Creation failed: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, *msg)
             }
+            Self::Data(DataError::IllegalTransition(e)) => {
+                tracing::warn!("Rejected payment status change: {}", e);
+                (StatusCode::CONFLICT, errors::PAYMENT_STATUS_CONFLICT)
+            }
             Self::Session(e) => {
                 tracing::error!(error = %e, "Session error in handler");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
             }
+            Self::TwoFactor(e) => {
+                tracing::error!(error = %e, "Two-factor error in handler");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+            }
+            Self::Export(e) => {
+                tracing::error!(error = %e, "Report export error in handler");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+            }
+            Self::Storage(e) => {
+                tracing::error!(error = %e, "Blob storage error in handler");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+            }
         };
 
         (status, pages::server_error(&CurrentUser::Guest, None, error_pages::FALLBACK_SITE_NAME, message)).into_response()
@@ -0,0 +1,55 @@
+use axum::{Extension, Form, extract::Path};
+use tower_sessions::Session;
+
+use crate::{
+    auth::CurrentUser,
+    constants::{errors, messages},
+    data::{commands, queries},
+    session::FlashMessage,
+    handlers::errors::HandlerResult,
+    models::{coupon::CouponForm, CouponId},
+    paths,
+};
+
+pub async fn post_forms_admin_coupons(
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<CouponForm>,
+) -> HandlerResult {
+    let admin_user_id = current_user.require_authenticated()?;
+
+    let new_coupon = match form.new_coupon() {
+        Ok(new_coupon) => new_coupon,
+        Err(message) => {
+            return Ok(FlashMessage::error(message)
+                .set_and_redirect(&session, paths::pages::admin::COUPONS)
+                .await?);
+        }
+    };
+
+    if queries::coupon::get_coupon_by_code(&new_coupon.code).await?.is_some() {
+        return Ok(FlashMessage::error(errors::COUPON_CODE_TAKEN)
+            .set_and_redirect(&session, paths::pages::admin::COUPONS)
+            .await?);
+    }
+
+    let coupon = commands::coupon::create_coupon(new_coupon, admin_user_id).await?;
+    tracing::info!("Coupon {} created by admin {}", coupon.code, admin_user_id);
+
+    Ok(FlashMessage::success(messages::COUPON_CREATED)
+        .set_and_redirect(&session, paths::pages::admin::COUPONS)
+        .await?)
+}
+
+pub async fn post_forms_admin_coupons_coupon_id_deactivate(
+    Path(raw_coupon_id): Path<String>,
+    session: Session,
+) -> HandlerResult {
+    let coupon_id = CouponId::parse_or_not_found(&raw_coupon_id, errors::COUPON_NOT_FOUND)?;
+
+    commands::coupon::deactivate_coupon(&coupon_id).await?;
+
+    Ok(FlashMessage::success(messages::COUPON_DEACTIVATED)
+        .set_and_redirect(&session, paths::pages::admin::COUPONS)
+        .await?)
+}
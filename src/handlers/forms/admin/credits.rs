@@ -0,0 +1,39 @@
+use axum::{Extension, Form, extract::Path};
+use tower_sessions::Session;
+
+use crate::{
+    auth::CurrentUser,
+    constants::{errors, messages},
+    data::commands,
+    session::FlashMessage,
+    handlers::errors::HandlerResult,
+    models::{credit::CreditAdjustmentForm, UserId},
+    paths::helpers,
+};
+
+pub async fn post_forms_admin_users_user_id_credits(
+    Path(raw_user_id): Path<String>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<CreditAdjustmentForm>,
+) -> HandlerResult {
+    let admin_user_id = current_user.require_authenticated()?;
+    let user_id = UserId::parse_or_invalid(&raw_user_id)?;
+    let user_path = helpers::user_detail_path(&user_id);
+
+    let (amount, note) = match form.adjustment() {
+        Ok(adjustment) => adjustment,
+        Err(message) => {
+            return Ok(FlashMessage::error(message).set_and_redirect(&session, &user_path).await?);
+        }
+    };
+
+    if !commands::credit::adjust_credits(&user_id, amount, &note, admin_user_id).await? {
+        return Ok(FlashMessage::error(errors::CREDIT_ADJUSTMENT_NEGATIVE)
+            .set_and_redirect(&session, &user_path)
+            .await?);
+    }
+    tracing::info!("Credit balance of user {} adjusted by {} by admin {}", user_id, amount, admin_user_id);
+
+    Ok(FlashMessage::success(messages::CREDITS_ADJUSTED).set_and_redirect(&session, &user_path).await?)
+}
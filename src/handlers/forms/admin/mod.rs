@@ -1,3 +1,13 @@
+mod coupons;
+mod credits;
 mod grant_role;
+mod pricing;
+mod refund;
+mod retry_analysis_job;
 
+pub use coupons::{post_forms_admin_coupons, post_forms_admin_coupons_coupon_id_deactivate};
+pub use credits::post_forms_admin_users_user_id_credits;
 pub use grant_role::post_forms_admin_users_user_id_grant_role;
+pub use pricing::post_forms_admin_pricing;
+pub use refund::post_forms_admin_orders_order_id_refund;
+pub use retry_analysis_job::post_forms_admin_analysis_jobs_job_id_retry;
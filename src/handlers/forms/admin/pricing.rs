@@ -0,0 +1,39 @@
+use axum::{Extension, Form};
+use tower_sessions::Session;
+
+use crate::{
+    auth::CurrentUser,
+    constants::messages,
+    data::commands,
+    session::FlashMessage,
+    handlers::errors::HandlerResult,
+    models::pricing::PricingRulesForm,
+    paths,
+};
+
+pub async fn post_forms_admin_pricing(
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<PricingRulesForm>,
+) -> HandlerResult {
+    let (admin_user_id, admin_email) = match &current_user {
+        CurrentUser::Authenticated { user_id, email, .. } => (user_id, email),
+        CurrentUser::Guest => unreachable!("Admin route accessed by guest"),
+    };
+
+    let rules = match form.rules() {
+        Ok(rules) => rules,
+        Err(message) => {
+            return Ok(FlashMessage::error(message)
+                .set_and_redirect(&session, paths::pages::admin::PRICING)
+                .await?);
+        }
+    };
+
+    let rule_set = commands::pricing::publish_pricing_rules(rules, admin_user_id, admin_email).await?;
+    tracing::info!("Pricing version {} published by {}", rule_set.version, admin_email);
+
+    Ok(FlashMessage::success(messages::PRICING_RULES_PUBLISHED)
+        .set_and_redirect(&session, paths::pages::admin::PRICING)
+        .await?)
+}
@@ -0,0 +1,150 @@
+use axum::{Extension, Form, extract::{Path, State}};
+use tower_sessions::Session;
+use validator::Validate;
+
+use crate::{
+    auth::CurrentUser,
+    config::AppConfig,
+    constants::{errors, messages, payment::REFUND_UNRECORDED_NOTE},
+    data::{commands::{self, order::{OrderEventParams, RecordRefundParams}}, queries::admin},
+    email,
+    session::FlashMessage,
+    handlers::errors::HandlerResult,
+    models::{order::Order, refund::RefundForm, OrderId},
+    paths::helpers,
+    payment::{CancelRequest, SharedPaymentGateway},
+};
+
+use super::super::parse_validation_errors;
+
+/// Empty amount means "refund whatever is left".
+fn parse_refund_amount(raw: &str, refundable: i32) -> Result<Option<i32>, &'static str> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+
+    match raw.parse::<i32>() {
+        Ok(amount) if amount > 0 && amount <= refundable => Ok(Some(amount)),
+        _ => Err(errors::REFUND_AMOUNT_INVALID),
+    }
+}
+
+pub async fn post_forms_admin_orders_order_id_refund(
+    Path(raw_order_id): Path<String>,
+    State(config): State<AppConfig>,
+    State(gateway): State<SharedPaymentGateway>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(mut form): Form<RefundForm>,
+) -> HandlerResult {
+    let admin_user_id = current_user.require_authenticated()?;
+    let order_id = OrderId::parse_or_not_found(&raw_order_id, errors::ORDER_NOT_FOUND)?;
+    let detail_path = helpers::order_detail_path(&order_id);
+
+    let order = admin::get_order_detail(&order_id).await?;
+    let refundable = order.refundable_amount();
+
+    // Card payments go back through the provider; credit payments go back on the balance.
+    if refundable <= 0 || (order.payment_key.is_none() && !order.paid_with_credits) {
+        return Ok(FlashMessage::error(errors::REFUND_NOT_ALLOWED)
+            .set_and_redirect(&session, &detail_path)
+            .await?);
+    }
+
+    // Trimmed first, so a reason of only spaces fails validation instead of
+    // being stored empty.
+    form.reason = form.reason.trim().to_string();
+    if let Err(validation_errors) = form.validate() {
+        let message = parse_validation_errors(&validation_errors).into_values().next().unwrap_or_default();
+        return Ok(FlashMessage::error(message).set_and_redirect(&session, &detail_path).await?);
+    }
+
+    let requested_amount = match parse_refund_amount(&form.amount, refundable) {
+        Ok(amount) => amount,
+        Err(message) => {
+            return Ok(FlashMessage::error(message).set_and_redirect(&session, &detail_path).await?);
+        }
+    };
+
+    let refunder_email = match &current_user {
+        CurrentUser::Authenticated { email, .. } => email.clone(),
+        CurrentUser::Guest => unreachable!("Admin route accessed by guest"),
+    };
+
+    let refund_amount = requested_amount.unwrap_or(refundable);
+    let mut refund = RecordRefundParams {
+        amount: refund_amount,
+        reason: form.reason,
+        refunded_by: admin_user_id.clone(),
+        refunded_by_email: refunder_email,
+        provider_payload: None,
+    };
+
+    let Some(payment_key) = order.payment_key.clone() else {
+        // No provider involved: the credit and its record commit together, or neither does.
+        let order = commands::order::record_refund(&order_id, refund).await?;
+        return notify_refunded(&config, &session, &order, refund_amount, &detail_path).await;
+    };
+
+    let payment = match gateway
+        .cancel(CancelRequest {
+            payment_key,
+            amount: requested_amount,
+            reason: refund.reason.clone(),
+        })
+        .await
+    {
+        Ok(payment) => payment,
+        Err(e) => {
+            tracing::error!("Refund for order {} failed: {}", order.order_number, e);
+            return Ok(FlashMessage::error(format!("Refund failed: {}", e))
+                .set_and_redirect(&session, &detail_path)
+                .await?);
+        }
+    };
+
+    refund.provider_payload = Some(payment.raw.clone());
+    let recorded = commands::order::record_refund(&order_id, refund).await;
+
+    // The money has already moved, so a failure here must not read as "nothing happened".
+    let order = match recorded {
+        Ok(order) => order,
+        Err(e) => {
+            tracing::error!(
+                "Refund of {} for order {} went through at the provider but was not recorded: {}. Provider response: {}",
+                refund_amount,
+                order.order_number,
+                e,
+                payment.raw
+            );
+            let event = OrderEventParams::admin(admin_user_id)
+                .with_payload(payment.raw)
+                .with_note(REFUND_UNRECORDED_NOTE);
+            if let Err(e) = commands::order::record_order_event(&order_id, event).await {
+                tracing::error!("Could not note the unrecorded refund on order {}: {}", order.order_number, e);
+            }
+            return Ok(FlashMessage::error(errors::REFUND_NOT_RECORDED)
+                .set_and_redirect(&session, &detail_path)
+                .await?);
+        }
+    };
+
+    notify_refunded(&config, &session, &order, refund_amount, &detail_path).await
+}
+
+async fn notify_refunded(
+    config: &AppConfig,
+    session: &Session,
+    order: &Order,
+    refund_amount: i32,
+    detail_path: &str,
+) -> HandlerResult {
+    if let Err(e) = email::send_refund_notice(config.email(), order, refund_amount).await {
+        tracing::error!("Failed to send refund email for order {}: {}", order.order_number, e);
+    }
+
+    Ok(FlashMessage::success(messages::REFUND_ISSUED)
+        .set_and_redirect(session, detail_path)
+        .await?)
+}
@@ -0,0 +1,25 @@
+use axum::extract::Path;
+use tower_sessions::Session;
+
+use crate::{
+    constants::messages,
+    data::commands,
+    session::FlashMessage,
+    handlers::errors::HandlerResult,
+    models::AnalysisJobId,
+    paths,
+};
+
+pub async fn post_forms_admin_analysis_jobs_job_id_retry(
+    Path(raw_job_id): Path<String>,
+    session: Session,
+) -> HandlerResult {
+    let job_id = AnalysisJobId::parse_or_invalid(&raw_job_id)?;
+
+    let job = commands::analysis_job::retry_job(&job_id).await?;
+    tracing::info!("Analysis job {} for order {} queued for retry by an admin", job.id, job.order);
+
+    Ok(FlashMessage::success(messages::ANALYSIS_JOB_RETRIED)
+        .set_and_redirect(&session, paths::pages::admin::ANALYSIS_JOBS)
+        .await?)
+}
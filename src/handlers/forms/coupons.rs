@@ -0,0 +1,88 @@
+use axum::{Extension, Form, extract::Path};
+use chrono::Utc;
+use tower_sessions::Session;
+
+use crate::{
+    auth::CurrentUser,
+    constants::{errors, messages},
+    data::{commands, queries},
+    handlers::errors::HandlerResult,
+    models::{
+        coupon::{normalize_code, ApplyCouponForm, CouponRejection},
+        order::PaymentStatus,
+        OrderId,
+    },
+    paths::helpers,
+    session::FlashMessage,
+    views::helpers::format_price,
+};
+
+fn rejection_message(rejection: CouponRejection) -> String {
+    match rejection {
+        CouponRejection::Inactive => errors::COUPON_NOT_FOUND.to_string(),
+        CouponRejection::NotStarted => errors::COUPON_NOT_STARTED.to_string(),
+        CouponRejection::Expired => errors::COUPON_EXPIRED.to_string(),
+        CouponRejection::UsedUp => errors::COUPON_USED_UP.to_string(),
+        CouponRejection::AlreadyUsed => errors::COUPON_ALREADY_USED.to_string(),
+        CouponRejection::BelowMinimum(amount) => {
+            format!("That code needs an order of at least ₩{}", format_price(amount))
+        }
+    }
+}
+
+/// Applying a code replaces any code already on the order.
+pub async fn post_forms_orders_order_id_coupon(
+    Path(raw_order_id): Path<String>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<ApplyCouponForm>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let order_id = OrderId::parse_or_not_found(&raw_order_id, errors::ORDER_NOT_FOUND)?;
+
+    let order = queries::order::get_order_for_user(&order_id, user_id).await?;
+    let quote_path = helpers::quote_path(&order.id);
+    if !matches!(order.payment_status, PaymentStatus::Pending) {
+        return Ok(FlashMessage::error(messages::ORDER_ALREADY_PROCESSED)
+            .set_and_redirect(&session, &quote_path)
+            .await?);
+    }
+
+    let Some(coupon) = queries::coupon::get_coupon_by_code(&normalize_code(&form.code)).await? else {
+        return Ok(FlashMessage::error(errors::COUPON_NOT_FOUND).set_and_redirect(&session, &quote_path).await?);
+    };
+
+    let usage = queries::coupon::get_coupon_usage(&coupon.id, user_id, &order.id).await?;
+    let subtotal = order.price_amount + order.discount_amount;
+    let discount_amount = match coupon.discount_for(subtotal, Utc::now(), usage) {
+        Ok(discount_amount) => discount_amount,
+        Err(rejection) => {
+            return Ok(FlashMessage::error(rejection_message(rejection))
+                .set_and_redirect(&session, &quote_path)
+                .await?);
+        }
+    };
+
+    // The checks above can go stale; the update repeats them atomically.
+    if commands::coupon::apply_coupon_to_order(&order.id, user_id, &coupon, discount_amount).await?.is_none() {
+        return Ok(FlashMessage::error(errors::COUPON_NO_LONGER_AVAILABLE).set_and_redirect(&session, &quote_path).await?);
+    }
+    tracing::info!("Coupon {} applied to order {} for ₩{} off", coupon.code, order.order_number, discount_amount);
+
+    Ok(FlashMessage::success(messages::COUPON_APPLIED).set_and_redirect(&session, &quote_path).await?)
+}
+
+pub async fn post_forms_orders_order_id_coupon_remove(
+    Path(raw_order_id): Path<String>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let order_id = OrderId::parse_or_not_found(&raw_order_id, errors::ORDER_NOT_FOUND)?;
+
+    commands::coupon::remove_coupon_from_order(&order_id, user_id).await?;
+
+    Ok(FlashMessage::success(messages::COUPON_REMOVED)
+        .set_and_redirect(&session, &helpers::quote_path(&order_id))
+        .await?)
+}
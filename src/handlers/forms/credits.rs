@@ -0,0 +1,33 @@
+use axum::{Extension, Form, response::{IntoResponse, Redirect}};
+use tower_sessions::Session;
+
+use crate::{
+    auth::CurrentUser,
+    data::commands,
+    handlers::errors::HandlerResult,
+    models::{credit::TopUpForm, OrderNumber},
+    paths,
+    session::FlashMessage,
+};
+
+pub async fn post_forms_credits_top_ups(
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<TopUpForm>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+
+    let amount = match form.amount() {
+        Ok(amount) => amount,
+        Err(message) => {
+            return Ok(FlashMessage::error(message)
+                .set_and_redirect(&session, paths::pages::DASHBOARD)
+                .await?);
+        }
+    };
+
+    let order_number = OrderNumber::generate_top_up(user_id);
+    let top_up = commands::credit::create_top_up(user_id, amount, &order_number).await?;
+
+    Ok(Redirect::to(&paths::helpers::top_up_checkout_path(&top_up.id)).into_response())
+}
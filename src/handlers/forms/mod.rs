@@ -1,17 +1,25 @@
 pub mod admin;
 mod contact;
+mod coupons;
+mod credits;
+mod share_links;
 mod sign_in;
 mod text_analyzer;
 mod todos;
+mod two_factor;
 
 pub use contact::post_forms_contact;
+pub use credits::post_forms_credits_top_ups;
+pub use coupons::{post_forms_orders_order_id_coupon, post_forms_orders_order_id_coupon_remove};
+pub use share_links::{post_forms_orders_order_id_share_links, post_forms_share_links_share_link_id_revoke};
 pub use sign_in::post_forms_sign_in;
 pub use text_analyzer::post_forms_text_analyzer;
 pub use todos::post_forms_todos;
+pub use two_factor::{post_forms_two_factor_disable, post_forms_two_factor_enable, post_forms_two_factor_verify};
 
 use std::collections::HashMap;
 
-pub(super) fn parse_validation_errors(
+pub(in crate::handlers) fn parse_validation_errors(
     validation_errors: &validator::ValidationErrors,
 ) -> HashMap<String, String> {
     validation_errors
@@ -0,0 +1,58 @@
+use axum::{Extension, Form, extract::Path};
+use chrono::{Duration, Utc};
+use tower_sessions::Session;
+
+use crate::{
+    auth::{self, CurrentUser},
+    constants::{errors, messages},
+    data::{commands, errors::DataError, queries},
+    handlers::errors::HandlerResult,
+    models::{share_link::ShareLinkForm, OrderId, ShareLinkId},
+    paths::helpers,
+    session::FlashMessage,
+};
+
+pub async fn post_forms_orders_order_id_share_links(
+    Path(raw_order_id): Path<String>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<ShareLinkForm>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let order_id = OrderId::parse_or_not_found(&raw_order_id, errors::ORDER_NOT_FOUND)?;
+
+    let order = queries::order::get_order_for_user(&order_id, user_id).await?;
+    if !order.payment_status.is_paid() {
+        return Err(DataError::Unauthorized(errors::PAYMENT_NOT_COMPLETED).into());
+    }
+    let confirmation_path = helpers::payment_confirmation_path(&order.id);
+
+    let expires_in_days = match form.expires_in_days() {
+        Ok(days) => days,
+        Err(message) => {
+            return Ok(FlashMessage::error(message).set_and_redirect(&session, &confirmation_path).await?);
+        }
+    };
+    let expires_at = expires_in_days.map(|days| Utc::now() + Duration::days(days));
+
+    commands::share_link::create_share_link(&order.id, user_id, &auth::generate_token(), expires_at).await?;
+
+    Ok(FlashMessage::success(messages::SHARE_LINK_CREATED)
+        .set_and_redirect(&session, &confirmation_path)
+        .await?)
+}
+
+pub async fn post_forms_share_links_share_link_id_revoke(
+    Path(raw_share_link_id): Path<String>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let link_id = ShareLinkId::parse_or_not_found(&raw_share_link_id, errors::SHARE_LINK_NOT_REVOCABLE)?;
+
+    let link = commands::share_link::revoke_share_link(&link_id, user_id).await?;
+
+    Ok(FlashMessage::success(messages::SHARE_LINK_REVOKED)
+        .set_and_redirect(&session, &helpers::payment_confirmation_path(&link.order))
+        .await?)
+}
@@ -1,32 +1,87 @@
-use axum::{Extension, extract::Multipart, response::{IntoResponse, Redirect}};
+use axum::{
+    Extension,
+    extract::{Multipart, State, multipart::MultipartError},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
 use tower_sessions::Session;
 
 use crate::{
     auth::CurrentUser,
-    constants::{errors, file_upload, pricing},
-    data::{commands, errors::DataError},
+    constants::{errors, file_upload},
+    data::{commands, errors::DataError, queries},
+    extraction::{self, ExtractError, FileKind},
     handlers::errors::HandlerResult,
     models::OrderNumber,
     paths,
+    pricing,
     session::FlashMessage,
+    storage::{self, BlobKey, SharedBlobStore},
 };
 
 struct ParsedUpload {
     filename: String,
     file_size: i32,
+    /// Detected from the content; what the file is priced as.
+    kind: FileKind,
     text_content: String,
+    text_encoding: Option<String>,
 }
 
 enum ParseResult {
-    Success(ParsedUpload),
-    FileTooLarge,
+    Success(Vec<ParsedUpload>),
+    /// The upload was refused; the message is shown to the user.
+    Rejected(String),
+}
+
+fn file_too_large(filename: &str) -> ParseResult {
+    ParseResult::Rejected(format!(
+        "{}: file too large. Maximum size is {} MB per file.",
+        filename,
+        file_upload::MAX_FILE_SIZE / 1024 / 1024
+    ))
+}
+
+fn batch_too_large() -> ParseResult {
+    ParseResult::Rejected(format!(
+        "Files too large. Maximum total size is {} MB per order.",
+        file_upload::MAX_BATCH_SIZE / 1024 / 1024
+    ))
+}
+
+/// Hitting the route's body limit ends the stream with 413; that is an
+/// oversized upload, not a broken one.
+fn multipart_failure(e: MultipartError) -> Result<ParseResult, DataError> {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return Ok(batch_too_large());
+    }
+    tracing::error!("Multipart error: {}", e);
+    Err(DataError::InvalidInput(format!("Failed to process multipart data: {}", e)))
+}
+
+fn batch_text_too_large() -> ParseResult {
+    ParseResult::Rejected(format!(
+        "Files contain too much text. Maximum is {} million characters per order.",
+        file_upload::MAX_BATCH_EXTRACTED_CHARS / 1_000_000
+    ))
 }
 
-async fn parse_file_upload(mut multipart: Multipart) -> Result<ParseResult, DataError> {
-    while let Some(field) = multipart.next_field().await.map_err(|e| {
-        tracing::error!("Multipart error: {}", e);
-        DataError::InvalidInput(format!("Failed to process multipart data: {}", e))
-    })? {
+/// Files are read a chunk at a time and refused as soon as one passes
+/// `MAX_FILE_SIZE` or the batch passes `MAX_BATCH_SIZE`, so an oversized
+/// upload is never held in memory. Extracted text is held for the whole batch,
+/// so it is capped across files too.
+async fn parse_file_uploads(mut multipart: Multipart) -> Result<ParseResult, DataError> {
+    let mut uploads = Vec::new();
+    let mut batch_size = 0;
+    let mut batch_chars = 0;
+
+    loop {
+        let mut field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return multipart_failure(e),
+        };
+
         let field_name = match field.name() {
             Some(name) => name,
             None => {
@@ -38,32 +93,89 @@ async fn parse_file_upload(mut multipart: Multipart) -> Result<ParseResult, Data
             continue;
         }
 
-        let filename = field.file_name().map(|s| s.to_string());
-        let data = field.bytes().await.map_err(|e| {
-            tracing::error!("Failed to read file: {}", e);
-            DataError::InvalidInput(format!("Failed to read file: {}", e))
-        })?;
+        // Browsers send a part with an empty filename when no file was chosen.
+        let filename = match field.file_name() {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => continue,
+        };
+        if uploads.len() == file_upload::MAX_FILES_PER_ORDER {
+            return Ok(ParseResult::Rejected(format!(
+                "Too many files. Upload at most {} files per order.",
+                file_upload::MAX_FILES_PER_ORDER
+            )));
+        }
+
+        let mut data = Vec::new();
+        loop {
+            let chunk = match field.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => return multipart_failure(e),
+            };
 
-        if data.len() > file_upload::MAX_FILE_SIZE {
-            return Ok(ParseResult::FileTooLarge);
+            if data.len() + chunk.len() > file_upload::MAX_FILE_SIZE {
+                tracing::info!("Rejected upload {}: over the per-file size limit", filename);
+                return Ok(file_too_large(&filename));
+            }
+            batch_size += chunk.len();
+            if batch_size > file_upload::MAX_BATCH_SIZE {
+                tracing::info!("Rejected upload {}: over the per-order size limit", filename);
+                return Ok(batch_too_large());
+            }
+            data.extend_from_slice(&chunk);
         }
 
-        let text_content = String::from_utf8(data.to_vec()).map_err(|e| {
-            tracing::error!("Invalid UTF-8 in file: {}", e);
-            DataError::InvalidInput("File must be valid UTF-8 text".to_string())
-        })?;
+        let file_size = data.len() as i32;
+        let extracted = match extract_text(data).await {
+            Ok(extracted) => extracted,
+            Err(error) => {
+                tracing::info!("Rejected upload {}: {}", filename, error);
+                return Ok(ParseResult::Rejected(format!("{}: {}", filename, error)));
+            }
+        };
+        tracing::debug!(kind = %extracted.kind, encoding = ?extracted.encoding, "Extracted text from {}", filename);
 
-        return Ok(ParseResult::Success(ParsedUpload {
-            filename: filename.ok_or(DataError::NotFound(errors::NO_FILE_PROVIDED))?,
-            file_size: data.len() as i32,
-            text_content,
-        }));
+        batch_chars += extracted.text.chars().count();
+        if batch_chars > file_upload::MAX_BATCH_EXTRACTED_CHARS {
+            tracing::info!("Rejected upload {}: over the per-order text limit", filename);
+            return Ok(batch_text_too_large());
+        }
+
+        uploads.push(ParsedUpload {
+            filename,
+            file_size,
+            kind: extracted.kind,
+            text_content: extracted.text,
+            text_encoding: extracted.encoding.map(str::to_string),
+        });
     }
 
-    Err(DataError::NotFound(errors::NO_FILE_PROVIDED))
+    if uploads.is_empty() {
+        return Err(DataError::NotFound(errors::NO_FILE_PROVIDED));
+    }
+    Ok(ParseResult::Success(uploads))
+}
+
+async fn extract_text(data: Vec<u8>) -> Result<extraction::Extracted, ExtractError> {
+    tokio::task::spawn_blocking(move || extraction::extract(&data))
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!("Text extraction task failed: {}", e);
+            Err(ExtractError::Unreadable)
+        })
+}
+
+/// "report.docx", or "report.docx and 2 more" for a batch.
+fn order_label(uploads: &[ParsedUpload]) -> String {
+    match uploads {
+        [only] => only.filename.clone(),
+        [first, rest @ ..] => format!("{} and {} more", first.filename, rest.len()),
+        [] => String::new(),
+    }
 }
 
 pub async fn post_forms_text_analyzer(
+    State(blob_store): State<SharedBlobStore>,
     Extension(current_user): Extension<CurrentUser>,
     session: Session,
     multipart: Multipart,
@@ -73,18 +185,44 @@ pub async fn post_forms_text_analyzer(
         CurrentUser::Guest => unreachable!("Protected route accessed by guest"),
     };
 
-    let upload = match parse_file_upload(multipart).await? {
-        ParseResult::FileTooLarge => {
-            return Ok(FlashMessage::error(format!("File too large. Maximum size is {} MB.", file_upload::MAX_FILE_SIZE / 1024 / 1024))
+    let uploads = match parse_file_uploads(multipart).await? {
+        ParseResult::Success(uploads) => uploads,
+        ParseResult::Rejected(message) => {
+            return Ok(FlashMessage::error(message)
                 .set_and_redirect(&session, paths::pages::TEXT_ANALYZER)
                 .await?);
         }
-        ParseResult::Success(upload) => upload,
     };
 
-    let text_length = upload.text_content.chars().count() as i32;
-    let calculated_price = text_length * pricing::PRICE_PER_CHARACTER;
-    let price_amount = calculated_price.max(pricing::MINIMUM_ORDER_AMOUNT);
+    let label = order_label(&uploads);
+    let rule_set = queries::pricing::get_current_pricing_rules().await?;
+    let lengths: Vec<i32> = uploads.iter().map(|upload| upload.text_content.chars().count() as i32).collect();
+    let files: Vec<(FileKind, i32)> = uploads
+        .iter()
+        .zip(&lengths)
+        .map(|(upload, &text_length)| (upload.kind, text_length))
+        .collect();
+    let priced = pricing::price_order(&rule_set.rules, &files);
+
+    // Same text, same blob: claim it back before storing, or housekeeping may
+    // delete what an expired order left behind between `put` and the new order.
+    let text_blobs: Vec<BlobKey> = uploads
+        .iter()
+        .map(|upload| BlobKey::for_content(upload.text_content.as_bytes()))
+        .collect();
+    commands::blob::keep_blobs(&text_blobs).await?;
+
+    let mut items = Vec::with_capacity(uploads.len());
+    for ((upload, text_length), price_amount) in uploads.into_iter().zip(lengths).zip(priced.item_prices) {
+        items.push(commands::order::NewOrderItem {
+            filename: upload.filename,
+            file_size: upload.file_size,
+            text_blob: storage::put_text(blob_store.as_ref(), upload.text_content).await?,
+            text_encoding: upload.text_encoding,
+            text_length,
+            price_amount,
+        });
+    }
 
     let order_number = OrderNumber::generate(&user_id);
 
@@ -92,12 +230,12 @@ pub async fn post_forms_text_analyzer(
         commands::order::CreateOrderParams {
             user_id,
             user_email,
-            filename: upload.filename,
-            file_size: upload.file_size,
-            text_content: upload.text_content,
-            text_length,
-            price_amount,
+            label,
+            price_amount: priced.total,
             order_number,
+            pricing_rule_set: rule_set.id,
+            pricing_version: rule_set.version,
+            items,
         },
     ).await?;
 
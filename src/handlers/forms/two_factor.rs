@@ -0,0 +1,154 @@
+use axum::{
+    Extension, Form,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use tower_sessions::Session;
+use validator::Validate;
+
+use crate::{
+    auth::{CurrentUser, two_factor::{self, SecondFactorCheck}},
+    config::AppConfig,
+    constants::messages,
+    data::{commands, queries},
+    handlers::errors::HandlerResult,
+    models::two_factor::{FIELD_CODE, TwoFactorCodeForm},
+    paths,
+    session::FlashMessage,
+    views::pages::{self, TwoFactorSetup},
+};
+
+use super::parse_validation_errors;
+
+/// Confirms enrollment with a first code, then shows the recovery codes once.
+pub async fn post_forms_two_factor_enable(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<TwoFactorCodeForm>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let CurrentUser::Authenticated { email, .. } = &current_user else {
+        unreachable!("require_authenticated rejects guests");
+    };
+
+    let credential = match queries::two_factor::get_totp_credential(user_id).await? {
+        Some(credential) if !credential.is_enabled() => credential,
+        Some(_) => {
+            return Ok(FlashMessage::info(messages::TWO_FACTOR_ALREADY_ENABLED)
+                .set_and_redirect(&session, paths::pages::ACCOUNT_TWO_FACTOR)
+                .await?);
+        }
+        None => return Ok(Redirect::to(paths::pages::ACCOUNT_TWO_FACTOR).into_response()),
+    };
+
+    let code_error = match form.validate() {
+        Err(errors) => parse_validation_errors(&errors).remove(FIELD_CODE),
+        Ok(()) => match two_factor::matching_step(&credential.secret, form.code.trim(), two_factor::current_unix_time())? {
+            Some(step) => {
+                let codes = two_factor::generate_recovery_codes();
+                let hashes = codes.iter().map(|code| two_factor::hash_recovery_code(code)).collect();
+                commands::two_factor::enable_totp(user_id, step as i64, hashes).await?;
+                two_factor::mark_verified(&session).await?;
+
+                return Ok(pages::recovery_codes(&current_user, None, config.site_name(), &codes).into_response());
+            }
+            None => Some(messages::TWO_FACTOR_CODE_INVALID.to_string()),
+        },
+    };
+
+    let otpauth_uri = two_factor::otpauth_uri(&credential.secret, config.site_name(), email)?;
+    let setup = TwoFactorSetup::Pending { secret: &credential.secret, otpauth_uri: &otpauth_uri };
+
+    Ok((
+        StatusCode::BAD_REQUEST,
+        pages::two_factor_setup(&current_user, None, config.site_name(), setup, code_error.as_deref()),
+    )
+        .into_response())
+}
+
+/// Disabling needs a current code so a hijacked session can't strip the second factor.
+pub async fn post_forms_two_factor_disable(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<TwoFactorCodeForm>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+
+    let Some(credential) = queries::two_factor::get_totp_credential(user_id)
+        .await?
+        .filter(|credential| credential.is_enabled())
+    else {
+        return Ok(Redirect::to(paths::pages::ACCOUNT_TWO_FACTOR).into_response());
+    };
+
+    if form.validate().is_ok() {
+        match two_factor::check_second_factor(user_id, &credential, &form.code).await? {
+            SecondFactorCheck::Passed => {
+                commands::two_factor::disable_totp(user_id).await?;
+                return Ok(FlashMessage::success(messages::TWO_FACTOR_DISABLED)
+                    .set_and_redirect(&session, paths::pages::ACCOUNT)
+                    .await?);
+            }
+            SecondFactorCheck::LockedOut => return locked_out(&config, &session).await,
+            SecondFactorCheck::Failed => {}
+        }
+    }
+
+    let unused_recovery_codes = queries::two_factor::count_unused_recovery_codes(user_id).await?;
+    let setup = TwoFactorSetup::Enabled { unused_recovery_codes };
+
+    Ok((
+        StatusCode::BAD_REQUEST,
+        pages::two_factor_setup(&current_user, None, config.site_name(), setup, Some(messages::TWO_FACTOR_CODE_INVALID)),
+    )
+        .into_response())
+}
+
+pub async fn post_forms_two_factor_verify(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Form(form): Form<TwoFactorCodeForm>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+
+    let Some(credential) = queries::two_factor::get_totp_credential(user_id)
+        .await?
+        .filter(|credential| credential.is_enabled())
+    else {
+        return Ok(Redirect::to(paths::pages::ACCOUNT_TWO_FACTOR).into_response());
+    };
+
+    if form.validate().is_ok() {
+        match two_factor::check_second_factor(user_id, &credential, &form.code).await? {
+            SecondFactorCheck::Passed => {
+                two_factor::mark_verified(&session).await?;
+                return Ok(Redirect::to(paths::pages::admin::HOME).into_response());
+            }
+            SecondFactorCheck::LockedOut => return locked_out(&config, &session).await,
+            SecondFactorCheck::Failed => {}
+        }
+    }
+
+    Ok((
+        StatusCode::BAD_REQUEST,
+        pages::two_factor_challenge(&current_user, None, config.site_name(), Some(messages::TWO_FACTOR_CODE_INVALID)),
+    )
+        .into_response())
+}
+
+/// Too many wrong codes: end the session so guessing has to start over from a
+/// fresh sign-in, and that still meets the lock.
+async fn locked_out(config: &AppConfig, session: &Session) -> HandlerResult {
+    session.flush().await?;
+    let flash = FlashMessage::error(messages::TWO_FACTOR_LOCKED);
+
+    Ok((
+        StatusCode::TOO_MANY_REQUESTS,
+        pages::sign_in(&CurrentUser::Guest, Some(&flash), config.site_name(), None, None),
+    )
+        .into_response())
+}
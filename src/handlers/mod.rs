@@ -1,7 +1,8 @@
-//! Handlers organized by interaction type (pages, forms, actions).
+//! Handlers organized by interaction type (pages, forms, actions, webhooks).
 
 pub mod actions;
 pub mod errors;
 pub mod fallback;
 pub mod forms;
 pub mod pages;
+pub mod webhooks;
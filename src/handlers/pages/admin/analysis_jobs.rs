@@ -0,0 +1,36 @@
+use axum::{Extension, extract::{Query, State}};
+use maud::Markup;
+use serde::Deserialize;
+
+use crate::{
+    auth::CurrentUser,
+    config::AppConfig,
+    constants::admin::ITEMS_PER_PAGE,
+    data::queries::analysis_job,
+    session::FlashMessage,
+    handlers::errors::HandlerError,
+    models::{admin::PaginatedResult, pagination::default_page},
+    views::pages::admin as admin_views,
+};
+
+#[derive(Deserialize)]
+pub struct AnalysisJobsQuery {
+    #[serde(default = "default_page")]
+    pub page: i64,
+}
+
+pub async fn get_admin_analysis_jobs(
+    State(config): State<AppConfig>,
+    Query(query): Query<AnalysisJobsQuery>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(flash): Extension<Option<FlashMessage>>,
+) -> Result<Markup, HandlerError> {
+    let page = query.page.max(1);
+
+    let jobs = analysis_job::get_failing_jobs_paginated(page, ITEMS_PER_PAGE).await?;
+    let total_count = analysis_job::get_failing_job_count().await?;
+
+    let paginated = PaginatedResult::new(jobs, total_count, page, ITEMS_PER_PAGE);
+
+    Ok(admin_views::analysis_jobs(&current_user, flash.as_ref(), config.site_name(), paginated))
+}
@@ -0,0 +1,21 @@
+use axum::{Extension, extract::State};
+use maud::Markup;
+
+use crate::{
+    auth::CurrentUser,
+    config::AppConfig,
+    data::queries::coupon,
+    session::FlashMessage,
+    handlers::errors::HandlerError,
+    views::pages::admin as admin_views,
+};
+
+pub async fn get_admin_coupons(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(flash): Extension<Option<FlashMessage>>,
+) -> Result<Markup, HandlerError> {
+    let coupons = coupon::get_all_coupons().await?;
+
+    Ok(admin_views::coupons(&current_user, flash.as_ref(), config.site_name(), &coupons))
+}
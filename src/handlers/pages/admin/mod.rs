@@ -1,11 +1,17 @@
+mod analysis_jobs;
+mod coupons;
 mod home;
 mod orders;
 mod order_detail;
+mod pricing;
 mod users;
 mod user_detail;
 
+pub use analysis_jobs::get_admin_analysis_jobs;
+pub use coupons::get_admin_coupons;
 pub use home::get_admin_home;
 pub use orders::get_admin_orders;
 pub use order_detail::get_admin_order_detail;
+pub use pricing::get_admin_pricing;
 pub use users::get_admin_users;
 pub use user_detail::get_admin_user_detail;
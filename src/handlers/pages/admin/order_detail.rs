@@ -5,7 +5,7 @@ use crate::{
     auth::CurrentUser,
     config::AppConfig,
     constants::errors,
-    data::queries::admin,
+    data::queries::{admin, order as order_queries, order_event, payment_event},
     session::FlashMessage,
     handlers::errors::HandlerError,
     models::OrderId,
@@ -20,11 +20,17 @@ pub async fn get_admin_order_detail(
 ) -> Result<Markup, HandlerError> {
     let order_id = OrderId::parse_or_not_found(&raw_order_id, errors::ORDER_NOT_FOUND)?;
     let order = admin::get_order_detail(&order_id).await?;
+    let items = order_queries::get_order_item_summaries(&order_id).await?;
+    let order_events = order_event::get_order_events(&order_id).await?;
+    let payment_events = payment_event::get_payment_events_for_order(&order_id).await?;
 
     Ok(admin_views::order_detail(
         &current_user,
         flash.as_ref(),
         config.site_name(),
         order,
+        items,
+        order_events,
+        payment_events,
     ))
 }
@@ -0,0 +1,23 @@
+use axum::{Extension, extract::State};
+use maud::Markup;
+
+use crate::{
+    auth::CurrentUser,
+    config::AppConfig,
+    constants::pricing::HISTORY_LIMIT,
+    data::queries::pricing,
+    session::FlashMessage,
+    handlers::errors::HandlerError,
+    views::pages::admin as admin_views,
+};
+
+pub async fn get_admin_pricing(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(flash): Extension<Option<FlashMessage>>,
+) -> Result<Markup, HandlerError> {
+    let current = pricing::get_current_pricing_rules().await?;
+    let history = pricing::get_pricing_history(HISTORY_LIMIT).await?;
+
+    Ok(admin_views::pricing(&current_user, flash.as_ref(), config.site_name(), &current, &history))
+}
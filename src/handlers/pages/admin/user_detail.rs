@@ -4,8 +4,8 @@ use maud::Markup;
 use crate::{
     auth::CurrentUser,
     config::AppConfig,
-    constants::admin::ITEMS_PER_PAGE,
-    data::queries::admin,
+    constants::{admin::ITEMS_PER_PAGE, credits},
+    data::queries::{self, admin},
     session::FlashMessage,
     handlers::errors::HandlerError,
     models::{pagination::PaginationQuery, admin::PaginatedResult, UserId},
@@ -29,11 +29,16 @@ pub async fn get_admin_user_detail(
     let total_count = admin::get_user_order_count(&user_id).await?;
     let paginated_orders = PaginatedResult::new(orders, total_count, page, ITEMS_PER_PAGE);
 
+    let credit_balance = queries::credit::get_credit_balance(&user_id).await?;
+    let credit_transactions = queries::credit::get_credit_transactions(&user_id, credits::HISTORY_LIMIT).await?;
+
     Ok(admin_views::user_detail(
         &current_user,
         flash.as_ref(),
         config.site_name(),
         user,
         paginated_orders,
+        credit_balance,
+        &credit_transactions,
     ))
 }
@@ -1,33 +1,67 @@
-use axum::{Extension, extract::{Path, State}};
+use axum::{Extension, extract::{Path, State}, response::IntoResponse};
 use maud::Markup;
+use tower_sessions::Session;
 
 use crate::{
     auth::CurrentUser,
     config::AppConfig,
-    constants::errors,
+    constants::{errors, messages},
     data::queries,
-    handlers::errors::HandlerError,
-    models::OrderId,
+    handlers::errors::{HandlerError, HandlerResult},
+    models::{order::PaymentStatus, CreditTopUpId, OrderId},
+    paths,
+    payment::SharedPaymentGateway,
     session::FlashMessage,
     views::pages,
 };
 
 pub async fn get_checkout(
     State(config): State<AppConfig>,
+    State(gateway): State<SharedPaymentGateway>,
     Extension(current_user): Extension<CurrentUser>,
     Extension(flash): Extension<Option<FlashMessage>>,
+    session: Session,
     Path(raw_order_id): Path<String>,
-) -> Result<Markup, HandlerError> {
+) -> HandlerResult {
     let user_id = current_user.require_authenticated()?;
     let order_id = OrderId::parse_or_not_found(&raw_order_id, errors::ORDER_NOT_FOUND)?;
 
     let order = queries::order::get_order_for_user(&order_id, user_id).await?;
 
+    // Paid, expired or failed orders have nothing to charge; a failed one is retried from the quote.
+    if order.payment_status != PaymentStatus::Pending {
+        return Ok(FlashMessage::error(messages::ORDER_ALREADY_PROCESSED)
+            .set_and_redirect(&session, &paths::helpers::quote_path(&order.id))
+            .await?);
+    }
+
     Ok(pages::checkout(
         &current_user,
         flash.as_ref(),
         config.site_name(),
         &order,
-        config.payment().toss_client_key(),
+        &gateway.checkout_widget(),
+    )
+    .into_response())
+}
+
+pub async fn get_top_up_checkout(
+    State(config): State<AppConfig>,
+    State(gateway): State<SharedPaymentGateway>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(flash): Extension<Option<FlashMessage>>,
+    Path(raw_top_up_id): Path<String>,
+) -> Result<Markup, HandlerError> {
+    let user_id = current_user.require_authenticated()?;
+    let top_up_id = CreditTopUpId::parse_or_not_found(&raw_top_up_id, errors::TOP_UP_NOT_FOUND)?;
+
+    let top_up = queries::credit::get_top_up_for_user(&top_up_id, user_id).await?;
+
+    Ok(pages::top_up_checkout(
+        &current_user,
+        flash.as_ref(),
+        config.site_name(),
+        &top_up,
+        &gateway.checkout_widget(),
     ))
 }
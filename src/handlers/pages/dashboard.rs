@@ -21,11 +21,16 @@ pub async fn get_dashboard(
     let recent_orders =
         queries::order::get_orders_for_user(user_id, constants::dashboard::RECENT_ORDERS_LIMIT)
             .await?;
+    let credit_balance = queries::credit::get_credit_balance(user_id).await?;
+    let credit_transactions =
+        queries::credit::get_credit_transactions(user_id, constants::credits::HISTORY_LIMIT).await?;
 
     Ok(pages::dashboard(
         &current_user,
         flash.as_ref(),
         config.site_name(),
         recent_orders,
+        credit_balance,
+        &credit_transactions,
     ))
 }
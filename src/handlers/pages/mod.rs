@@ -1,18 +1,28 @@
 pub mod admin;
+mod account;
 mod checkout;
 mod dashboard;
+mod invoice;
 mod payment_confirmation;
 mod quote;
+mod report_download;
 mod root;
+mod shared_report;
 mod sign_in;
 mod text_analyzer;
 mod todos;
+mod two_factor;
 
-pub use checkout::get_checkout;
+pub use account::get_account;
+pub use checkout::{get_checkout, get_top_up_checkout};
 pub use dashboard::get_dashboard;
-pub use payment_confirmation::get_payment_confirmation;
+pub use invoice::get_invoice;
+pub use payment_confirmation::{get_payment_confirmation, get_payment_confirmation_events};
 pub use quote::get_quote;
+pub use report_download::get_report_download;
 pub use root::get_root;
+pub use shared_report::get_shared_report;
 pub use sign_in::get_sign_in;
 pub use text_analyzer::get_text_analyzer;
 pub use todos::get_todos;
+pub use two_factor::{get_account_two_factor, get_two_factor};
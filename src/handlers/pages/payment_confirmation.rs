@@ -1,17 +1,39 @@
-use axum::{Extension, extract::{Path, State}};
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{Stream, stream};
 use maud::Markup;
+use tower_sessions::Session;
 
 use crate::{
-    auth::CurrentUser,
+    auth::{csrf, CurrentUser},
     config::AppConfig,
-    constants::errors,
-    data::{errors::DataError, queries},
+    constants::{analysis_jobs::PROGRESS_POLL_INTERVAL_MILLIS, errors},
+    data::{commands, errors::DataError, queries},
     handlers::errors::HandlerError,
-    models::{order::PaymentStatus, OrderId},
+    models::{
+        analysis_job::{AnalysisJob, AnalysisJobStatus},
+        order::Order,
+        OrderId, UserId,
+    },
     session::FlashMessage,
-    views::pages,
+    views::pages::{self, AnalysisProgress, ShareLinks},
 };
 
+async fn get_paid_order(raw_order_id: &str, user_id: &UserId) -> Result<Order, HandlerError> {
+    let order_id = OrderId::parse_or_not_found(raw_order_id, errors::ORDER_NOT_FOUND)?;
+    let order = queries::order::get_order_for_user(&order_id, user_id).await?;
+
+    if !order.payment_status.is_paid() {
+        return Err(DataError::Unauthorized(errors::PAYMENT_NOT_COMPLETED).into());
+    }
+    Ok(order)
+}
+
 pub async fn get_payment_confirmation(
     State(config): State<AppConfig>,
     Extension(current_user): Extension<CurrentUser>,
@@ -19,13 +41,106 @@ pub async fn get_payment_confirmation(
     Path(raw_order_id): Path<String>,
 ) -> Result<Markup, HandlerError> {
     let user_id = current_user.require_authenticated()?;
-    let order_id = OrderId::parse_or_not_found(&raw_order_id, errors::ORDER_NOT_FOUND)?;
+    let order = get_paid_order(&raw_order_id, user_id).await?;
 
-    let order = queries::order::get_order_for_user(&order_id, user_id).await?;
+    let job = commands::analysis_job::ensure_job_for_order(&order.id, order.item_count).await?;
 
-    if !matches!(order.payment_status, PaymentStatus::Paid) {
-        return Err(DataError::Unauthorized(errors::PAYMENT_NOT_COMPLETED).into());
-    }
+    let reports;
+    let links;
+    let csrf_token;
+    let progress = match job.status {
+        AnalysisJobStatus::Completed => {
+            reports = queries::order::get_order_reports(&order.id).await?;
+            links = queries::share_link::get_share_links_for_order(&order.id).await?;
+            csrf_token = csrf::current_token();
+            let share_links = ShareLinks {
+                base_url: config.base_url(),
+                links: &links,
+                csrf_token: &csrf_token,
+            };
+            AnalysisProgress::Complete(&reports, share_links)
+        }
+        AnalysisJobStatus::Failed => AnalysisProgress::Failed,
+        AnalysisJobStatus::Queued | AnalysisJobStatus::Running => AnalysisProgress::Running(&job),
+    };
+
+    Ok(pages::payment_confirmation(&current_user, flash.as_ref(), config.site_name(), &order.id, progress))
+}
+
+struct ProgressStream {
+    order_id: OrderId,
+    base_url: String,
+    /// The page that opened the stream stored one; the share forms need it.
+    csrf_token: String,
+    /// Status and file count last sent, so unchanged polls send nothing.
+    last_sent: Option<(AnalysisJobStatus, i32)>,
+}
+
+/// Server-sent events for the confirmation page: a "progress" fragment each
+/// time the job advances, then a "done" fragment once it has finished.
+pub async fn get_payment_confirmation_events(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Path(raw_order_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, HandlerError> {
+    let user_id = current_user.require_authenticated()?;
+    let order = get_paid_order(&raw_order_id, user_id).await?;
+    let csrf_token = csrf::stored_token(&session).await?.unwrap_or_default();
+
+    let initial = Some(ProgressStream {
+        order_id: order.id,
+        base_url: config.base_url().to_string(),
+        csrf_token,
+        last_sent: None,
+    });
+    let events = stream::unfold(initial, |state| async move {
+        let mut state = state?;
+        loop {
+            tokio::time::sleep(Duration::from_millis(PROGRESS_POLL_INTERVAL_MILLIS)).await;
+
+            let job = match queries::analysis_job::get_job_for_order(&state.order_id).await {
+                Ok(Some(job)) => job,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::error!("Failed to poll analysis progress for order {}: {}", state.order_id, e);
+                    return None;
+                }
+            };
+
+            if job.is_finished() {
+                let event = match finished_event(&job, &state.base_url, &state.csrf_token).await {
+                    Ok(event) => event,
+                    Err(e) => {
+                        tracing::error!("Failed to load analysis results for order {}: {}", state.order_id, e);
+                        return None;
+                    }
+                };
+                return Some((Ok(event), None));
+            }
+
+            let current = (job.status, job.items_done);
+            if state.last_sent != Some(current) {
+                state.last_sent = Some(current);
+                let event = Event::default()
+                    .event("progress")
+                    .data(pages::analysis_running(&job).into_string());
+                return Some((Ok(event), Some(state)));
+            }
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
 
-    Ok(pages::payment_confirmation(&current_user, flash.as_ref(), config.site_name(), &order))
+async fn finished_event(job: &AnalysisJob, base_url: &str, csrf_token: &str) -> Result<Event, DataError> {
+    let fragment = match job.status {
+        AnalysisJobStatus::Completed => {
+            let reports = queries::order::get_order_reports(&job.order).await?;
+            let links = queries::share_link::get_share_links_for_order(&job.order).await?;
+            pages::analysis_results(&job.order, &reports, &ShareLinks { base_url, links: &links, csrf_token })
+        }
+        _ => pages::analysis_failed(),
+    };
+    Ok(Event::default().event("done").data(fragment.into_string()))
 }
@@ -2,18 +2,21 @@ use axum::{Extension, extract::{Path, State}};
 use maud::Markup;
 
 use crate::{
+    analysis::PreviewBuilder,
     auth::CurrentUser,
     config::AppConfig,
     constants::errors,
-    data::queries,
+    data::{commands, errors::DataError, queries},
     handlers::errors::HandlerError,
-    models::OrderId,
+    models::{analysis::AnalysisPreview, order::OrderItemSummary, OrderId},
     session::FlashMessage,
+    storage::{self, BlobStore, SharedBlobStore},
     views::pages,
 };
 
 pub async fn get_quote(
     State(config): State<AppConfig>,
+    State(blob_store): State<SharedBlobStore>,
     Extension(current_user): Extension<CurrentUser>,
     Extension(flash): Extension<Option<FlashMessage>>,
     Path(raw_order_id): Path<String>,
@@ -22,6 +25,50 @@ pub async fn get_quote(
     let order_id = OrderId::parse_or_not_found(&raw_order_id, errors::ORDER_NOT_FOUND)?;
 
     let order = queries::order::get_order_for_user(&order_id, user_id).await?;
+    if order.expired_at.is_some() {
+        return Ok(pages::quote_expired(&current_user, flash.as_ref(), config.site_name(), &order));
+    }
+    let items = queries::order::get_order_item_summaries(&order.id).await?;
 
-    Ok(pages::quote(&current_user, flash.as_ref(), config.site_name(), &order))
+    let preview = match queries::order::get_order_preview(&order.id).await? {
+        Some(preview) => preview,
+        None => {
+            let preview = build_preview(blob_store.as_ref(), &items).await?;
+            commands::order::save_order_preview(&order.id, &preview).await?;
+            preview
+        }
+    };
+
+    let credit_balance = queries::credit::get_credit_balance(user_id).await?;
+
+    Ok(pages::quote(&current_user, flash.as_ref(), config.site_name(), &order, &items, &preview, credit_balance))
+}
+
+/// Reads the order's files one at a time; counting runs off the async runtime.
+async fn build_preview(blob_store: &dyn BlobStore, items: &[OrderItemSummary]) -> Result<AnalysisPreview, HandlerError> {
+    let mut builder = PreviewBuilder::default();
+    for summary in items {
+        // Inline text only comes with the full item.
+        let text = match &summary.text_blob {
+            Some(text_blob) => storage::get_text(blob_store, text_blob).await?,
+            None => {
+                let item = queries::order::get_order_item(&summary.id).await?;
+                // Only expired orders lack text, and their quote never gets this far.
+                let Some(text) = item.and_then(|item| item.text_content) else {
+                    continue;
+                };
+                text
+            }
+        };
+        builder = tokio::task::spawn_blocking(move || {
+            builder.add(&text);
+            builder
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("Preview counting task failed: {}", e);
+            DataError::CreationFailed(errors::PREVIEW_CREATION_FAILED)
+        })?;
+    }
+    Ok(builder.finish())
 }
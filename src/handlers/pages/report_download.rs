@@ -0,0 +1,69 @@
+use axum::{
+    Extension,
+    extract::Path,
+    http::header,
+    response::IntoResponse,
+};
+use tower_sessions::Session;
+
+use crate::{
+    auth::CurrentUser,
+    constants::errors,
+    data::{errors::DataError, queries},
+    export::{self, ExportError, ExportFormat, ReportExport},
+    handlers::errors::HandlerResult,
+    models::{analysis_job::AnalysisJobStatus, OrderId},
+    paths,
+    session::FlashMessage,
+};
+
+pub async fn get_report_download(
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    Path((raw_order_id, raw_format)): Path<(String, String)>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let order_id = OrderId::parse_or_not_found(&raw_order_id, errors::ORDER_NOT_FOUND)?;
+    let format = ExportFormat::parse(&raw_format).ok_or(DataError::NotFound(errors::REPORT_FORMAT_NOT_FOUND))?;
+
+    let order = queries::order::get_order_for_user(&order_id, user_id).await?;
+    if !order.payment_status.is_paid() {
+        return Err(DataError::Unauthorized(errors::PAYMENT_NOT_COMPLETED).into());
+    }
+
+    let job = queries::analysis_job::get_job_for_order(&order.id).await?;
+    if !job.is_some_and(|job| job.status == AnalysisJobStatus::Completed) {
+        return Ok(FlashMessage::error(errors::REPORT_NOT_READY)
+            .set_and_redirect(&session, &paths::helpers::payment_confirmation_path(&order.id))
+            .await?);
+    }
+
+    let export = ReportExport {
+        files: queries::order::get_order_reports(&order.id).await?,
+        order_number: order.order_number,
+        paid_at: order.paid_at,
+    };
+    let filename = export.filename(format);
+    // Subsetting fonts and laying out every file's tables is CPU-bound.
+    let rendered = tokio::task::spawn_blocking(move || export::render(format, &export))
+        .await
+        .map_err(ExportError::from)?;
+    let body = match rendered {
+        Ok(body) => body,
+        Err(ExportError::UnsupportedText) => {
+            return Ok(FlashMessage::error(errors::REPORT_PDF_UNSUPPORTED_TEXT)
+                .set_and_redirect(&session, &paths::helpers::payment_confirmation_path(&order.id))
+                .await?);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
+    )
+        .into_response())
+}
@@ -0,0 +1,66 @@
+use std::net::SocketAddr;
+
+use axum::{
+    Extension,
+    extract::{ConnectInfo, Path, State},
+    http::{HeaderMap, HeaderValue, header},
+    response::IntoResponse,
+};
+
+use crate::{
+    auth::CurrentUser,
+    config::AppConfig,
+    constants::{errors, share_links::MAX_USER_AGENT_LENGTH},
+    data::{commands, errors::DataError, queries},
+    handlers::errors::HandlerResult,
+    models::analysis_job::AnalysisJobStatus,
+    rate_limit::client_ip,
+    views::pages,
+};
+
+/// Public, read-only results behind a share link. Every visit is logged
+/// before anything is shown; revoked, expired and unknown links all 404.
+pub async fn get_shared_report(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(token): Path<String>,
+) -> HandlerResult {
+    let link = queries::share_link::get_active_share_link(&token)
+        .await?
+        .ok_or(DataError::NotFound(errors::SHARE_LINK_NOT_FOUND))?;
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.chars().take(MAX_USER_AGENT_LENGTH).collect());
+    // Same client address the rate limiter goes by.
+    let ip = client_ip(&headers, Some(addr.ip()), config.rate_limit().trusted_proxy_hops());
+    commands::share_link::record_share_link_access(&link.id, ip.map(|ip| ip.to_string()), user_agent).await?;
+
+    let order = queries::order::get_order(&link.order)
+        .await?
+        .filter(|order| order.payment_status.is_paid())
+        .ok_or(DataError::NotFound(errors::SHARE_LINK_NOT_FOUND))?;
+
+    let job = queries::analysis_job::get_job_for_order(&order.id).await?;
+    let reports = match job {
+        Some(job) if job.status == AnalysisJobStatus::Completed => {
+            Some(queries::order::get_order_reports(&order.id).await?)
+        }
+        _ => None,
+    };
+
+    let page = pages::shared_report(&current_user, config.site_name(), reports.as_deref());
+
+    // The token is the only credential: keep the page out of caches and search results.
+    Ok((
+        [
+            (header::CACHE_CONTROL, HeaderValue::from_static("no-store")),
+            (header::HeaderName::from_static("x-robots-tag"), HeaderValue::from_static("noindex, nofollow")),
+        ],
+        page,
+    )
+        .into_response())
+}
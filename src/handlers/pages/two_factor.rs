@@ -0,0 +1,69 @@
+use axum::{
+    Extension,
+    extract::State,
+    response::{IntoResponse, Redirect},
+};
+use tower_sessions::Session;
+
+use crate::{
+    auth::{CurrentUser, two_factor},
+    config::AppConfig,
+    data::{commands, queries},
+    handlers::errors::HandlerResult,
+    paths,
+    session::FlashMessage,
+    views::pages::{self, TwoFactorSetup},
+};
+
+/// Shows the enrollment QR code, reusing an unconfirmed secret so a refresh
+/// doesn't invalidate an authenticator that already scanned it.
+pub async fn get_account_two_factor(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(flash): Extension<Option<FlashMessage>>,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+    let CurrentUser::Authenticated { email, .. } = &current_user else {
+        unreachable!("require_authenticated rejects guests");
+    };
+
+    let secret = match queries::two_factor::get_totp_credential(user_id).await? {
+        Some(credential) if credential.is_enabled() => {
+            let unused_recovery_codes = queries::two_factor::count_unused_recovery_codes(user_id).await?;
+            let setup = TwoFactorSetup::Enabled { unused_recovery_codes };
+            return Ok(pages::two_factor_setup(&current_user, flash.as_ref(), config.site_name(), setup, None)
+                .into_response());
+        }
+        Some(pending) => pending.secret,
+        None => {
+            let secret = two_factor::new_secret();
+            commands::two_factor::start_totp_enrollment(user_id, &secret).await?;
+            secret
+        }
+    };
+
+    let otpauth_uri = two_factor::otpauth_uri(&secret, config.site_name(), email)?;
+    let setup = TwoFactorSetup::Pending { secret: &secret, otpauth_uri: &otpauth_uri };
+
+    Ok(pages::two_factor_setup(&current_user, flash.as_ref(), config.site_name(), setup, None).into_response())
+}
+
+pub async fn get_two_factor(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(flash): Extension<Option<FlashMessage>>,
+    session: Session,
+) -> HandlerResult {
+    let user_id = current_user.require_authenticated()?;
+
+    if two_factor::is_verified(&session).await? {
+        return Ok(Redirect::to(paths::pages::admin::HOME).into_response());
+    }
+
+    match queries::two_factor::get_totp_credential(user_id).await? {
+        Some(credential) if credential.is_enabled() => {
+            Ok(pages::two_factor_challenge(&current_user, flash.as_ref(), config.site_name(), None).into_response())
+        }
+        _ => Ok(Redirect::to(paths::pages::ACCOUNT_TWO_FACTOR).into_response()),
+    }
+}
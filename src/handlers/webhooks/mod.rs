@@ -0,0 +1,3 @@
+mod toss;
+
+pub use toss::post_webhooks_toss;
@@ -0,0 +1,263 @@
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+
+use crate::{
+    config::AppConfig,
+    constants::payment,
+    email::{self, EmailConfig},
+    data::{
+        commands::{self, order::OrderEventParams, payment_event::RecordPaymentEventParams},
+        errors::DataError,
+        queries,
+    },
+    handlers::errors::HandlerResult,
+    models::{
+        credit::CreditTopUp,
+        order::{Order, PaymentStatus},
+        payment_event::WebhookOutcome,
+        OrderNumber,
+    },
+    payment::{
+        webhook::{self, WebhookEvent},
+        reverse_charge, PaymentRecord, SharedPaymentGateway,
+    },
+};
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> &'a str {
+    headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default()
+}
+
+/// Receives Toss payment events. Every delivery is recorded in `payment_event`, with its
+/// payload capped, whether or not it is acted on. Toss signs only some event types, so a
+/// signature is checked when one is sent and a bad one is refused; the payload itself only
+/// tells us *which* payment changed — its status is re-fetched from the provider, so a
+/// forged, replayed or stale event cannot move an order.
+pub async fn post_webhooks_toss(
+    State(config): State<AppConfig>,
+    State(gateway): State<SharedPaymentGateway>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> HandlerResult {
+    let payload = String::from_utf8_lossy(&body).into_owned();
+    let event = serde_json::from_str::<WebhookEvent>(&payload).ok();
+
+    let signature_valid = match (config.payment().webhook_secret(), header_str(&headers, payment::WEBHOOK_SIGNATURE_HEADER)) {
+        (Some(secret), signature) if !signature.is_empty() => Some(webhook::verify_signature(
+            secret,
+            &payload,
+            header_str(&headers, payment::WEBHOOK_TRANSMISSION_TIME_HEADER),
+            signature,
+        )),
+        _ => None,
+    };
+
+    let mut record = RecordPaymentEventParams {
+        provider: payment::PROVIDER_TOSS,
+        event_type: event.as_ref().map_or_else(|| payment::WEBHOOK_EVENT_UNKNOWN.to_string(), |e| e.event_type.clone()),
+        order_id: None,
+        order_number: event.as_ref().map(|e| OrderNumber::from(e.data.order_id.clone())),
+        payment_key: event.as_ref().map(|e| e.data.payment_key.clone()),
+        provider_status: None,
+        payload,
+        signature_valid,
+        outcome: WebhookOutcome::Ignored,
+    };
+
+    let (outcome, status_code) = match &event {
+        _ if signature_valid == Some(false) => {
+            tracing::warn!(bytes = body.len(), "Rejected payment webhook with invalid signature");
+            (WebhookOutcome::InvalidSignature, StatusCode::UNAUTHORIZED)
+        }
+        None => {
+            tracing::warn!(bytes = body.len(), "Rejected malformed payment webhook payload");
+            (WebhookOutcome::Malformed, StatusCode::BAD_REQUEST)
+        }
+        Some(event) if event.event_type == payment::WEBHOOK_EVENT_PAYMENT_STATUS_CHANGED => {
+            apply_status_change(&gateway, config.email(), event, &mut record).await?
+        }
+        Some(_) => (WebhookOutcome::Ignored, StatusCode::OK),
+    };
+
+    record.outcome = outcome;
+    commands::payment_event::record_payment_event(record).await?;
+
+    Ok(status_code.into_response())
+}
+
+async fn apply_status_change(
+    gateway: &SharedPaymentGateway,
+    email_config: &EmailConfig,
+    event: &WebhookEvent,
+    record: &mut RecordPaymentEventParams,
+) -> Result<(WebhookOutcome, StatusCode), DataError> {
+    let order_number = OrderNumber::from(event.data.order_id.clone());
+    if order_number.is_top_up() {
+        return apply_top_up_status_change(gateway, event, &order_number, record).await;
+    }
+
+    let Some(order) = queries::order::get_order_by_order_number(&order_number).await? else {
+        tracing::warn!("Payment webhook for unknown order {}", order_number);
+        return Ok((WebhookOutcome::OrderNotFound, StatusCode::OK));
+    };
+    record.order_id = Some(order.id.clone());
+
+    let payment = match gateway.lookup(&event.data.payment_key).await {
+        Ok(payment) => payment,
+        Err(e) => {
+            // Non-2xx makes the provider redeliver later.
+            tracing::error!("Payment lookup for webhook on order {} failed: {}", order_number, e);
+            return Ok((WebhookOutcome::LookupFailed, StatusCode::SERVICE_UNAVAILABLE));
+        }
+    };
+    record.provider_status = Some(payment.status.as_str().to_string());
+
+    let outcome = reconcile_order(gateway, email_config, &order, &payment).await?;
+    // Non-2xx makes the provider redeliver, and the reversal is tried again.
+    let status_code = if outcome == WebhookOutcome::ReversalFailed {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    Ok((outcome, status_code))
+}
+
+/// Credit top-ups share the order number space and the provider, but live in their own table.
+async fn apply_top_up_status_change(
+    gateway: &SharedPaymentGateway,
+    event: &WebhookEvent,
+    order_number: &OrderNumber,
+    record: &mut RecordPaymentEventParams,
+) -> Result<(WebhookOutcome, StatusCode), DataError> {
+    let Some(top_up) = queries::credit::get_top_up_by_order_number(order_number).await? else {
+        tracing::warn!("Payment webhook for unknown top-up {}", order_number);
+        return Ok((WebhookOutcome::OrderNotFound, StatusCode::OK));
+    };
+
+    let payment = match gateway.lookup(&event.data.payment_key).await {
+        Ok(payment) => payment,
+        Err(e) => {
+            tracing::error!("Payment lookup for webhook on top-up {} failed: {}", order_number, e);
+            return Ok((WebhookOutcome::LookupFailed, StatusCode::SERVICE_UNAVAILABLE));
+        }
+    };
+    record.provider_status = Some(payment.status.as_str().to_string());
+
+    Ok((reconcile_top_up(&top_up, &payment).await?, StatusCode::OK))
+}
+
+async fn reconcile_top_up(top_up: &CreditTopUp, payment: &PaymentRecord) -> Result<WebhookOutcome, DataError> {
+    if payment.order_number != top_up.order_number {
+        tracing::warn!("Payment {} belongs to {}, not {}", payment.payment_key, payment.order_number, top_up.order_number);
+        return Ok(WebhookOutcome::Ignored);
+    }
+
+    let Some(target_status) = payment.status.to_payment_status() else {
+        return Ok(WebhookOutcome::Ignored);
+    };
+
+    if top_up.payment_status == target_status {
+        return Ok(WebhookOutcome::AlreadyApplied);
+    }
+
+    if top_up.payment_status != PaymentStatus::Pending {
+        tracing::warn!(
+            "Webhook wants top-up {} {} but it is already {}",
+            top_up.order_number,
+            target_status.as_str(),
+            top_up.payment_status.as_str()
+        );
+        return Ok(WebhookOutcome::Ignored);
+    }
+
+    if target_status == PaymentStatus::Paid && payment.total_amount != top_up.amount {
+        tracing::error!(
+            "Webhook amount mismatch for top-up {}: expected {}, got {}",
+            top_up.order_number,
+            top_up.amount,
+            payment.total_amount
+        );
+        return Ok(WebhookOutcome::AmountMismatch);
+    }
+
+    match commands::credit::update_top_up_payment(&top_up.id, &payment.payment_key, target_status).await {
+        Ok(_) => {
+            tracing::info!("Webhook moved top-up {} to {}", top_up.order_number, target_status.as_str());
+            Ok(WebhookOutcome::Applied)
+        }
+        Err(DataError::NotFound(_)) => Ok(WebhookOutcome::AlreadyApplied),
+        Err(e) => Err(e),
+    }
+}
+
+async fn reconcile_order(
+    gateway: &SharedPaymentGateway,
+    email_config: &EmailConfig,
+    order: &Order,
+    payment: &PaymentRecord,
+) -> Result<WebhookOutcome, DataError> {
+    if payment.order_number != order.order_number {
+        tracing::warn!("Payment {} belongs to {}, not {}", payment.payment_key, payment.order_number, order.order_number);
+        return Ok(WebhookOutcome::Ignored);
+    }
+
+    let Some(target_status) = payment.status.to_payment_status() else {
+        return Ok(WebhookOutcome::Ignored);
+    };
+
+    // Refunds are recorded by the admin refund form, so once an order has been
+    // paid a paid or cancelled provider status only confirms what we hold.
+    let settled_earlier = order.payment_status.is_paid() || order.payment_status == PaymentStatus::Refunded;
+    if order.payment_status == target_status || (settled_earlier && target_status != PaymentStatus::Failed) {
+        return Ok(WebhookOutcome::AlreadyApplied);
+    }
+
+    // The order expired or was cancelled while the charge was in flight;
+    // nothing will deliver for it, so the money goes back.
+    if target_status == PaymentStatus::Paid && order.payment_status == PaymentStatus::Cancelled {
+        tracing::warn!("Webhook reports a charge for cancelled order {}; reversing it", order.order_number);
+        return Ok(if reverse_charge(gateway, order, &payment.payment_key).await {
+            WebhookOutcome::ChargeReversed
+        } else {
+            WebhookOutcome::ReversalFailed
+        });
+    }
+
+    if !order.payment_status.can_transition_to(target_status) {
+        tracing::warn!(
+            "Webhook wants order {} {} but it is already {}",
+            order.order_number,
+            target_status.as_str(),
+            order.payment_status.as_str()
+        );
+        return Ok(WebhookOutcome::Ignored);
+    }
+
+    if target_status == PaymentStatus::Paid && payment.total_amount != order.price_amount {
+        tracing::error!(
+            "Webhook amount mismatch for order {}: expected {}, got {}",
+            order.order_number,
+            order.price_amount,
+            payment.total_amount
+        );
+        return Ok(WebhookOutcome::AmountMismatch);
+    }
+
+    let event = OrderEventParams::provider().with_payload(payment.raw.clone());
+    match commands::order::settle_order_payment(&order.id, &payment.payment_key, target_status, event).await {
+        Ok(order) => {
+            tracing::info!("Webhook moved order {} to {}", order.order_number, target_status.as_str());
+            if let Err(e) = email::send_payment_update(email_config, &order).await {
+                tracing::error!("Failed to send payment email for order {}: {}", order.order_number, e);
+            }
+            Ok(WebhookOutcome::Applied)
+        }
+        // Lost the race against the browser redirect — the order already left `pending`.
+        Err(DataError::IllegalTransition(e)) if e.from == target_status => Ok(WebhookOutcome::AlreadyApplied),
+        Err(DataError::IllegalTransition(_)) => Ok(WebhookOutcome::Ignored),
+        Err(e) => Err(e),
+    }
+}
@@ -1,6 +1,5 @@
 use surrealdb::opt::auth::Root;
 
-use super::schema::init_schema;
 use crate::db::DB;
 
 pub async fn init_database(database_url: &str) {
@@ -42,6 +41,4 @@ pub async fn init_database(database_url: &str) {
         eprintln!("Failed to select namespace/database: {}", e);
         std::process::exit(1);
     });
-
-    init_schema().await;
 }
@@ -0,0 +1,34 @@
+use crate::migrations;
+
+/// Brings the schema up to date, or — when startup migrations are disabled —
+/// refuses to start against a database that is behind this build.
+pub async fn init_migrations(migrate_on_startup: bool) {
+    if migrate_on_startup {
+        run_migrations().await;
+        return;
+    }
+
+    let pending = migrations::pending().await.unwrap_or_else(|e| {
+        eprintln!("Failed to check database migrations: {}", e);
+        std::process::exit(1);
+    });
+
+    if !pending.is_empty() {
+        eprintln!("Database has {} pending migration(s):", pending.len());
+        for migration in pending {
+            eprintln!("  {:04} {}", migration.version, migration.name);
+        }
+        eprintln!("\nRun `cargo run -- migrate` or set MIGRATE_ON_STARTUP=true.");
+        std::process::exit(1);
+    }
+}
+
+pub async fn run_migrations() {
+    let applied = migrations::run_pending().await.unwrap_or_else(|e| {
+        eprintln!("Failed to run database migrations: {}", e);
+        std::process::exit(1);
+    });
+
+    let latest = migrations::MIGRATIONS.last().map_or(0, |m| m.version);
+    tracing::info!("Database schema at version {} ({} migration(s) applied now)", latest, applied.len());
+}
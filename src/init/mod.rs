@@ -1,8 +1,15 @@
 mod database;
 mod logging;
-mod schema;
+mod migrations;
+mod scheduler;
 mod session;
+mod shutdown;
+mod storage;
 
 pub use database::init_database;
 pub use logging::init_logging;
+pub use migrations::{init_migrations, run_migrations};
+pub use scheduler::init_scheduler;
 pub use session::init_session;
+pub use shutdown::shutdown_signal;
+pub use storage::init_blob_store;
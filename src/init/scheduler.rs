@@ -0,0 +1,21 @@
+use axum::extract::FromRef;
+
+use crate::{
+    config::{AppConfig, AppState},
+    jobs::{self, housekeeping::RateLimitPruneJob, RunningScheduler, Scheduler},
+    rate_limit::SharedRateLimiter,
+    storage::SharedBlobStore,
+};
+
+pub fn init_scheduler(state: &AppState) -> RunningScheduler {
+    let config = AppConfig::from_ref(state);
+
+    let mut scheduler = Scheduler::new();
+    jobs::housekeeping::register(&mut scheduler, config.jobs(), SharedBlobStore::from_ref(state));
+    jobs::analysis::register(&mut scheduler, config.jobs(), SharedBlobStore::from_ref(state));
+    scheduler.register(
+        RateLimitPruneJob::new(SharedRateLimiter::from_ref(state)),
+        config.rate_limit().window(),
+    );
+    scheduler.start()
+}
@@ -0,0 +1,53 @@
+use std::fmt::Display;
+
+use crate::{
+    config::{StorageBackend, StorageConfig},
+    data::{commands, queries},
+    storage::{self, BlobStore, SharedBlobStore},
+};
+
+const INLINE_TEXT_BATCH: i64 = 50;
+
+/// Opens the blob store, then moves any text still stored inline on order
+/// items — uploads from before blob storage — into it.
+pub async fn init_blob_store(config: &StorageConfig) -> SharedBlobStore {
+    let store = storage::create_blob_store(config);
+    // Moving text into memory would drop it from the database for good on
+    // exit; readers fall back to the inline text instead.
+    if matches!(config.backend(), StorageBackend::Memory) {
+        return store;
+    }
+
+    let moved = move_inline_text(store.as_ref()).await;
+    if moved > 0 {
+        tracing::info!("Moved the text of {} order item(s) into blob storage", moved);
+    }
+    store
+}
+
+async fn move_inline_text(store: &dyn BlobStore) -> usize {
+    let mut moved = 0;
+    loop {
+        let items = queries::order::get_items_with_inline_text(INLINE_TEXT_BATCH)
+            .await
+            .unwrap_or_else(|e| exit("Failed to load order items with inline text", e));
+        if items.is_empty() {
+            return moved;
+        }
+
+        for item in items {
+            let key = storage::put_text(store, item.text_content)
+                .await
+                .unwrap_or_else(|e| exit("Failed to store order item text", e));
+            commands::order::move_text_to_blob(&item.id, &key)
+                .await
+                .unwrap_or_else(|e| exit("Failed to point order item at its text blob", e));
+            moved += 1;
+        }
+    }
+}
+
+fn exit(context: &str, error: impl Display) -> ! {
+    eprintln!("{}: {}", context, error);
+    std::process::exit(1);
+}
@@ -0,0 +1,161 @@
+//! Works the `analysis_job` queue: reports for paid orders are generated here,
+//! off the request path. Each job covers one order and reports progress per
+//! file; failures back off and retry until `MAX_ATTEMPTS`, then wait for an
+//! admin to retry them.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use tokio::task::JoinSet;
+
+use crate::{
+    analysis,
+    config::JobsConfig,
+    constants::{
+        analysis_jobs::{LEASE_SECS, MAX_ATTEMPTS, MAX_CLAIMS_PER_TICK, RETRY_BASE_DELAY_SECS, RETRY_MAX_DELAY_SECS},
+        errors,
+    },
+    data::{commands, errors::DataError, queries},
+    models::analysis_job::AnalysisJob,
+    storage::{self, BlobStore, SharedBlobStore},
+};
+
+use super::{Job, JobError, Scheduler};
+
+pub fn register(scheduler: &mut Scheduler, config: &JobsConfig, blob_store: SharedBlobStore) {
+    scheduler.register(
+        AnalysisQueueJob {
+            workers: config.analysis_workers(),
+            blob_store,
+        },
+        config.analysis_poll_interval(),
+    );
+}
+
+/// Works through the queue on every tick, running up to `workers` jobs at once
+/// and claiming at most `MAX_CLAIMS_PER_TICK`.
+pub struct AnalysisQueueJob {
+    workers: usize,
+    blob_store: SharedBlobStore,
+}
+
+#[async_trait]
+impl Job for AnalysisQueueJob {
+    fn name(&self) -> &'static str {
+        "analysis_queue"
+    }
+
+    async fn run(&self) -> Result<String, JobError> {
+        let mut running = JoinSet::new();
+        let mut finished = 0;
+        let mut claimed = 0;
+        let mut claim_error = None;
+
+        loop {
+            // Stop claiming after an error, but let jobs already claimed finish.
+            while claim_error.is_none() && running.len() < self.workers && claimed < MAX_CLAIMS_PER_TICK {
+                match commands::analysis_job::claim_next_job(lease_deadline()).await {
+                    Ok(Some(job)) => {
+                        claimed += 1;
+                        running.spawn(run_analysis(job, self.blob_store.clone()));
+                    }
+                    Ok(None) => break,
+                    Err(e) => claim_error = Some(e),
+                }
+            }
+
+            match running.join_next().await {
+                Some(Ok(())) => finished += 1,
+                Some(Err(e)) => tracing::error!("Analysis task panicked: {}", e),
+                None => break,
+            }
+        }
+
+        if let Some(e) = claim_error {
+            return Err(e.into());
+        }
+        // Nothing queued is the common case; an empty summary keeps it out of the logs.
+        if finished == 0 {
+            return Ok(String::new());
+        }
+        Ok(format!("{} analysis jobs run", finished))
+    }
+}
+
+async fn run_analysis(job: AnalysisJob, blob_store: SharedBlobStore) {
+    let outcome = match analyze_order(&job, blob_store.as_ref()).await {
+        Ok(()) => {
+            tracing::info!("Analysis job {} completed for order {}", job.id, job.order);
+            commands::analysis_job::complete_job(&job).await
+        }
+        Err(e) => {
+            let retry_at = (job.attempts < MAX_ATTEMPTS).then(|| Utc::now() + retry_delay(job.attempts));
+            tracing::warn!(
+                "Analysis job {} failed on attempt {}/{} ({}): {}",
+                job.id,
+                job.attempts,
+                MAX_ATTEMPTS,
+                if retry_at.is_some() { "will retry" } else { "giving up" },
+                e
+            );
+            commands::analysis_job::record_failure(&job, &e.to_string(), retry_at).await
+        }
+    };
+
+    match outcome {
+        Ok(()) => {}
+        // The lease ran out, so the job is another claim's to settle now.
+        Err(DataError::NotFound(_)) => tracing::warn!("Analysis job {} outlived its lease; dropping this outcome", job.id),
+        Err(e) => tracing::error!("Failed to record outcome of analysis job {}: {}", job.id, e),
+    }
+}
+
+/// Items that already have a report are skipped, so a retry resumes where the
+/// last attempt stopped.
+async fn analyze_order(job: &AnalysisJob, blob_store: &dyn BlobStore) -> Result<(), JobError> {
+    let pending = queries::order::get_unanalyzed_item_ids(&job.order).await?;
+    let mut items_done = job.items_total - pending.len() as i32;
+    commands::analysis_job::record_progress(job, items_done, lease_deadline()).await?;
+
+    for item_id in pending {
+        let item = queries::order::get_order_item(&item_id)
+            .await?
+            .ok_or(DataError::NotFound(errors::ORDER_ITEM_NOT_FOUND))?;
+
+        let text = storage::get_item_text(blob_store, item.text_blob.as_ref(), item.text_content)
+            .await?
+            .ok_or(DataError::NotFound(errors::ORDER_ITEM_TEXT_NOT_FOUND))?;
+
+        let report = tokio::task::spawn_blocking(move || analysis::analyze(&text))
+            .await
+            .map_err(|e| JobError::Failed(format!("analysis task failed: {}", e)))?;
+        commands::order::save_analysis_report(&item_id, &report).await?;
+
+        items_done += 1;
+        commands::analysis_job::record_progress(job, items_done, lease_deadline()).await?;
+    }
+    Ok(())
+}
+
+fn lease_deadline() -> DateTime<Utc> {
+    Utc::now() + Duration::seconds(LEASE_SECS)
+}
+
+/// Exponential backoff after the given (1-based) attempt, capped at `RETRY_MAX_DELAY_SECS`.
+fn retry_delay(attempt: i32) -> Duration {
+    let doublings = attempt.saturating_sub(1).clamp(0, 20) as u32;
+    let seconds = RETRY_BASE_DELAY_SECS.saturating_mul(1 << doublings).min(RETRY_MAX_DELAY_SECS);
+    Duration::seconds(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_cap() {
+        assert_eq!(retry_delay(1), Duration::seconds(RETRY_BASE_DELAY_SECS));
+        assert_eq!(retry_delay(2), Duration::seconds(RETRY_BASE_DELAY_SECS * 2));
+        assert_eq!(retry_delay(3), Duration::seconds(RETRY_BASE_DELAY_SECS * 4));
+        assert_eq!(retry_delay(40), Duration::seconds(RETRY_MAX_DELAY_SECS));
+    }
+}
@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tower_sessions::ExpiredDeletion;
+
+use crate::{
+    config::JobsConfig,
+    data::{commands, queries},
+    rate_limit::SharedRateLimiter,
+    session::SurrealSessionStore,
+    storage::SharedBlobStore,
+};
+
+use super::{Job, JobError, Scheduler};
+
+pub fn register(scheduler: &mut Scheduler, config: &JobsConfig, blob_store: SharedBlobStore) {
+    scheduler
+        .register(SessionCleanupJob, config.session_cleanup_interval())
+        .register(MagicLinkPurgeJob, config.magic_link_purge_interval())
+        .register(
+            StalePendingOrderJob {
+                ttl: config.pending_order_ttl(),
+                blob_store,
+            },
+            config.stale_order_interval(),
+        );
+}
+
+pub struct SessionCleanupJob;
+
+#[async_trait]
+impl Job for SessionCleanupJob {
+    fn name(&self) -> &'static str {
+        "session_cleanup"
+    }
+
+    async fn run(&self) -> Result<String, JobError> {
+        SurrealSessionStore::new()
+            .delete_expired()
+            .await
+            .map_err(|e| JobError::Failed(e.to_string()))?;
+
+        Ok("expired sessions deleted".to_string())
+    }
+}
+
+pub struct MagicLinkPurgeJob;
+
+#[async_trait]
+impl Job for MagicLinkPurgeJob {
+    fn name(&self) -> &'static str {
+        "magic_link_purge"
+    }
+
+    async fn run(&self) -> Result<String, JobError> {
+        let deleted = commands::magic_link::delete_expired_magic_links().await?;
+        Ok(format!("{} expired magic links deleted", deleted))
+    }
+}
+
+/// Expires checkouts that were abandoned before payment and deletes the
+/// uploaded text no other order uses, retrying deletes that failed before.
+pub struct StalePendingOrderJob {
+    ttl: Duration,
+    blob_store: SharedBlobStore,
+}
+
+#[async_trait]
+impl Job for StalePendingOrderJob {
+    fn name(&self) -> &'static str {
+        "stale_pending_orders"
+    }
+
+    async fn run(&self) -> Result<String, JobError> {
+        let ttl = chrono::Duration::from_std(self.ttl).map_err(|e| JobError::Failed(e.to_string()))?;
+        let expired = commands::order::expire_stale_pending_orders(Utc::now() - ttl).await?;
+        let (purged, failed) = self.collect_blobs().await?;
+
+        Ok(format!(
+            "{} stale pending orders expired, {} text blobs purged, {} left to retry",
+            expired.expired, purged, failed
+        ))
+    }
+}
+
+impl StalePendingOrderJob {
+    /// Deletes the listed blobs nothing points at. Blobs are shared by content,
+    /// so each is checked again right before it goes: an upload of the same
+    /// text may have taken it back since the list was read.
+    async fn collect_blobs(&self) -> Result<(usize, usize), JobError> {
+        let (mut purged, mut failed) = (0, 0);
+        for key in queries::blob::get_listed_blob_keys().await? {
+            if !queries::blob::is_blob_collectable(&key).await? {
+                commands::blob::forget_blob(&key).await?;
+                continue;
+            }
+
+            match self.blob_store.delete(&key).await {
+                Ok(()) => {
+                    commands::blob::forget_blob(&key).await?;
+                    purged += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Could not delete text blob {}, will retry: {}", key, e);
+                    commands::blob::record_blob_delete_failure(&key).await?;
+                    failed += 1;
+                }
+            }
+        }
+        Ok((purged, failed))
+    }
+}
+
+/// Forgets rate-limit keys whose attempts have all aged out of the window.
+pub struct RateLimitPruneJob {
+    limiter: SharedRateLimiter,
+}
+
+impl RateLimitPruneJob {
+    pub fn new(limiter: SharedRateLimiter) -> Self {
+        Self { limiter }
+    }
+}
+
+#[async_trait]
+impl Job for RateLimitPruneJob {
+    fn name(&self) -> &'static str {
+        "rate_limit_prune"
+    }
+
+    async fn run(&self) -> Result<String, JobError> {
+        let pruned = self.limiter.prune();
+        Ok(format!("{} idle rate limit keys pruned", pruned))
+    }
+}
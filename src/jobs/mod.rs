@@ -0,0 +1,8 @@
+//! In-process recurring jobs. Modules implement `Job` and register it on the
+//! `Scheduler` during startup; `main` stops the scheduler on shutdown.
+
+pub mod analysis;
+pub mod housekeeping;
+mod scheduler;
+
+pub use scheduler::{Job, JobError, RunningScheduler, Scheduler};
@@ -0,0 +1,151 @@
+use std::{sync::Arc, time::{Duration, Instant}};
+
+use async_trait::async_trait;
+use tokio::{sync::watch, task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{data::errors::DataError, storage::StorageError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum JobError {
+    #[error("{0}")]
+    Data(#[from] DataError),
+
+    #[error("{0}")]
+    Storage(#[from] StorageError),
+
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// A recurring unit of background work. Implement it anywhere and hand it to
+/// `Scheduler::register` — the returned summary is logged after each run.
+/// Jobs that poll often return an empty summary when there was nothing to do;
+/// those runs are only logged at debug level.
+#[async_trait]
+pub trait Job: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    async fn run(&self) -> Result<String, JobError>;
+}
+
+struct ScheduledJob {
+    job: Arc<dyn Job>,
+    interval: Duration,
+}
+
+/// Collects jobs before `start`; each job then runs on its own tokio task.
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<ScheduledJob>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, job: impl Job, interval: Duration) -> &mut Self {
+        self.jobs.push(ScheduledJob {
+            job: Arc::new(job),
+            interval,
+        });
+        self
+    }
+
+    /// Jobs run once immediately, then on every interval tick.
+    pub fn start(self) -> RunningScheduler {
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+        let handles = self
+            .jobs
+            .into_iter()
+            .map(|scheduled| {
+                tracing::info!("Scheduling job {} every {:?}", scheduled.job.name(), scheduled.interval);
+                tokio::spawn(run_job_loop(scheduled, shutdown_rx.clone()))
+            })
+            .collect();
+
+        RunningScheduler {
+            shutdown_tx,
+            handles,
+        }
+    }
+}
+
+async fn run_job_loop(scheduled: ScheduledJob, mut shutdown_rx: watch::Receiver<bool>) {
+    let mut ticker = tokio::time::interval(scheduled.interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => run_once(scheduled.job.as_ref()).await,
+            _ = shutdown_rx.changed() => break,
+        }
+    }
+
+    tracing::info!("Job {} stopped", scheduled.job.name());
+}
+
+async fn run_once(job: &dyn Job) {
+    let started = Instant::now();
+    match job.run().await {
+        Ok(summary) if summary.is_empty() => tracing::debug!("Job {} had nothing to do", job.name()),
+        Ok(summary) => tracing::info!("Job {} finished in {:?}: {}", job.name(), started.elapsed(), summary),
+        Err(e) => tracing::error!("Job {} failed after {:?}: {}", job.name(), started.elapsed(), e),
+    }
+}
+
+pub struct RunningScheduler {
+    shutdown_tx: watch::Sender<bool>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl RunningScheduler {
+    /// Signals every job loop and waits for in-flight runs to finish.
+    pub async fn shutdown(self) {
+        let _ = self.shutdown_tx.send(true);
+        for handle in self.handles {
+            if let Err(e) = handle.await {
+                tracing::error!("Job task panicked during shutdown: {}", e);
+            }
+        }
+        tracing::info!("Scheduler stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    struct CountingJob(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Job for CountingJob {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        async fn run(&self) -> Result<String, JobError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok("counted".to_string())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_jobs_run_on_interval_until_shutdown() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut scheduler = Scheduler::new();
+        scheduler.register(CountingJob(runs.clone()), Duration::from_secs(60));
+        let running = scheduler.start();
+
+        tokio::time::sleep(Duration::from_secs(150)).await;
+        running.shutdown().await;
+        let runs_at_shutdown = runs.load(Ordering::SeqCst);
+        assert_eq!(runs_at_shutdown, 3);
+
+        tokio::time::sleep(Duration::from_secs(600)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), runs_at_shutdown);
+    }
+}
@@ -1,16 +1,25 @@
+mod analysis;
 mod auth;
 mod config;
 mod constants;
 mod data;
 mod db;
 mod email;
+mod export;
+mod extraction;
 mod handlers;
 mod init;
+mod jobs;
 mod middlewares;
+mod migrations;
 mod models;
 mod paths;
+mod payment;
+mod pricing;
+mod rate_limit;
 mod routes;
 mod session;
+mod storage;
 mod views;
 
 use config::{AppConfig, AppState};
@@ -21,18 +30,38 @@ async fn main() {
 
     dotenvy::dotenv().ok();
 
+    let migrate_only = match std::env::args().nth(1).as_deref() {
+        None => false,
+        Some("migrate") => true,
+        Some(command) => {
+            eprintln!("Unknown command: {}", command);
+            eprintln!("\nUsage: axum_surreal_template [migrate]");
+            std::process::exit(1);
+        }
+    };
+
     let config = AppConfig::from_env().unwrap_or_else(|e| {
         eprintln!("Configuration error: {}", e);
         eprintln!("\nPlease check your .env file and ensure all required variables are set.");
-        eprintln!("Required: DATABASE_URL, SERVER_ADDR, SITE_NAME");
+        eprintln!("Required: DATABASE_URL, SERVER_ADDR, SITE_NAME, PAYMENT_MODE");
         std::process::exit(1);
     });
 
     init::init_database(config.database_url()).await;
+
+    if migrate_only {
+        init::run_migrations().await;
+        init::init_blob_store(config.storage()).await;
+        return;
+    }
+    init::init_migrations(config.migrate_on_startup()).await;
+    let blob_store = init::init_blob_store(config.storage()).await;
+
     let session_layer = init::init_session();
 
     let server_addr = config.server_addr().to_string();
-    let state = AppState::new(config);
+    let state = AppState::new(config, blob_store);
+    let scheduler = init::init_scheduler(&state);
 
     let listener = tokio::net::TcpListener::bind(&server_addr)
         .await
@@ -51,8 +80,13 @@ async fn main() {
     let app = routes::create_routes(state, session_layer)
         .into_make_service_with_connect_info::<std::net::SocketAddr>();
 
-    if let Err(e) = axum::serve(listener, app).await {
+    if let Err(e) = axum::serve(listener, app)
+        .with_graceful_shutdown(init::shutdown_signal())
+        .await
+    {
         tracing::error!("Server error: {}", e);
         std::process::exit(1);
     }
+
+    scheduler.shutdown().await;
 }
@@ -0,0 +1,261 @@
+use axum::{
+    Extension,
+    body::{Body, Bytes},
+    extract::{FromRequest, Multipart, Request, State},
+    http::{HeaderMap, Method, StatusCode, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_util::{StreamExt, future, stream};
+use tower_sessions::Session;
+
+use crate::{
+    auth::{
+        CurrentUser,
+        csrf::{self, CSRF_FORM_FIELD, CSRF_HEADER},
+    },
+    config::AppConfig,
+    constants::{
+        csrf::{MAX_BUFFERED_BODY_BYTES, MAX_MULTIPART_PREFIX_BYTES},
+        errors,
+    },
+    paths,
+    views::pages,
+};
+
+/// Routes that never render a form nor take one: provider callbacks and event streams.
+const EXEMPT_ROUTES: [&str; 1] = [paths::pages::PAYMENT_CONFIRMATION_EVENTS];
+/// Public routes that may show an existing token but never mint one.
+const READ_ONLY_ROUTES: [&str; 1] = [paths::pages::SHARED_REPORT];
+
+/// Checks the session's CSRF token on state-changing requests under /forms and
+/// /actions. HTMX and fetch() send it as a header (see `base_layout`); plain
+/// forms send it as the `_csrf` field (see `form::csrf_field`). A token is
+/// only minted, and the session only written, when a response embeds one.
+pub async fn csrf_protection(
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    session: Session,
+    req: Request,
+    next: Next,
+) -> Response {
+    let path = req.uri().path();
+    if is_under(path, paths::webhooks::BASE) || EXEMPT_ROUTES.iter().any(|route| matches_route(route, path)) {
+        return next.run(req).await;
+    }
+    let may_mint = !READ_ONLY_ROUTES.iter().any(|route| matches_route(route, path));
+
+    let stored = match csrf::stored_token(&session).await {
+        Ok(stored) => stored,
+        Err(e) => {
+            tracing::error!("Failed to load CSRF token from session: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Session error").into_response();
+        }
+    };
+
+    let req = if requires_token(req.method(), req.uri().path()) {
+        let (parts, body) = req.into_parts();
+        let (provided, body) = match provided_token(&parts, body).await {
+            Ok(found) => found,
+            Err(status) => return status.into_response(),
+        };
+
+        if !stored.as_deref().is_some_and(|token| csrf::tokens_match(token, provided.as_deref())) {
+            tracing::warn!(method = %parts.method, path = %parts.uri.path(), "Rejected request with missing or invalid CSRF token");
+            return (
+                StatusCode::FORBIDDEN,
+                pages::forbidden(&current_user, None, config.site_name(), errors::CSRF_TOKEN_INVALID),
+            )
+                .into_response();
+        }
+
+        Request::from_parts(parts, body)
+    } else {
+        req
+    };
+
+    let (response, minted) = csrf::scope(stored, may_mint, next.run(req)).await;
+    if let Some(token) = minted
+        && let Err(e) = csrf::store_token(&session, &token).await
+    {
+        tracing::error!("Failed to store CSRF token in session: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Session error").into_response();
+    }
+    response
+}
+
+fn requires_token(method: &Method, path: &str) -> bool {
+    let state_changing = matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE);
+    let protected = [paths::forms::BASE, paths::actions::BASE].iter().any(|base| is_under(path, base));
+
+    state_changing && protected
+}
+
+fn is_under(path: &str, base: &str) -> bool {
+    path.strip_prefix(base).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Whether `path` fits a route template such as `/shared/{token}`, each
+/// `{param}` standing for one non-empty segment.
+fn matches_route(template: &str, path: &str) -> bool {
+    let mut segments = path.split('/');
+    template.split('/').all(|expected| {
+        segments.next().is_some_and(|segment| {
+            if expected.starts_with('{') && expected.ends_with('}') {
+                !segment.is_empty()
+            } else {
+                segment == expected
+            }
+        })
+    }) && segments.next().is_none()
+}
+
+/// Returns the token the request carries and the body to hand on, which is
+/// whatever had to be read to find the token put back in front of the rest.
+async fn provided_token(parts: &Parts, body: Body) -> Result<(Option<String>, Body), StatusCode> {
+    if let Some(token) = header_value(&parts.headers, CSRF_HEADER) {
+        return Ok((Some(token), body));
+    }
+
+    let content_type = header_value(&parts.headers, header::CONTENT_TYPE.as_str()).unwrap_or_default();
+    if content_type.starts_with("multipart/form-data") {
+        return Ok(multipart_token(parts, body).await);
+    }
+
+    let bytes = axum::body::to_bytes(body, MAX_BUFFERED_BODY_BYTES)
+        .await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE)?;
+    let token = if content_type.starts_with("application/x-www-form-urlencoded") {
+        serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)
+            .ok()
+            .and_then(|fields| fields.into_iter().find_map(|(name, value)| (name == CSRF_FORM_FIELD).then_some(value)))
+    } else {
+        None
+    };
+    Ok((token, Body::from(bytes)))
+}
+
+/// Multipart bodies can be large uploads, so they are never buffered whole:
+/// forms send `_csrf` as their first field (see `form::csrf_field`) and only
+/// the start of the body is read until that field is complete.
+async fn multipart_token(parts: &Parts, body: Body) -> (Option<String>, Body) {
+    let mut chunks = body.into_data_stream();
+    let mut prefix = Vec::new();
+    let mut token = None;
+
+    while let Some(chunk) = chunks.next().await {
+        match chunk {
+            Ok(chunk) => prefix.extend_from_slice(&chunk),
+            Err(e) => {
+                tracing::debug!("Failed to read multipart body while looking for the CSRF field: {}", e);
+                break;
+            }
+        }
+
+        match leading_field(parts, Bytes::copy_from_slice(&prefix)).await {
+            LeadingField::Incomplete if prefix.len() < MAX_MULTIPART_PREFIX_BYTES => continue,
+            LeadingField::Token(found) => token = Some(found),
+            LeadingField::Incomplete | LeadingField::Other => {}
+        }
+        break;
+    }
+
+    let prefix = stream::once(future::ready(Ok::<_, axum::Error>(Bytes::from(prefix))));
+    (token, Body::from_stream(prefix.chain(chunks)))
+}
+
+enum LeadingField {
+    Token(String),
+    /// The first field isn't `_csrf`.
+    Other,
+    /// The first field hasn't been read in full yet.
+    Incomplete,
+}
+
+async fn leading_field(parts: &Parts, prefix: Bytes) -> LeadingField {
+    let req = Request::from_parts(parts.clone(), Body::from(prefix));
+    let Ok(mut multipart) = Multipart::from_request(req, &()).await else {
+        return LeadingField::Other;
+    };
+
+    // A field only ends at the next boundary, so a value cut short by the end
+    // of the prefix is an error here, never a truncated token.
+    match multipart.next_field().await {
+        Ok(Some(field)) if field.name() == Some(CSRF_FORM_FIELD) => match field.text().await {
+            Ok(token) => LeadingField::Token(token),
+            Err(_) => LeadingField::Incomplete,
+        },
+        Ok(Some(_)) => LeadingField::Other,
+        Ok(None) | Err(_) => LeadingField::Incomplete,
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requires_token_for_mutations_under_forms_and_actions() {
+        assert!(requires_token(&Method::POST, "/forms/sign_in"));
+        assert!(requires_token(&Method::DELETE, "/actions/todos/abc"));
+        assert!(requires_token(&Method::PATCH, "/actions/todos/abc/toggle"));
+
+        assert!(!requires_token(&Method::GET, "/actions/auth/verify"));
+        assert!(!requires_token(&Method::POST, "/webhooks/toss"));
+        assert!(!requires_token(&Method::POST, "/formsfoo"));
+    }
+
+    #[test]
+    fn test_matches_route_one_segment_per_param() {
+        assert!(matches_route(paths::pages::SHARED_REPORT, "/shared/abc"));
+        assert!(matches_route(paths::pages::PAYMENT_CONFIRMATION_EVENTS, "/payment_confirmation/o1/events"));
+
+        assert!(!matches_route(paths::pages::SHARED_REPORT, "/shared/"));
+        assert!(!matches_route(paths::pages::SHARED_REPORT, "/shared/abc/more"));
+        assert!(!matches_route(paths::pages::PAYMENT_CONFIRMATION_EVENTS, "/payment_confirmation/o1"));
+    }
+
+    fn multipart_request(fields: &[(&str, &str)]) -> (Parts, Vec<u8>) {
+        let mut body = Vec::new();
+        for (name, value) in fields {
+            body.extend_from_slice(
+                format!("--XYZ\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", name, value).as_bytes(),
+            );
+        }
+        body.extend_from_slice(b"--XYZ--\r\n");
+
+        let (parts, ()) = Request::builder()
+            .method(Method::POST)
+            .header(header::CONTENT_TYPE, "multipart/form-data; boundary=XYZ")
+            .body(())
+            .unwrap()
+            .into_parts();
+        (parts, body)
+    }
+
+    /// Arrives a few bytes at a time, like a slow upload.
+    fn trickled(body: &[u8]) -> Body {
+        let chunks: Vec<Result<Bytes, std::io::Error>> =
+            body.chunks(7).map(|chunk| Ok(Bytes::copy_from_slice(chunk))).collect();
+        Body::from_stream(stream::iter(chunks))
+    }
+
+    #[tokio::test]
+    async fn test_multipart_token_reads_leading_field_and_keeps_body_intact() {
+        let upload = "x".repeat(MAX_MULTIPART_PREFIX_BYTES * 2);
+        let (parts, body) = multipart_request(&[(CSRF_FORM_FIELD, "secret-token"), ("file", &upload)]);
+
+        let (token, passed_on) = multipart_token(&parts, trickled(&body)).await;
+        assert_eq!(token.as_deref(), Some("secret-token"));
+        let passed_on = axum::body::to_bytes(passed_on, usize::MAX).await.unwrap();
+        assert_eq!(passed_on, body);
+
+        let (parts, body) = multipart_request(&[("file", &upload), (CSRF_FORM_FIELD, "secret-token")]);
+        let (token, _) = multipart_token(&parts, trickled(&body)).await;
+        assert_eq!(token, None);
+    }
+}
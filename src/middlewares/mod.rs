@@ -1,13 +1,17 @@
 //! Middleware ordering is critical — see routes/mod.rs.
 
 mod auth;
+mod csrf;
 mod http_tracing;
+mod rate_limit;
 mod require_admin;
 mod security_headers;
 mod session;
 
 pub use auth::require_authentication;
+pub use csrf::csrf_protection;
 pub use http_tracing::create_http_trace_layer;
+pub use rate_limit::{rate_limit_contact, rate_limit_sign_in};
 pub use require_admin::require_admin;
 pub use security_headers::security_headers;
 pub use session::session_context;
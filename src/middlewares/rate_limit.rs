@@ -0,0 +1,107 @@
+use std::net::SocketAddr;
+
+use axum::{
+    Extension,
+    body::{Body, Bytes},
+    extract::{ConnectInfo, Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::{
+    auth::CurrentUser,
+    config::AppConfig,
+    constants::{messages, rate_limit::MAX_FORM_BODY_BYTES},
+    rate_limit::{client_ip, RateLimitScope, SharedRateLimiter},
+    session::FlashMessage,
+    views::pages,
+};
+
+/// The fields the rate-limited forms share; used for the per-email key and to refill the form.
+#[derive(Deserialize, Default)]
+struct LimitedFormFields {
+    #[serde(default)]
+    email: String,
+    #[serde(default)]
+    message: String,
+}
+
+pub async fn rate_limit_sign_in(
+    State(limiter): State<SharedRateLimiter>,
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    req: Request,
+    next: Next,
+) -> Response {
+    enforce(RateLimitScope::SignIn, &limiter, &config, &current_user, req, next).await
+}
+
+pub async fn rate_limit_contact(
+    State(limiter): State<SharedRateLimiter>,
+    State(config): State<AppConfig>,
+    Extension(current_user): Extension<CurrentUser>,
+    req: Request,
+    next: Next,
+) -> Response {
+    enforce(RateLimitScope::Contact, &limiter, &config, &current_user, req, next).await
+}
+
+/// Buffers the urlencoded body to read the email, then hands an identical request
+/// to the handler. Blocked requests get the form back with a 429 and Retry-After.
+async fn enforce(
+    scope: RateLimitScope,
+    limiter: &SharedRateLimiter,
+    config: &AppConfig,
+    current_user: &CurrentUser,
+    req: Request,
+    next: Next,
+) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let ip = client_ip(req.headers(), peer, config.rate_limit().trusted_proxy_hops());
+
+    let (parts, body) = req.into_parts();
+    let bytes: Bytes = match axum::body::to_bytes(body, MAX_FORM_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+    };
+    let fields: LimitedFormFields = serde_urlencoded::from_bytes(&bytes).unwrap_or_default();
+
+    match limiter.check(scope, ip, Some(&fields.email)) {
+        Ok(()) => next.run(Request::from_parts(parts, Body::from(bytes))).await,
+        Err(retry_after) => {
+            tracing::warn!(?scope, ?ip, email = %fields.email, "Rate limit exceeded");
+
+            let flash = FlashMessage::error(messages::RATE_LIMITED);
+            let page = match scope {
+                RateLimitScope::SignIn => {
+                    pages::sign_in(current_user, Some(&flash), config.site_name(), Some(&fields.email), None)
+                }
+                RateLimitScope::Contact => pages::root(
+                    current_user,
+                    Some(&flash),
+                    config.site_name(),
+                    Some(&fields.email),
+                    Some(&fields.message),
+                    None,
+                    None,
+                ),
+            };
+
+            // Round up so clients never retry a moment too early.
+            let retry_after_secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                page,
+            )
+                .into_response()
+        }
+    }
+}
+
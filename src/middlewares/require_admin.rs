@@ -1,9 +1,54 @@
-use axum::{extract::Request, http::StatusCode, middleware::Next, response::IntoResponse};
-use crate::{auth::CurrentUser, constants::errors};
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Redirect},
+};
+use tower_sessions::Session;
 
-pub async fn require_admin(req: Request, next: Next) -> axum::response::Response {
-    match req.extensions().get::<CurrentUser>() {
-        Some(current_user) if current_user.is_admin() => next.run(req).await,
-        _ => (StatusCode::FORBIDDEN, errors::FORBIDDEN).into_response(),
+use crate::{
+    auth::{two_factor, CurrentUser},
+    config::AppConfig,
+    constants::{errors, messages},
+    data::queries,
+    paths,
+    session::FlashMessage,
+};
+
+/// Admins must have passed the second factor since signing in. Enrolled admins are
+/// sent to the challenge; unenrolled ones to enrollment when ADMIN_TOTP_REQUIRED is set.
+pub async fn require_admin(
+    State(config): State<AppConfig>,
+    session: Session,
+    req: Request,
+    next: Next,
+) -> axum::response::Response {
+    let user_id = match req.extensions().get::<CurrentUser>() {
+        Some(current_user @ CurrentUser::Authenticated { user_id, .. }) if current_user.is_admin() => user_id.clone(),
+        _ => return (StatusCode::FORBIDDEN, errors::FORBIDDEN).into_response(),
+    };
+
+    match two_factor::is_verified(&session).await {
+        Ok(true) => return next.run(req).await,
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!("Failed to read second factor state from session: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Session error").into_response();
+        }
+    }
+
+    match queries::two_factor::get_totp_credential(&user_id).await {
+        Ok(Some(credential)) if credential.is_enabled() => Redirect::to(paths::pages::TWO_FACTOR).into_response(),
+        Ok(_) if config.admin_totp_required() => {
+            if let Err(e) = FlashMessage::info(messages::TWO_FACTOR_SETUP_REQUIRED).set(&session).await {
+                tracing::warn!("Failed to set flash message in admin middleware: {}", e);
+            }
+            Redirect::to(paths::pages::ACCOUNT_TWO_FACTOR).into_response()
+        }
+        Ok(_) => next.run(req).await,
+        Err(e) => {
+            tracing::error!("Failed to load TOTP credential: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Session error").into_response()
+        }
     }
 }
@@ -0,0 +1,144 @@
+//! Ordered schema migrations embedded from `migrations/*.surql`.
+//!
+//! Append new entries to `MIGRATIONS` with the next version number. Never edit a
+//! file that has been applied anywhere — its checksum is recorded and a mismatch
+//! stops the app from starting.
+
+mod runner;
+
+pub use runner::{pending, run_pending};
+
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("../../migrations/0001_initial_schema.surql"),
+    },
+    Migration {
+        version: 2,
+        name: "passkeys",
+        sql: include_str!("../../migrations/0002_passkeys.surql"),
+    },
+    Migration {
+        version: 3,
+        name: "two_factor",
+        sql: include_str!("../../migrations/0003_two_factor.surql"),
+    },
+    Migration {
+        version: 4,
+        name: "analysis_report",
+        sql: include_str!("../../migrations/0004_analysis_report.surql"),
+    },
+    Migration {
+        version: 5,
+        name: "text_encoding",
+        sql: include_str!("../../migrations/0005_text_encoding.surql"),
+    },
+    Migration {
+        version: 6,
+        name: "order_items",
+        sql: include_str!("../../migrations/0006_order_items.surql"),
+    },
+    Migration {
+        version: 7,
+        name: "analysis_jobs",
+        sql: include_str!("../../migrations/0007_analysis_jobs.surql"),
+    },
+    Migration {
+        version: 8,
+        name: "share_links",
+        sql: include_str!("../../migrations/0008_share_links.surql"),
+    },
+    Migration {
+        version: 9,
+        name: "text_blobs",
+        sql: include_str!("../../migrations/0009_text_blobs.surql"),
+    },
+    Migration {
+        version: 10,
+        name: "analysis_preview",
+        sql: include_str!("../../migrations/0010_analysis_preview.surql"),
+    },
+    Migration {
+        version: 11,
+        name: "pricing_rules",
+        sql: include_str!("../../migrations/0011_pricing_rules.surql"),
+    },
+    Migration {
+        version: 12,
+        name: "coupons",
+        sql: include_str!("../../migrations/0012_coupons.surql"),
+    },
+    Migration {
+        version: 13,
+        name: "credits",
+        sql: include_str!("../../migrations/0013_credits.surql"),
+    },
+    Migration {
+        version: 14,
+        name: "order_expiry",
+        sql: include_str!("../../migrations/0014_order_expiry.surql"),
+    },
+    Migration {
+        version: 15,
+        name: "order_events",
+        sql: include_str!("../../migrations/0015_order_events.surql"),
+    },
+    Migration {
+        version: 16,
+        name: "two_factor_lockout",
+        sql: include_str!("../../migrations/0016_two_factor_lockout.surql"),
+    },
+    Migration {
+        version: 17,
+        name: "credit_refunds",
+        sql: include_str!("../../migrations/0017_credit_refunds.surql"),
+    },
+    Migration {
+        version: 18,
+        name: "blob_gc",
+        sql: include_str!("../../migrations/0018_blob_gc.surql"),
+    },
+    Migration {
+        version: 19,
+        name: "webhook_signature",
+        sql: include_str!("../../migrations/0019_webhook_signature.surql"),
+    },
+    Migration {
+        version: 20,
+        name: "order_pending_since",
+        sql: include_str!("../../migrations/0020_order_pending_since.surql"),
+    },
+    Migration {
+        version: 21,
+        name: "analysis_job_lease_owner",
+        sql: include_str!("../../migrations/0021_analysis_job_lease_owner.surql"),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_sequential_from_one() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, index + 1, "migration {} is out of order", migration.name);
+        }
+    }
+
+    #[test]
+    fn test_names_are_unique_snake_case() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert!(!migration.name.is_empty());
+            assert!(migration.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'));
+            assert!(MIGRATIONS[..index].iter().all(|m| m.name != migration.name));
+        }
+    }
+}
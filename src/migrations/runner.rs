@@ -0,0 +1,163 @@
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::db::DB;
+
+use super::{Migration, MIGRATIONS};
+
+const MIGRATION_TABLE: &str = r#"
+DEFINE TABLE IF NOT EXISTS migration SCHEMAFULL;
+DEFINE FIELD IF NOT EXISTS version ON migration TYPE int;
+DEFINE FIELD IF NOT EXISTS name ON migration TYPE string;
+DEFINE FIELD IF NOT EXISTS checksum ON migration TYPE string;
+DEFINE FIELD IF NOT EXISTS applied_at ON migration TYPE datetime DEFAULT time::now();
+DEFINE INDEX IF NOT EXISTS version_idx ON migration FIELDS version UNIQUE;
+"#;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("Database error: {0}")]
+    Database(Box<surrealdb::Error>),
+
+    #[error("Migration {version} ({name}) was edited after it was applied (recorded checksum {recorded}, embedded {embedded})")]
+    ChecksumMismatch {
+        version: u32,
+        name: &'static str,
+        recorded: String,
+        embedded: String,
+    },
+
+    #[error("Database has migration {0} applied, but this build does not know about it")]
+    UnknownVersion(u32),
+
+    #[error("Migration {version} ({name}) failed: {source}")]
+    Failed {
+        version: u32,
+        name: &'static str,
+        source: Box<surrealdb::Error>,
+    },
+}
+
+impl From<surrealdb::Error> for MigrationError {
+    fn from(e: surrealdb::Error) -> Self {
+        Self::Database(Box::new(e))
+    }
+}
+
+#[derive(Deserialize)]
+struct AppliedMigration {
+    version: u32,
+    checksum: String,
+}
+
+fn checksum(sql: &str) -> String {
+    format!("{:x}", Sha256::digest(sql.as_bytes()))
+}
+
+/// Checks recorded migrations against the embedded ones and returns those not yet applied.
+pub async fn pending() -> Result<Vec<&'static Migration>, MigrationError> {
+    DB.query(MIGRATION_TABLE).await?.check()?;
+
+    let applied: Vec<AppliedMigration> = DB
+        .query("SELECT version, checksum FROM migration ORDER BY version")
+        .await?
+        .take(0)?;
+
+    verify_applied(&applied)?;
+
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|migration| !applied.iter().any(|a| a.version == migration.version))
+        .collect())
+}
+
+fn verify_applied(applied: &[AppliedMigration]) -> Result<(), MigrationError> {
+    for record in applied {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|m| m.version == record.version)
+            .ok_or(MigrationError::UnknownVersion(record.version))?;
+
+        let embedded = checksum(migration.sql);
+        if embedded != record.checksum {
+            return Err(MigrationError::ChecksumMismatch {
+                version: migration.version,
+                name: migration.name,
+                recorded: record.checksum.clone(),
+                embedded,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies every pending migration in version order, each in its own transaction
+/// together with its `migration` record. Returns the migrations that were applied.
+pub async fn run_pending() -> Result<Vec<&'static Migration>, MigrationError> {
+    let pending = pending().await?;
+
+    for migration in &pending {
+        apply(migration).await?;
+        tracing::info!("Applied migration {} ({})", migration.version, migration.name);
+    }
+
+    Ok(pending)
+}
+
+async fn apply(migration: &'static Migration) -> Result<(), MigrationError> {
+    let query = format!(
+        "BEGIN TRANSACTION;\n{}\nCREATE type::thing('migration', $version) CONTENT {{ version: $version, name: $name, checksum: $checksum }};\nCOMMIT TRANSACTION;",
+        migration.sql
+    );
+
+    let failed = |e: surrealdb::Error| MigrationError::Failed {
+        version: migration.version,
+        name: migration.name,
+        source: Box::new(e),
+    };
+
+    DB.query(query)
+        .bind(("version", migration.version))
+        .bind(("name", migration.name))
+        .bind(("checksum", checksum(migration.sql)))
+        .await
+        .map_err(failed)?
+        .check()
+        .map_err(failed)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_applied_accepts_matching_checksums() {
+        let applied: Vec<AppliedMigration> = MIGRATIONS
+            .iter()
+            .map(|m| AppliedMigration {
+                version: m.version,
+                checksum: checksum(m.sql),
+            })
+            .collect();
+
+        assert!(verify_applied(&applied).is_ok());
+    }
+
+    #[test]
+    fn test_verify_applied_rejects_edited_and_unknown_migrations() {
+        let edited = [AppliedMigration {
+            version: 1,
+            checksum: checksum("DEFINE TABLE something_else;"),
+        }];
+        assert!(matches!(verify_applied(&edited), Err(MigrationError::ChecksumMismatch { version: 1, .. })));
+
+        let unknown = [AppliedMigration {
+            version: u32::MAX,
+            checksum: String::new(),
+        }];
+        assert!(matches!(verify_applied(&unknown), Err(MigrationError::UnknownVersion(u32::MAX))));
+    }
+}
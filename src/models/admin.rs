@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::models::{order::PaymentStatus, OrderId, OrderNumber, UserId};
+use crate::models::{order::PaymentStatus, refund::Refund, OrderId, OrderNumber, UserId};
 
 pub use crate::models::pagination::PaginatedResult;
 
@@ -45,7 +45,31 @@ pub struct OrderDetail {
     pub payment_status: PaymentStatus,
     pub created_at: DateTime<Utc>,
     pub paid_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub expired_at: Option<DateTime<Utc>>,
     pub payment_key: Option<String>,
     pub filename: String,
     pub text_length: i32,
+    #[serde(default)]
+    pub pricing_version: Option<i32>,
+    #[serde(default)]
+    pub coupon_code: Option<String>,
+    #[serde(default)]
+    pub discount_amount: i32,
+    #[serde(default)]
+    pub paid_with_credits: bool,
+    #[serde(default)]
+    pub refunded_amount: i32,
+    #[serde(default)]
+    pub refunds: Vec<Refund>,
+}
+
+impl OrderDetail {
+    pub fn refundable_amount(&self) -> i32 {
+        if self.payment_status.is_paid() {
+            self.price_amount - self.refunded_amount
+        } else {
+            0
+        }
+    }
 }
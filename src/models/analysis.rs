@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// Structured text analysis stored on each item of a paid order. Produced by `analysis::analyze`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisReport {
+    pub character_count: usize,
+    pub word_count: usize,
+    pub sentence_count: usize,
+    pub paragraph_count: usize,
+    pub average_word_length: f64,
+    pub average_sentence_length: f64,
+    pub reading_time_seconds: u64,
+    pub flesch_reading_ease: f64,
+    pub top_words: Vec<TermCount>,
+    pub top_bigrams: Vec<TermCount>,
+    pub top_trigrams: Vec<TermCount>,
+}
+
+/// A stored report and the file it describes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileReport {
+    pub filename: String,
+    pub report: AnalysisReport,
+}
+
+/// What the quote page shows before payment: the order's word count and its
+/// leading keywords. Stored on the order the first time the quote is viewed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisPreview {
+    pub word_count: usize,
+    pub top_words: Vec<TermCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TermCount {
+    pub term: String,
+    pub count: usize,
+}
+
+impl AnalysisReport {
+    /// Conventional Flesch bands; scores run roughly 0 (hardest) to 100 (easiest).
+    pub fn readability_label(&self) -> &'static str {
+        match self.flesch_reading_ease {
+            score if score >= 90.0 => "Very easy",
+            score if score >= 70.0 => "Easy",
+            score if score >= 60.0 => "Standard",
+            score if score >= 50.0 => "Fairly difficult",
+            score if score >= 30.0 => "Difficult",
+            _ => "Very difficult",
+        }
+    }
+
+    pub fn reading_time_minutes(&self) -> u64 {
+        self.reading_time_seconds.div_ceil(60)
+    }
+}
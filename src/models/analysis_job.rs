@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AnalysisJobId, OrderId, OrderNumber};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalysisJobStatus {
+    Queued,
+    Running,
+    Completed,
+    /// Out of attempts; stays put until an admin retries it.
+    Failed,
+}
+
+impl AnalysisJobStatus {
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            Self::Queued => "Queued",
+            Self::Running => "Running",
+            Self::Completed => "Completed",
+            Self::Failed => "Failed",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Queued => "text-yellow-600",
+            Self::Running => "text-indigo-600",
+            Self::Completed => "text-green-600",
+            Self::Failed => "text-red-600",
+        }
+    }
+}
+
+/// Report generation for one paid order, tracked per file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisJob {
+    pub id: AnalysisJobId,
+    pub order: OrderId,
+    pub status: AnalysisJobStatus,
+    /// Claims so far, including the one in progress.
+    pub attempts: i32,
+    pub items_total: i32,
+    pub items_done: i32,
+    /// When a queued job becomes eligible; pushed back after each failure.
+    pub run_at: DateTime<Utc>,
+    /// Token of the claim the job is running under; `None` when it isn't.
+    #[serde(default)]
+    pub lease_owner: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl AnalysisJob {
+    pub fn progress_percent(&self) -> i32 {
+        if self.items_total == 0 {
+            return 0;
+        }
+        self.items_done * 100 / self.items_total
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, AnalysisJobStatus::Completed | AnalysisJobStatus::Failed)
+    }
+}
+
+/// Admin listing of jobs that have hit an error, with the order they belong to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisJobListItem {
+    pub id: AnalysisJobId,
+    pub order: OrderId,
+    pub order_number: OrderNumber,
+    pub user_email: String,
+    pub status: AnalysisJobStatus,
+    pub attempts: i32,
+    pub run_at: DateTime<Utc>,
+    /// Token of the claim the job is running under; `None` when it isn't.
+    #[serde(default)]
+    pub lease_owner: Option<String>,
+    pub last_error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,253 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{coupons, errors},
+    models::CouponId,
+};
+
+pub const FIELD_CODE: &str = "code";
+pub const FIELD_KIND: &str = "kind";
+pub const FIELD_VALUE: &str = "value";
+pub const FIELD_STARTS_ON: &str = "starts_on";
+pub const FIELD_ENDS_ON: &str = "ends_on";
+pub const FIELD_MAX_REDEMPTIONS: &str = "max_redemptions";
+pub const FIELD_MAX_REDEMPTIONS_PER_USER: &str = "max_redemptions_per_user";
+pub const FIELD_MIN_ORDER_AMOUNT: &str = "min_order_amount";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscountKind {
+    /// `value` is a percentage of the order.
+    Percent,
+    /// `value` is an amount in won.
+    Fixed,
+}
+
+/// Codes are matched case-insensitively and stored upper case.
+pub fn normalize_code(raw: &str) -> String {
+    raw.trim().to_uppercase()
+}
+
+/// Why a code can't be used on an order; the handler turns it into a message.
+#[derive(Debug, PartialEq, Eq)]
+pub enum CouponRejection {
+    Inactive,
+    NotStarted,
+    Expired,
+    UsedUp,
+    AlreadyUsed,
+    BelowMinimum(i32),
+}
+
+/// How often a code has been used, not counting the order it is being applied to.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct CouponUsage {
+    pub total: i64,
+    pub by_user: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Coupon {
+    pub id: CouponId,
+    pub code: String,
+    pub kind: DiscountKind,
+    pub value: i32,
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Exclusive: the code stops working at this instant.
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_redemptions: Option<i32>,
+    pub max_redemptions_per_user: Option<i32>,
+    pub min_order_amount: i32,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    /// Only filled in for the admin list.
+    #[serde(default)]
+    pub redemption_count: i64,
+}
+
+impl Coupon {
+    /// The discount on an order of `subtotal`, capped so the order still costs
+    /// at least `coupons::MIN_PAYABLE_AMOUNT`.
+    pub fn discount_for(&self, subtotal: i32, now: DateTime<Utc>, usage: CouponUsage) -> Result<i32, CouponRejection> {
+        if !self.active {
+            return Err(CouponRejection::Inactive);
+        }
+        if self.starts_at.is_some_and(|starts_at| starts_at > now) {
+            return Err(CouponRejection::NotStarted);
+        }
+        if self.ends_at.is_some_and(|ends_at| ends_at <= now) {
+            return Err(CouponRejection::Expired);
+        }
+        if self.max_redemptions.is_some_and(|max| usage.total >= i64::from(max)) {
+            return Err(CouponRejection::UsedUp);
+        }
+        if self.max_redemptions_per_user.is_some_and(|max| usage.by_user >= i64::from(max)) {
+            return Err(CouponRejection::AlreadyUsed);
+        }
+        if subtotal < self.min_order_amount {
+            return Err(CouponRejection::BelowMinimum(self.min_order_amount));
+        }
+
+        let discount = match self.kind {
+            DiscountKind::Percent => (i64::from(subtotal) * i64::from(self.value) / 100) as i32,
+            DiscountKind::Fixed => self.value,
+        };
+        Ok(discount.min(subtotal - coupons::MIN_PAYABLE_AMOUNT).max(0))
+    }
+}
+
+/// What an admin fills in to create a code.
+#[derive(Debug, Clone, Serialize)]
+pub struct NewCoupon {
+    pub code: String,
+    pub kind: DiscountKind,
+    pub value: i32,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub max_redemptions: Option<i32>,
+    pub max_redemptions_per_user: Option<i32>,
+    pub min_order_amount: i32,
+}
+
+/// Optional fields are empty strings when left blank. Dates are whole UTC days;
+/// the end date is the last day the code works.
+#[derive(Deserialize)]
+pub struct CouponForm {
+    pub code: String,
+    pub kind: DiscountKind,
+    pub value: String,
+    #[serde(default)]
+    pub starts_on: String,
+    #[serde(default)]
+    pub ends_on: String,
+    #[serde(default)]
+    pub max_redemptions: String,
+    #[serde(default)]
+    pub max_redemptions_per_user: String,
+    #[serde(default)]
+    pub min_order_amount: String,
+}
+
+#[derive(Deserialize)]
+pub struct ApplyCouponForm {
+    pub code: String,
+}
+
+impl CouponForm {
+    pub fn new_coupon(&self) -> Result<NewCoupon, &'static str> {
+        let code = normalize_code(&self.code);
+        if code.is_empty()
+            || code.len() > coupons::MAX_CODE_LENGTH
+            || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(errors::COUPON_CODE_INVALID);
+        }
+
+        let value = match (self.kind, self.value.trim().parse::<i32>()) {
+            (DiscountKind::Percent, Ok(percent)) if (1..=100).contains(&percent) => percent,
+            (DiscountKind::Fixed, Ok(amount)) if amount > 0 => amount,
+            _ => return Err(errors::COUPON_VALUE_INVALID),
+        };
+
+        let starts_at = parse_day(&self.starts_on)?;
+        let ends_at = parse_day(&self.ends_on)?.map(|last_day| last_day + Duration::days(1));
+        if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at)
+            && ends_at <= starts_at
+        {
+            return Err(errors::COUPON_DATES_INVALID);
+        }
+
+        Ok(NewCoupon {
+            code,
+            kind: self.kind,
+            value,
+            starts_at,
+            ends_at,
+            max_redemptions: parse_limit(&self.max_redemptions)?,
+            max_redemptions_per_user: parse_limit(&self.max_redemptions_per_user)?,
+            min_order_amount: match self.min_order_amount.trim() {
+                "" => 0,
+                raw => raw.parse::<i32>().ok().filter(|amount| *amount >= 0).ok_or(errors::COUPON_VALUE_INVALID)?,
+            },
+        })
+    }
+}
+
+fn parse_day(raw: &str) -> Result<Option<DateTime<Utc>>, &'static str> {
+    match raw.trim() {
+        "" => Ok(None),
+        raw => NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .map(|day| Some(day.and_time(chrono::NaiveTime::MIN).and_utc()))
+            .map_err(|_| errors::COUPON_DATES_INVALID),
+    }
+}
+
+fn parse_limit(raw: &str) -> Result<Option<i32>, &'static str> {
+    match raw.trim() {
+        "" => Ok(None),
+        raw => match raw.parse::<i32>() {
+            Ok(limit) if limit > 0 => Ok(Some(limit)),
+            _ => Err(errors::COUPON_LIMIT_INVALID),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coupon(kind: DiscountKind, value: i32) -> Coupon {
+        Coupon {
+            id: CouponId::parse("test").unwrap(),
+            code: "SPRING".to_string(),
+            kind,
+            value,
+            starts_at: None,
+            ends_at: None,
+            max_redemptions: Some(10),
+            max_redemptions_per_user: Some(1),
+            min_order_amount: 1_000,
+            active: true,
+            created_at: Utc::now(),
+            redemption_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_discount_amounts_are_capped() {
+        let now = Utc::now();
+        let usage = CouponUsage::default();
+
+        assert_eq!(coupon(DiscountKind::Percent, 15).discount_for(2_001, now, usage), Ok(300));
+        assert_eq!(coupon(DiscountKind::Fixed, 500).discount_for(2_000, now, usage), Ok(500));
+        assert_eq!(
+            coupon(DiscountKind::Fixed, 5_000).discount_for(2_000, now, usage),
+            Ok(2_000 - coupons::MIN_PAYABLE_AMOUNT)
+        );
+        assert_eq!(
+            coupon(DiscountKind::Percent, 100).discount_for(1_000, now, usage),
+            Ok(1_000 - coupons::MIN_PAYABLE_AMOUNT)
+        );
+    }
+
+    #[test]
+    fn test_discount_checks_window_limits_and_minimum() {
+        let now = Utc::now();
+        let usage = CouponUsage::default();
+        let mut code = coupon(DiscountKind::Fixed, 500);
+
+        assert_eq!(code.discount_for(999, now, usage), Err(CouponRejection::BelowMinimum(1_000)));
+        assert_eq!(code.discount_for(2_000, now, CouponUsage { total: 10, by_user: 0 }), Err(CouponRejection::UsedUp));
+        assert_eq!(code.discount_for(2_000, now, CouponUsage { total: 3, by_user: 1 }), Err(CouponRejection::AlreadyUsed));
+
+        code.ends_at = Some(now);
+        assert_eq!(code.discount_for(2_000, now, usage), Err(CouponRejection::Expired));
+        code.ends_at = None;
+        code.starts_at = Some(now + Duration::hours(1));
+        assert_eq!(code.discount_for(2_000, now, usage), Err(CouponRejection::NotStarted));
+        code.starts_at = None;
+        code.active = false;
+        assert_eq!(code.discount_for(2_000, now, usage), Err(CouponRejection::Inactive));
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{credits, errors},
+    models::{order::PaymentStatus, CreditTopUpId, CreditTransactionId, OrderId, OrderNumber, UserId},
+};
+
+pub const FIELD_AMOUNT: &str = "amount";
+pub const FIELD_NOTE: &str = "note";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CreditTransactionKind {
+    TopUp,
+    OrderPayment,
+    Adjustment,
+    Refund,
+}
+
+impl CreditTransactionKind {
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            Self::TopUp => "Top-up",
+            Self::OrderPayment => "Order payment",
+            Self::Adjustment => "Adjustment",
+            Self::Refund => "Refund",
+        }
+    }
+}
+
+/// One ledger entry. `amount` is negative for debits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditTransaction {
+    pub id: CreditTransactionId,
+    pub amount: i32,
+    pub balance_after: i32,
+    pub kind: CreditTransactionKind,
+    pub order: Option<OrderId>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A purchase of credit, paid by card. Uses the order payment statuses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditTopUp {
+    pub id: CreditTopUpId,
+    pub user: UserId,
+    pub order_number: OrderNumber,
+    pub amount: i32,
+    pub payment_status: PaymentStatus,
+    pub payment_key: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct TopUpForm {
+    pub amount: String,
+}
+
+impl TopUpForm {
+    /// Only the amounts offered on the dashboard are accepted.
+    pub fn amount(&self) -> Result<i32, &'static str> {
+        match self.amount.trim().parse::<i32>() {
+            Ok(amount) if credits::TOP_UP_CHOICES.contains(&amount) => Ok(amount),
+            _ => Err(errors::TOP_UP_AMOUNT_INVALID),
+        }
+    }
+}
+
+/// A signed amount: negative takes credit away.
+#[derive(Deserialize)]
+pub struct CreditAdjustmentForm {
+    pub amount: String,
+    pub note: String,
+}
+
+impl CreditAdjustmentForm {
+    pub fn adjustment(&self) -> Result<(i32, String), &'static str> {
+        let note = self.note.trim();
+        if note.is_empty() || note.chars().count() > credits::MAX_NOTE_LENGTH {
+            return Err(errors::CREDIT_ADJUSTMENT_INVALID);
+        }
+
+        match self.amount.trim().parse::<i32>() {
+            Ok(amount) if amount != 0 => Ok((amount, note.to_string())),
+            _ => Err(errors::CREDIT_ADJUSTMENT_INVALID),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjustment_needs_amount_and_note() {
+        let form = |amount: &str, note: &str| CreditAdjustmentForm {
+            amount: amount.to_string(),
+            note: note.to_string(),
+        };
+
+        assert_eq!(form("-500", " goodwill ").adjustment(), Ok((-500, "goodwill".to_string())));
+        assert!(form("0", "goodwill").adjustment().is_err());
+        assert!(form("5.5", "goodwill").adjustment().is_err());
+        assert!(form("500", "  ").adjustment().is_err());
+        assert!(form("500", &"x".repeat(credits::MAX_NOTE_LENGTH + 1)).adjustment().is_err());
+    }
+}
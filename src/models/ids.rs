@@ -75,3 +75,11 @@ macro_rules! define_id {
 define_id!(UserId, "user");
 define_id!(TodoId, "todo");
 define_id!(OrderId, "order");
+define_id!(OrderItemId, "order_item");
+define_id!(PasskeyId, "passkey");
+define_id!(AnalysisJobId, "analysis_job");
+define_id!(ShareLinkId, "share_link");
+define_id!(PricingRuleSetId, "pricing_rule_set");
+define_id!(CouponId, "coupon");
+define_id!(CreditTopUpId, "credit_top_up");
+define_id!(CreditTransactionId, "credit_transaction");
@@ -1,13 +1,24 @@
 pub mod admin;
+pub mod analysis;
+pub mod analysis_job;
 pub mod contact;
+pub mod coupon;
+pub mod credit;
 pub mod ids;
 pub mod order;
+pub mod order_event;
 pub mod order_number;
 pub mod pagination;
+pub mod passkey;
+pub mod payment_event;
+pub mod pricing;
+pub mod refund;
 pub mod role;
+pub mod share_link;
 pub mod sign_in;
 pub mod todo;
+pub mod two_factor;
 
-pub use ids::{OrderId, TodoId, UserId};
+pub use ids::{AnalysisJobId, CouponId, CreditTopUpId, CreditTransactionId, OrderId, OrderItemId, PasskeyId, PricingRuleSetId, ShareLinkId, TodoId, UserId};
 pub use order_number::OrderNumber;
 pub use role::Role;
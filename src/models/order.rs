@@ -1,33 +1,62 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::models::{OrderId, OrderNumber, UserId};
+use crate::{
+    models::{analysis::AnalysisReport, refund::Refund, OrderId, OrderItemId, OrderNumber, UserId},
+    storage::BlobKey,
+};
 
+/// Where an order stands with its payment. Moves only along the edges
+/// `can_transition_to` allows; every move is recorded in `order_event`.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum PaymentStatus {
     Pending,
     Paid,
     Failed,
     Cancelled,
+    PartiallyRefunded,
+    Refunded,
+}
+
+/// An order asked to move between statuses the state machine doesn't connect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Order cannot move from {} to {}", from.as_str(), to.as_str())]
+pub struct IllegalTransition {
+    pub from: PaymentStatus,
+    pub to: PaymentStatus,
 }
 
 impl PaymentStatus {
+    pub const ALL: [Self; 6] = [
+        Self::Pending,
+        Self::Paid,
+        Self::Failed,
+        Self::Cancelled,
+        Self::PartiallyRefunded,
+        Self::Refunded,
+    ];
+
+    /// Statuses that still grant access to the paid report.
+    pub const PAID: [Self; 2] = [Self::Paid, Self::PartiallyRefunded];
+
     pub fn display_text(&self) -> &'static str {
         match self {
             Self::Paid => "Paid",
             Self::Pending => "Pending",
             Self::Failed => "Failed",
             Self::Cancelled => "Cancelled",
+            Self::PartiallyRefunded => "Partially refunded",
+            Self::Refunded => "Refunded",
         }
     }
 
     pub fn css_class(&self) -> &'static str {
         match self {
-            Self::Paid => "text-green-600",
+            Self::Paid | Self::PartiallyRefunded => "text-green-600",
             Self::Pending => "text-yellow-600",
             Self::Failed => "text-red-600",
-            Self::Cancelled => "text-gray-600",
+            Self::Cancelled | Self::Refunded => "text-gray-600",
         }
     }
 
@@ -37,8 +66,38 @@ impl PaymentStatus {
             Self::Pending => "pending",
             Self::Failed => "failed",
             Self::Cancelled => "cancelled",
+            Self::PartiallyRefunded => "partially_refunded",
+            Self::Refunded => "refunded",
+        }
+    }
+
+    pub fn is_paid(&self) -> bool {
+        Self::PAID.contains(self)
+    }
+
+    /// Pending orders settle one way; paid ones can only be refunded, in
+    /// parts until nothing is left; failed ones can be retried.
+    pub fn can_transition_to(self, to: Self) -> bool {
+        matches!(
+            (self, to),
+            (Self::Pending, Self::Paid | Self::Failed | Self::Cancelled)
+                | (Self::Paid | Self::PartiallyRefunded, Self::PartiallyRefunded | Self::Refunded)
+                | (Self::Failed, Self::Pending)
+        )
+    }
+
+    pub fn transition_to(self, to: Self) -> Result<Self, IllegalTransition> {
+        if self.can_transition_to(to) {
+            Ok(to)
+        } else {
+            Err(IllegalTransition { from: self, to })
         }
     }
+
+    /// Every status an order may move to `to` from.
+    pub fn sources_of(to: Self) -> Vec<Self> {
+        Self::ALL.into_iter().filter(|from| from.can_transition_to(to)).collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,16 +105,76 @@ pub struct Order {
     pub id: OrderId,
     pub user: UserId,
     pub user_email: String,
+    /// Display label: the filename, or the first filename and a count for batches.
     pub filename: String,
+    /// Totals across all items.
     pub file_size: i32,
-    pub text_content: String,
     pub text_length: i32,
+    /// What the customer pays: the items' prices, raised to the minimum order
+    /// amount, less `discount_amount`.
     pub price_amount: i32,
+    #[serde(default)]
+    pub coupon_code: Option<String>,
+    #[serde(default)]
+    pub discount_amount: i32,
+    pub item_count: i32,
+    /// Pricing version the order was quoted with.
+    #[serde(default)]
+    pub pricing_version: Option<i32>,
     pub payment_status: PaymentStatus,
     pub payment_key: Option<String>,
+    /// Paid from the user's credit balance rather than by card.
+    #[serde(default)]
+    pub paid_with_credits: bool,
     pub order_number: OrderNumber,
     pub created_at: DateTime<Utc>,
     pub paid_at: Option<DateTime<Utc>>,
+    /// Set when the order was cancelled for going unpaid past its time to live.
+    #[serde(default)]
+    pub expired_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub refunded_amount: i32,
+    #[serde(default)]
+    pub refunds: Vec<Refund>,
+}
+
+/// One uploaded file in an order. The report lives here; the text is in the
+/// blob store under `text_blob`, or still inline in `text_content` for uploads
+/// from before blob storage when the blob store runs in memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderItem {
+    pub id: OrderItemId,
+    pub order: OrderId,
+    pub position: i32,
+    pub filename: String,
+    pub file_size: i32,
+    /// `None` for inline text, and once the order has expired and its text was purged.
+    #[serde(default)]
+    pub text_blob: Option<BlobKey>,
+    #[serde(default)]
+    pub text_content: Option<String>,
+    /// Charset the uploaded text was decoded from; `None` for DOCX/PDF uploads.
+    #[serde(default)]
+    pub text_encoding: Option<String>,
+    pub text_length: i32,
+    pub price_amount: i32,
+    #[serde(default)]
+    pub analysis_report: Option<AnalysisReport>,
+}
+
+/// An item without its text, for quote and order listings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderItemSummary {
+    pub id: OrderItemId,
+    pub filename: String,
+    pub file_size: i32,
+    /// `None` once the order has expired and its text was purged.
+    #[serde(default)]
+    pub text_blob: Option<BlobKey>,
+    #[serde(default)]
+    pub text_encoding: Option<String>,
+    pub text_length: i32,
+    pub price_amount: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,3 +188,39 @@ pub struct OrderSummary {
     pub order_number: OrderNumber,
     pub created_at: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_settles_once() {
+        for to in [PaymentStatus::Paid, PaymentStatus::Failed, PaymentStatus::Cancelled] {
+            assert_eq!(PaymentStatus::Pending.transition_to(to), Ok(to));
+        }
+        assert!(!PaymentStatus::Pending.can_transition_to(PaymentStatus::Refunded));
+        assert!(!PaymentStatus::Paid.can_transition_to(PaymentStatus::Failed));
+        assert!(!PaymentStatus::Cancelled.can_transition_to(PaymentStatus::Paid));
+    }
+
+    #[test]
+    fn test_refunds_and_retries() {
+        assert!(PaymentStatus::Paid.can_transition_to(PaymentStatus::PartiallyRefunded));
+        assert!(PaymentStatus::PartiallyRefunded.can_transition_to(PaymentStatus::PartiallyRefunded));
+        assert!(PaymentStatus::PartiallyRefunded.can_transition_to(PaymentStatus::Refunded));
+        assert!(PaymentStatus::Failed.can_transition_to(PaymentStatus::Pending));
+        assert_eq!(
+            PaymentStatus::Refunded.transition_to(PaymentStatus::Paid),
+            Err(IllegalTransition { from: PaymentStatus::Refunded, to: PaymentStatus::Paid })
+        );
+    }
+
+    #[test]
+    fn test_sources_of() {
+        assert_eq!(PaymentStatus::sources_of(PaymentStatus::Pending), vec![PaymentStatus::Failed]);
+        assert_eq!(
+            PaymentStatus::sources_of(PaymentStatus::Refunded),
+            vec![PaymentStatus::Paid, PaymentStatus::PartiallyRefunded]
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{order::PaymentStatus, UserId};
+
+/// Who moved an order's payment status.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActorKind {
+    Customer,
+    Admin,
+    /// The payment provider, through the webhook.
+    Provider,
+    /// Background jobs, such as expiring unpaid orders.
+    System,
+}
+
+impl ActorKind {
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            Self::Customer => "Customer",
+            Self::Admin => "Admin",
+            Self::Provider => "Payment provider",
+            Self::System => "System",
+        }
+    }
+}
+
+/// One payment status change, for the admin order timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderEvent {
+    /// `None` for the event that records how the order was created.
+    pub from_status: Option<PaymentStatus>,
+    pub to_status: PaymentStatus,
+    pub actor_kind: ActorKind,
+    pub actor: Option<UserId>,
+    pub actor_email: Option<String>,
+    /// The provider's response that justified the change, as received.
+    pub payload: Option<String>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
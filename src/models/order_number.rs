@@ -3,8 +3,11 @@ use uuid::Uuid;
 
 use crate::models::UserId;
 
+const ORDER_PREFIX: &str = "ORD";
+const TOP_UP_PREFIX: &str = "TOP";
+
 /// Strongly-typed order number for payment and order identification.
-/// Format: ORD-{user_id}-{uuid_prefix}
+/// Format: ORD-{user_id}-{uuid_prefix}, or TOP-… for credit top-ups.
 /// Distinct from OrderId (database record ID) — OrderNumber is human-readable
 /// and used in payment flows.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -13,12 +16,25 @@ pub struct OrderNumber(String);
 
 impl OrderNumber {
     pub fn generate(user_id: &UserId) -> Self {
+        Self::with_prefix(ORDER_PREFIX, user_id)
+    }
+
+    /// Credit top-ups go through the same payment flow under their own prefix.
+    pub fn generate_top_up(user_id: &UserId) -> Self {
+        Self::with_prefix(TOP_UP_PREFIX, user_id)
+    }
+
+    pub fn is_top_up(&self) -> bool {
+        self.0.starts_with(TOP_UP_PREFIX)
+    }
+
+    fn with_prefix(prefix: &str, user_id: &UserId) -> Self {
         let uuid_string = Uuid::new_v4().to_string();
         let uuid_prefix = uuid_string
             .split('-')
             .next()
             .expect("UUID should have at least one segment");
-        Self(format!("ORD-{}-{}", user_id, uuid_prefix))
+        Self(format!("{}-{}-{}", prefix, user_id, uuid_prefix))
     }
 
     pub fn as_str(&self) -> &str {
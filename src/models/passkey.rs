@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use validator::Validate;
+use webauthn_rs::prelude::RegisterPublicKeyCredential;
+
+use crate::constants::validation::PASSKEY_NAME_MAX_LENGTH;
+
+use super::{PasskeyId, UserId};
+
+// MUST match struct field names — passkeys.js posts the name input under this key
+pub const FIELD_NAME: &str = "name";
+
+/// JSON body posted by passkeys.js once the browser has created a credential.
+#[derive(Deserialize, Validate)]
+pub struct FinishRegistrationRequest {
+    #[validate(length(min = 1, max = PASSKEY_NAME_MAX_LENGTH, message = "Passkey name must be 1-64 characters"))]
+    pub name: String,
+    pub credential: RegisterPublicKeyCredential,
+}
+
+/// Listing row for the account page.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Passkey {
+    pub id: PasskeyId,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Stored credential loaded for a WebAuthn ceremony. `credential` is the
+/// serialized webauthn-rs passkey — decode it via `auth::passkey`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PasskeyCredential {
+    pub user: UserId,
+    pub user_handle: String,
+    pub credential: String,
+}
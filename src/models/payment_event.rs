@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How a received webhook event was handled — stored for the admin audit trail.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookOutcome {
+    Applied,
+    AlreadyApplied,
+    Ignored,
+    InvalidSignature,
+    Malformed,
+    OrderNotFound,
+    AmountMismatch,
+    LookupFailed,
+    /// Paid for an order that had already been cancelled, so the charge was refunded.
+    ChargeReversed,
+    ReversalFailed,
+}
+
+impl WebhookOutcome {
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            Self::Applied => "Applied",
+            Self::AlreadyApplied => "Already applied",
+            Self::Ignored => "Ignored",
+            Self::InvalidSignature => "Invalid signature",
+            Self::Malformed => "Malformed",
+            Self::OrderNotFound => "Order not found",
+            Self::AmountMismatch => "Amount mismatch",
+            Self::LookupFailed => "Lookup failed",
+            Self::ChargeReversed => "Charge reversed",
+            Self::ReversalFailed => "Reversal failed",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Applied => "text-green-600",
+            Self::AlreadyApplied | Self::Ignored => "text-gray-600",
+            Self::LookupFailed | Self::ChargeReversed => "text-yellow-600",
+            Self::InvalidSignature
+            | Self::Malformed
+            | Self::OrderNotFound
+            | Self::AmountMismatch
+            | Self::ReversalFailed => "text-red-600",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentEvent {
+    pub event_type: String,
+    pub payment_key: Option<String>,
+    pub provider_status: Option<String>,
+    pub outcome: WebhookOutcome,
+    /// `None` for unsigned deliveries.
+    #[serde(default)]
+    pub signature_valid: Option<bool>,
+    pub payload: String,
+    #[serde(default)]
+    pub payload_truncated: bool,
+    pub received_at: DateTime<Utc>,
+}
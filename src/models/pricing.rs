@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{coupons::MIN_PAYABLE_AMOUNT, file_upload::ACCEPTED_EXTENSIONS, pricing::MAX_AMOUNT},
+    models::{PricingRuleSetId, UserId},
+};
+
+pub const FIELD_RATE_PER_THOUSAND: &str = "rate_per_thousand";
+pub const FIELD_MINIMUM_ORDER_AMOUNT: &str = "minimum_order_amount";
+pub const FIELD_TIERS: &str = "tiers";
+pub const FIELD_SURCHARGES: &str = "surcharges";
+
+/// From `min_characters` in the whole order up, every character is charged at
+/// this rate instead of the base one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PricingTier {
+    pub min_characters: i32,
+    pub rate_per_thousand: i32,
+}
+
+/// A flat amount added to each file whose detected type this extension names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileTypeSurcharge {
+    /// Lowercase, without the dot.
+    pub extension: String,
+    pub amount: i32,
+}
+
+/// Rates are in won per 1,000 characters so volume tiers can go below ₩1 a character.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PricingRules {
+    pub rate_per_thousand: i32,
+    pub minimum_order_amount: i32,
+    /// Sorted by `min_characters`.
+    #[serde(default)]
+    pub tiers: Vec<PricingTier>,
+    #[serde(default)]
+    pub surcharges: Vec<FileTypeSurcharge>,
+}
+
+/// One published version of the rules. Versions are never edited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricingRuleSet {
+    pub id: PricingRuleSetId,
+    pub version: i32,
+    pub rules: PricingRules,
+    /// `None` for the version seeded by the migration.
+    pub created_by: Option<UserId>,
+    pub created_by_email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tiers and surcharges are edited as text, one `key = value` entry per line.
+#[derive(Deserialize)]
+pub struct PricingRulesForm {
+    pub rate_per_thousand: String,
+    pub minimum_order_amount: String,
+    #[serde(default)]
+    pub tiers: String,
+    #[serde(default)]
+    pub surcharges: String,
+}
+
+impl PricingRulesForm {
+    pub fn rules(&self) -> Result<PricingRules, String> {
+        let rate_per_thousand = parse_amount(&self.rate_per_thousand, "Rate per 1,000 characters")?;
+        let minimum_order_amount = parse_amount(&self.minimum_order_amount, "Minimum order amount")?;
+        // Every order is raised to the minimum, so it is what keeps prices chargeable.
+        if minimum_order_amount < MIN_PAYABLE_AMOUNT {
+            return Err(format!("Minimum order amount must be at least {}, the smallest card payment", MIN_PAYABLE_AMOUNT));
+        }
+
+        let mut tiers = Vec::new();
+        for (key, value) in entries(&self.tiers, "Tier")? {
+            let min_characters = match key.parse::<i32>() {
+                Ok(min) if min > 0 => min,
+                _ => return Err(format!("Tier \"{}\": the character count must be a whole number above 0", key)),
+            };
+            if tiers.iter().any(|tier: &PricingTier| tier.min_characters == min_characters) {
+                return Err(format!("Tier \"{}\" is listed twice", key));
+            }
+            tiers.push(PricingTier {
+                min_characters,
+                rate_per_thousand: parse_amount(value, &format!("Tier \"{}\"", key))?,
+            });
+        }
+        tiers.sort_by_key(|tier| tier.min_characters);
+
+        let mut surcharges = Vec::new();
+        for (key, value) in entries(&self.surcharges, "Surcharge")? {
+            let extension = key.trim_start_matches('.').to_lowercase();
+            if !is_accepted_extension(&extension) {
+                return Err(format!("Surcharge \"{}\": uploads of that type are not accepted", key));
+            }
+            if surcharges.iter().any(|surcharge: &FileTypeSurcharge| surcharge.extension == extension) {
+                return Err(format!("Surcharge \"{}\" is listed twice", key));
+            }
+            surcharges.push(FileTypeSurcharge {
+                extension,
+                amount: parse_amount(value, &format!("Surcharge \"{}\"", key))?,
+            });
+        }
+
+        Ok(PricingRules {
+            rate_per_thousand,
+            minimum_order_amount,
+            tiers,
+            surcharges,
+        })
+    }
+}
+
+impl PricingRules {
+    /// The tiers in the editor's text format.
+    pub fn tiers_text(&self) -> String {
+        self.tiers
+            .iter()
+            .map(|tier| format!("{} = {}", tier.min_characters, tier.rate_per_thousand))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The surcharges in the editor's text format.
+    pub fn surcharges_text(&self) -> String {
+        self.surcharges
+            .iter()
+            .map(|surcharge| format!("{} = {}", surcharge.extension, surcharge.amount))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn parse_amount(raw: &str, label: &str) -> Result<i32, String> {
+    match raw.trim().parse::<i32>() {
+        Ok(amount) if (0..=MAX_AMOUNT).contains(&amount) => Ok(amount),
+        _ => Err(format!("{} must be a whole number from 0 to {}", label, MAX_AMOUNT)),
+    }
+}
+
+/// Blank lines are skipped; anything else must be `key = value`.
+fn entries<'a>(text: &'a str, label: &str) -> Result<Vec<(&'a str, &'a str)>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.split_once('=') {
+            Some((key, value)) => Ok((key.trim(), value.trim())),
+            None => Err(format!("{} \"{}\" must look like \"key = value\"", label, line)),
+        })
+        .collect()
+}
+
+fn is_accepted_extension(extension: &str) -> bool {
+    ACCEPTED_EXTENSIONS
+        .split(',')
+        .any(|accepted| accepted.trim_start_matches('.') == extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(tiers: &str, surcharges: &str) -> PricingRulesForm {
+        PricingRulesForm {
+            rate_per_thousand: "1000".to_string(),
+            minimum_order_amount: "100".to_string(),
+            tiers: tiers.to_string(),
+            surcharges: surcharges.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rules_parse_sorts_tiers_and_normalises_extensions() {
+        let rules = form("100000 = 600\n\n 10000=800 ", ".PDF = 300").rules().unwrap();
+
+        assert_eq!(rules.tiers.iter().map(|tier| tier.min_characters).collect::<Vec<_>>(), [10_000, 100_000]);
+        assert_eq!(rules.surcharges, [FileTypeSurcharge { extension: "pdf".to_string(), amount: 300 }]);
+        assert_eq!(form(&rules.tiers_text(), &rules.surcharges_text()).rules().unwrap(), rules);
+    }
+
+    #[test]
+    fn test_rules_parse_rejects_bad_entries() {
+        assert!(form("10000", "").rules().is_err());
+        assert!(form("0 = 500", "").rules().is_err());
+        assert!(form("10000 = 800\n10000 = 700", "").rules().is_err());
+        assert!(form("10000 = -1", "").rules().is_err());
+        assert!(form("", "exe = 100").rules().is_err());
+        assert!(form("", "pdf = 100\n.pdf = 200").rules().is_err());
+
+        let mut below_payable = form("", "");
+        below_payable.minimum_order_amount = (MIN_PAYABLE_AMOUNT - 1).to_string();
+        assert!(below_payable.rules().is_err());
+        below_payable.minimum_order_amount = "0".to_string();
+        assert!(below_payable.rules().is_err());
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::UserId;
+
+pub const FIELD_AMOUNT: &str = "amount";
+pub const FIELD_REASON: &str = "reason";
+
+/// `amount` stays a string so an empty field can mean "refund the remaining balance".
+#[derive(Deserialize, Validate)]
+pub struct RefundForm {
+    pub amount: String,
+    #[validate(length(min = 1, message = "Refund reason cannot be empty"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Refund {
+    pub amount: i32,
+    pub reason: String,
+    pub refunded_by: UserId,
+    pub refunded_by_email: String,
+    pub refunded_at: DateTime<Utc>,
+}
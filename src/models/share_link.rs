@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{errors, share_links::EXPIRY_CHOICES_DAYS},
+    models::{OrderId, ShareLinkId},
+};
+
+pub const FIELD_EXPIRES_IN_DAYS: &str = "expires_in_days";
+
+/// `expires_in_days` is empty for a link that never expires.
+#[derive(Deserialize)]
+pub struct ShareLinkForm {
+    pub expires_in_days: String,
+}
+
+impl ShareLinkForm {
+    /// Only the lifetimes offered in the form are accepted.
+    pub fn expires_in_days(&self) -> Result<Option<i64>, &'static str> {
+        let raw = self.expires_in_days.trim();
+        if raw.is_empty() {
+            return Ok(None);
+        }
+
+        match raw.parse::<i64>() {
+            Ok(days) if EXPIRY_CHOICES_DAYS.contains(&days) => Ok(Some(days)),
+            _ => Err(errors::SHARE_LINK_EXPIRY_INVALID),
+        }
+    }
+}
+
+/// A link the owner has handed out, with how often it has been opened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareLink {
+    pub id: ShareLinkId,
+    pub order: OrderId,
+    pub token: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub access_count: i64,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+}
+
+impl ShareLink {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+
+    pub fn status_text(&self, now: DateTime<Utc>) -> &'static str {
+        if self.revoked_at.is_some() {
+            "Revoked"
+        } else if self.is_active(now) {
+            "Active"
+        } else {
+            "Expired"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(expires_in_days: &str) -> ShareLinkForm {
+        ShareLinkForm {
+            expires_in_days: expires_in_days.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_expires_in_days_accepts_offered_choices_only() {
+        assert_eq!(form("").expires_in_days(), Ok(None));
+        assert_eq!(form("7").expires_in_days(), Ok(Some(7)));
+        assert!(form("2").expires_in_days().is_err());
+        assert!(form("-7").expires_in_days().is_err());
+        assert!(form("soon").expires_in_days().is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use validator::Validate;
+
+// MUST match struct field names for proper form deserialization
+pub const FIELD_CODE: &str = "code";
+
+#[derive(Deserialize, Validate)]
+pub struct TwoFactorCodeForm {
+    #[validate(length(min = 1, message = "Enter a code"))]
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TotpCredential {
+    pub secret: String,
+    pub enabled_at: Option<DateTime<Utc>>,
+}
+
+impl TotpCredential {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+}
@@ -18,12 +18,20 @@ macro_rules! define_nested_routes {
 pub mod pages {
     pub const ROOT: &str = "/";
     pub const SIGN_IN: &str = "/sign_in";
+    pub const ACCOUNT: &str = "/account";
+    pub const ACCOUNT_TWO_FACTOR: &str = "/account/two_factor";
+    pub const TWO_FACTOR: &str = "/two_factor";
     pub const DASHBOARD: &str = "/dashboard";
     pub const TODOS: &str = "/todos";
     pub const TEXT_ANALYZER: &str = "/text_analyzer";
     pub const QUOTE: &str = "/quote/{order_id}";
     pub const CHECKOUT: &str = "/checkout/{order_id}";
+    pub const INVOICE: &str = "/orders/{order_id}/invoice";
+    pub const TOP_UP_CHECKOUT: &str = "/credits/top_ups/{top_up_id}/checkout";
     pub const PAYMENT_CONFIRMATION: &str = "/payment_confirmation/{order_id}";
+    pub const PAYMENT_CONFIRMATION_EVENTS: &str = "/payment_confirmation/{order_id}/events";
+    pub const REPORT_DOWNLOAD: &str = "/reports/{order_id}/{format}";
+    pub const SHARED_REPORT: &str = "/shared/{token}";
 
     pub mod admin {
         pub const HOME: &str = "/admin";
@@ -31,6 +39,9 @@ pub mod pages {
         pub const USER_DETAIL: &str = "/admin/users/{user_id}";
         pub const ORDERS: &str = "/admin/orders";
         pub const ORDER_DETAIL: &str = "/admin/orders/{order_id}";
+        pub const ANALYSIS_JOBS: &str = "/admin/analysis_jobs";
+        pub const PRICING: &str = "/admin/pricing";
+        pub const COUPONS: &str = "/admin/coupons";
     }
 }
 
@@ -40,10 +51,24 @@ pub mod forms {
         TODOS => "/todos",
         CONTACT => "/contact",
         TEXT_ANALYZER => "/text_analyzer",
+        CREDITS_TOP_UPS => "/credits/top_ups",
+        ORDERS_ORDER_ID_COUPON => "/orders/{order_id}/coupon",
+        ORDERS_ORDER_ID_COUPON_REMOVE => "/orders/{order_id}/coupon/remove",
+        ORDERS_ORDER_ID_SHARE_LINKS => "/orders/{order_id}/share_links",
+        SHARE_LINKS_SHARE_LINK_ID_REVOKE => "/share_links/{share_link_id}/revoke",
+        TWO_FACTOR_ENABLE => "/two_factor/enable",
+        TWO_FACTOR_DISABLE => "/two_factor/disable",
+        TWO_FACTOR_VERIFY => "/two_factor/verify",
     });
 
     pub mod admin {
         pub const GRANT_ROLE: &str = "/forms/admin/users/{user_id}/grant-role";
+        pub const ADJUST_CREDITS: &str = "/forms/admin/users/{user_id}/credits";
+        pub const REFUND_ORDER: &str = "/forms/admin/orders/{order_id}/refund";
+        pub const RETRY_ANALYSIS_JOB: &str = "/forms/admin/analysis_jobs/{job_id}/retry";
+        pub const PUBLISH_PRICING: &str = "/forms/admin/pricing";
+        pub const CREATE_COUPON: &str = "/forms/admin/coupons";
+        pub const DEACTIVATE_COUPON: &str = "/forms/admin/coupons/{coupon_id}/deactivate";
     }
 }
 
@@ -54,7 +79,15 @@ pub mod actions {
         TODOS_TODO_ID => "/todos/{todo_id}",
         TODOS_TODO_ID_TOGGLE => "/todos/{todo_id}/toggle",
         PAYMENT_INITIATE => "/payment/initiate",
+        PAYMENT_RETRY => "/payment/retry",
         PAYMENT_VERIFY => "/payment/verify",
+        CREDITS_PAY => "/credits/pay",
+        CREDITS_VERIFY => "/credits/verify",
+        PASSKEYS_REGISTER_START => "/passkeys/register/start",
+        PASSKEYS_REGISTER_FINISH => "/passkeys/register/finish",
+        PASSKEYS_AUTHENTICATE_START => "/passkeys/authenticate/start",
+        PASSKEYS_AUTHENTICATE_FINISH => "/passkeys/authenticate/finish",
+        PASSKEYS_PASSKEY_ID => "/passkeys/{passkey_id}",
     });
 
     pub mod admin {
@@ -62,9 +95,16 @@ pub mod actions {
     }
 }
 
+pub mod webhooks {
+    define_nested_routes!("/webhooks", {
+        TOSS => "/toss",
+    });
+}
+
 pub mod static_files {
     define_nested_routes!("/static", {
         FAVICON => "/img/favicon.svg",
+        PASSKEYS_JS => "/js/passkeys.js",
     });
 }
 
@@ -99,7 +139,51 @@ pub mod helpers {
         with_param(pages::CHECKOUT, "order_id", order_id)
     }
 
+    pub fn invoice_path(order_id: &impl ToString) -> String {
+        with_param(pages::INVOICE, "order_id", order_id)
+    }
+
+    pub fn top_up_checkout_path(top_up_id: &impl ToString) -> String {
+        with_param(pages::TOP_UP_CHECKOUT, "top_up_id", top_up_id)
+    }
+
     pub fn payment_confirmation_path(order_id: &impl ToString) -> String {
         with_param(pages::PAYMENT_CONFIRMATION, "order_id", order_id)
     }
+
+    pub fn payment_confirmation_events_path(order_id: &impl ToString) -> String {
+        with_param(pages::PAYMENT_CONFIRMATION_EVENTS, "order_id", order_id)
+    }
+
+    pub fn report_download_path(order_id: &impl ToString, format: &str) -> String {
+        with_param(&with_param(pages::REPORT_DOWNLOAD, "order_id", order_id), "format", &format)
+    }
+
+    pub fn shared_report_path(token: &str) -> String {
+        with_param(pages::SHARED_REPORT, "token", &token)
+    }
+
+    pub fn apply_coupon_path(order_id: &impl ToString) -> String {
+        with_param(forms::ORDERS_ORDER_ID_COUPON, "order_id", order_id)
+    }
+
+    pub fn remove_coupon_path(order_id: &impl ToString) -> String {
+        with_param(forms::ORDERS_ORDER_ID_COUPON_REMOVE, "order_id", order_id)
+    }
+
+    pub fn create_share_link_path(order_id: &impl ToString) -> String {
+        with_param(forms::ORDERS_ORDER_ID_SHARE_LINKS, "order_id", order_id)
+    }
+
+    pub fn revoke_share_link_path(share_link_id: &impl ToString) -> String {
+        with_param(forms::SHARE_LINKS_SHARE_LINK_ID_REVOKE, "share_link_id", share_link_id)
+    }
+
+    pub fn deactivate_coupon_path(coupon_id: &impl ToString) -> String {
+        with_param(forms::admin::DEACTIVATE_COUPON, "coupon_id", coupon_id)
+    }
+
+    pub fn retry_analysis_job_path(job_id: &impl ToString) -> String {
+        with_param(forms::admin::RETRY_ANALYSIS_JOB, "job_id", job_id)
+    }
 }
@@ -0,0 +1,73 @@
+use super::{ConfirmRequest, PaymentError, PaymentRecord, ProviderPaymentStatus, SharedPaymentGateway};
+
+/// How asking the provider to capture a payment turned out.
+pub enum ConfirmOutcome {
+    Paid(PaymentRecord),
+    Declined(PaymentError),
+    /// Neither confirmed nor declined; leave it pending for the webhook.
+    Unsettled,
+}
+
+/// Captures a payment. Only a decline fails it: after a timeout or a garbled
+/// response the money may still have been taken, so the payment is looked up,
+/// and it only counts as paid if the provider holds it as done for the same
+/// order and amount.
+pub async fn confirm_or_look_up(gateway: &SharedPaymentGateway, request: ConfirmRequest) -> ConfirmOutcome {
+    let order_number = request.order_number.clone();
+    let payment_key = request.payment_key.clone();
+    let amount = request.amount;
+
+    let error = match gateway.confirm(request).await {
+        Ok(payment) => return ConfirmOutcome::Paid(payment),
+        Err(e @ PaymentError::Declined { .. }) => {
+            tracing::error!("Payment confirmation failed for {}: {}", order_number, e);
+            return ConfirmOutcome::Declined(e);
+        }
+        Err(e) => e,
+    };
+    tracing::error!("Payment confirmation for {} has no clear outcome: {}", order_number, error);
+
+    match gateway.lookup(&payment_key).await {
+        Ok(payment)
+            if payment.status == ProviderPaymentStatus::Done
+                && payment.order_number == order_number
+                && payment.total_amount == amount =>
+        {
+            ConfirmOutcome::Paid(payment)
+        }
+        Ok(payment) => {
+            tracing::warn!("Leaving {} pending; provider reports {}", order_number, payment.status.as_str());
+            ConfirmOutcome::Unsettled
+        }
+        Err(e) => {
+            tracing::error!("Payment lookup for {} failed: {}", order_number, e);
+            ConfirmOutcome::Unsettled
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{models::OrderNumber, payment::{MockGateway, MockScenario}};
+
+    async fn confirm_with(scenario: MockScenario) -> ConfirmOutcome {
+        let gateway: SharedPaymentGateway = Arc::new(MockGateway::new(scenario));
+        let request = ConfirmRequest {
+            payment_key: "mock_key".to_string(),
+            order_number: OrderNumber::from("ORD-test".to_string()),
+            amount: 1000,
+        };
+        confirm_or_look_up(&gateway, request).await
+    }
+
+    #[tokio::test]
+    async fn test_only_a_decline_fails_the_payment() {
+        assert!(matches!(confirm_with(MockScenario::Success).await, ConfirmOutcome::Paid(_)));
+        assert!(matches!(confirm_with(MockScenario::Decline).await, ConfirmOutcome::Declined(_)));
+        // The mock never recorded a charge, so the lookup can't settle it either.
+        assert!(matches!(confirm_with(MockScenario::Timeout).await, ConfirmOutcome::Unsettled));
+    }
+}
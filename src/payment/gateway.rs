@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::models::{order::PaymentStatus, OrderNumber};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PaymentError {
+    #[error("Payment declined: {message} ({code})")]
+    Declined { code: String, message: String },
+    #[error("Payment amount mismatch: expected {expected}, got {actual}")]
+    AmountMismatch { expected: i32, actual: i32 },
+    #[error("Payment provider timed out")]
+    Timeout,
+    #[error("Payment provider request failed: {0}")]
+    Transport(String),
+    #[error("Unexpected payment provider response: {0}")]
+    InvalidResponse(String),
+}
+
+/// Payment lifecycle as reported by the provider (Toss naming).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProviderPaymentStatus {
+    Ready,
+    InProgress,
+    WaitingForDeposit,
+    Done,
+    Canceled,
+    PartialCanceled,
+    Aborted,
+    Expired,
+}
+
+impl ProviderPaymentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ready => "READY",
+            Self::InProgress => "IN_PROGRESS",
+            Self::WaitingForDeposit => "WAITING_FOR_DEPOSIT",
+            Self::Done => "DONE",
+            Self::Canceled => "CANCELED",
+            Self::PartialCanceled => "PARTIAL_CANCELED",
+            Self::Aborted => "ABORTED",
+            Self::Expired => "EXPIRED",
+        }
+    }
+
+    /// `None` while the provider still considers the payment in flight.
+    pub fn to_payment_status(self) -> Option<PaymentStatus> {
+        match self {
+            Self::Ready | Self::InProgress | Self::WaitingForDeposit => None,
+            Self::Done | Self::PartialCanceled => Some(PaymentStatus::Paid),
+            Self::Canceled => Some(PaymentStatus::Cancelled),
+            Self::Aborted | Self::Expired => Some(PaymentStatus::Failed),
+        }
+    }
+}
+
+pub struct ConfirmRequest {
+    pub payment_key: String,
+    pub order_number: OrderNumber,
+    pub amount: i32,
+}
+
+pub struct CancelRequest {
+    pub payment_key: String,
+    /// `None` cancels the remaining balance in full.
+    pub amount: Option<i32>,
+    pub reason: String,
+}
+
+/// Provider-side view of a payment, returned by every gateway call.
+#[derive(Debug, Clone)]
+pub struct PaymentRecord {
+    pub payment_key: String,
+    pub order_number: OrderNumber,
+    pub status: ProviderPaymentStatus,
+    pub total_amount: i32,
+    pub balance_amount: i32,
+    /// The provider's response as received, kept on the order's timeline.
+    pub raw: String,
+}
+
+/// What the checkout page renders to collect a payment.
+pub enum CheckoutWidget {
+    Toss { client_key: String },
+    /// Offline stand-in: posts straight back to the verify endpoint with this key.
+    Mock { payment_key: String },
+}
+
+#[async_trait]
+pub trait PaymentGateway: Send + Sync {
+    async fn confirm(&self, request: ConfirmRequest) -> Result<PaymentRecord, PaymentError>;
+
+    async fn cancel(&self, request: CancelRequest) -> Result<PaymentRecord, PaymentError>;
+
+    async fn lookup(&self, payment_key: &str) -> Result<PaymentRecord, PaymentError>;
+
+    fn checkout_widget(&self) -> CheckoutWidget;
+}
@@ -0,0 +1,197 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::constants::payment;
+
+use super::gateway::{
+    CancelRequest, CheckoutWidget, ConfirmRequest, PaymentError, PaymentGateway, PaymentRecord,
+    ProviderPaymentStatus,
+};
+
+/// Outcome every confirm/cancel call of the mock gateway produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockScenario {
+    Success,
+    Decline,
+    Timeout,
+    AmountMismatch,
+}
+
+impl MockScenario {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "success" => Some(Self::Success),
+            "decline" => Some(Self::Decline),
+            "timeout" => Some(Self::Timeout),
+            "amount_mismatch" => Some(Self::AmountMismatch),
+            _ => None,
+        }
+    }
+}
+
+/// Deterministic in-process gateway for offline development and tests.
+/// Remembers confirmed payments so cancel and lookup behave like the provider.
+pub struct MockGateway {
+    scenario: MockScenario,
+    payments: Mutex<HashMap<String, PaymentRecord>>,
+}
+
+impl MockGateway {
+    pub fn new(scenario: MockScenario) -> Self {
+        Self {
+            scenario,
+            payments: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn simulated_failure(&self, amount: i32) -> Option<PaymentError> {
+        match self.scenario {
+            MockScenario::Success => None,
+            MockScenario::Decline => Some(PaymentError::Declined {
+                code: payment::MOCK_DECLINE_CODE.to_string(),
+                message: payment::MOCK_DECLINE_MESSAGE.to_string(),
+            }),
+            MockScenario::Timeout => Some(PaymentError::Timeout),
+            MockScenario::AmountMismatch => Some(PaymentError::AmountMismatch {
+                expected: amount,
+                actual: amount + payment::MOCK_AMOUNT_MISMATCH_DELTA,
+            }),
+        }
+    }
+
+    /// What Toss would have sent back for `record`, in its field names.
+    fn provider_response(record: &PaymentRecord) -> String {
+        serde_json::json!({
+            "paymentKey": record.payment_key,
+            "orderId": record.order_number.as_str(),
+            "status": record.status.as_str(),
+            "totalAmount": record.total_amount,
+            "balanceAmount": record.balance_amount,
+        })
+        .to_string()
+    }
+
+    fn not_found() -> PaymentError {
+        PaymentError::Declined {
+            code: payment::MOCK_NOT_FOUND_CODE.to_string(),
+            message: "Payment not found".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl PaymentGateway for MockGateway {
+    async fn confirm(&self, request: ConfirmRequest) -> Result<PaymentRecord, PaymentError> {
+        if let Some(error) = self.simulated_failure(request.amount) {
+            return Err(error);
+        }
+
+        let mut record = PaymentRecord {
+            payment_key: request.payment_key.clone(),
+            order_number: request.order_number,
+            status: ProviderPaymentStatus::Done,
+            total_amount: request.amount,
+            balance_amount: request.amount,
+            raw: String::new(),
+        };
+        record.raw = Self::provider_response(&record);
+
+        self.payments
+            .lock()
+            .expect("Mock payment store poisoned")
+            .insert(request.payment_key, record.clone());
+
+        Ok(record)
+    }
+
+    async fn cancel(&self, request: CancelRequest) -> Result<PaymentRecord, PaymentError> {
+        let mut payments = self.payments.lock().expect("Mock payment store poisoned");
+        let record = payments.get_mut(&request.payment_key).ok_or_else(Self::not_found)?;
+
+        let cancel_amount = request.amount.unwrap_or(record.balance_amount);
+        if let Some(error) = self.simulated_failure(cancel_amount) {
+            return Err(error);
+        }
+        if cancel_amount <= 0 || cancel_amount > record.balance_amount {
+            return Err(PaymentError::Declined {
+                code: payment::MOCK_INVALID_CANCEL_CODE.to_string(),
+                message: "Cancel amount exceeds the remaining balance".to_string(),
+            });
+        }
+
+        record.balance_amount -= cancel_amount;
+        record.status = if record.balance_amount == 0 {
+            ProviderPaymentStatus::Canceled
+        } else {
+            ProviderPaymentStatus::PartialCanceled
+        };
+        record.raw = Self::provider_response(record);
+
+        Ok(record.clone())
+    }
+
+    async fn lookup(&self, payment_key: &str) -> Result<PaymentRecord, PaymentError> {
+        self.payments
+            .lock()
+            .expect("Mock payment store poisoned")
+            .get(payment_key)
+            .cloned()
+            .ok_or_else(Self::not_found)
+    }
+
+    fn checkout_widget(&self) -> CheckoutWidget {
+        CheckoutWidget::Mock {
+            payment_key: format!("{}{}", payment::MOCK_PAYMENT_KEY_PREFIX, Uuid::new_v4().simple()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OrderNumber;
+
+    fn confirm_request(amount: i32) -> ConfirmRequest {
+        ConfirmRequest {
+            payment_key: "mock_key".to_string(),
+            order_number: OrderNumber::from("ORD-test".to_string()),
+            amount,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_success_scenario_confirms_and_tracks_balance() {
+        let gateway = MockGateway::new(MockScenario::Success);
+        let record = gateway.confirm(confirm_request(1000)).await.unwrap();
+        assert_eq!(record.status, ProviderPaymentStatus::Done);
+
+        let partial = gateway
+            .cancel(CancelRequest {
+                payment_key: "mock_key".to_string(),
+                amount: Some(400),
+                reason: "partial".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(partial.status, ProviderPaymentStatus::PartialCanceled);
+        assert_eq!(partial.balance_amount, 600);
+        assert!(partial.raw.contains("\"PARTIAL_CANCELED\""));
+
+        let looked_up = gateway.lookup("mock_key").await.unwrap();
+        assert_eq!(looked_up.balance_amount, 600);
+    }
+
+    #[tokio::test]
+    async fn test_failure_scenarios() {
+        let declined = MockGateway::new(MockScenario::Decline).confirm(confirm_request(1000)).await;
+        assert!(matches!(declined, Err(PaymentError::Declined { .. })));
+
+        let timed_out = MockGateway::new(MockScenario::Timeout).confirm(confirm_request(1000)).await;
+        assert!(matches!(timed_out, Err(PaymentError::Timeout)));
+
+        let mismatch = MockGateway::new(MockScenario::AmountMismatch).confirm(confirm_request(1000)).await;
+        assert!(matches!(mismatch, Err(PaymentError::AmountMismatch { expected: 1000, .. })));
+    }
+}
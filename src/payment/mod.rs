@@ -0,0 +1,35 @@
+//! Payment provider abstraction. Handlers talk to `SharedPaymentGateway`, never to a provider directly.
+
+mod confirm;
+mod gateway;
+mod mock;
+mod reverse;
+mod toss;
+pub mod webhook;
+
+use std::sync::Arc;
+
+pub use confirm::{confirm_or_look_up, ConfirmOutcome};
+pub use gateway::{
+    CancelRequest, CheckoutWidget, ConfirmRequest, PaymentError, PaymentGateway, PaymentRecord,
+    ProviderPaymentStatus,
+};
+pub use mock::{MockGateway, MockScenario};
+pub use reverse::reverse_charge;
+pub use toss::TossGateway;
+
+use crate::config::{PaymentConfig, PaymentMode};
+
+pub type SharedPaymentGateway = Arc<dyn PaymentGateway>;
+
+pub fn create_gateway(config: &PaymentConfig) -> SharedPaymentGateway {
+    match config.mode() {
+        PaymentMode::Toss { client_key, secret_key } => {
+            Arc::new(TossGateway::new(client_key.clone(), secret_key.clone()))
+        }
+        PaymentMode::Mock { scenario } => {
+            tracing::warn!("Using mock payment gateway (scenario: {:?}) — no real charges", scenario);
+            Arc::new(MockGateway::new(*scenario))
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use super::{CancelRequest, SharedPaymentGateway};
+use crate::{
+    constants::payment,
+    data::commands::{self, order::OrderEventParams},
+    models::order::Order,
+};
+
+/// Refunds a charge in full, for an order that can no longer take it, and
+/// notes it on the order's timeline. Returns `false` when the provider
+/// refused or couldn't be reached, so the charge still stands.
+pub async fn reverse_charge(gateway: &SharedPaymentGateway, order: &Order, payment_key: &str) -> bool {
+    let cancelled = gateway
+        .cancel(CancelRequest {
+            payment_key: payment_key.to_string(),
+            amount: None,
+            reason: payment::CHARGE_REVERSED_REASON.to_string(),
+        })
+        .await;
+
+    match cancelled {
+        Ok(payment) => {
+            let event = OrderEventParams::system()
+                .with_payload(payment.raw)
+                .with_note(payment::CHARGE_REVERSED_REASON);
+            if let Err(e) = commands::order::record_order_event(&order.id, event).await {
+                tracing::error!("Reversed charge {} for order {} but could not record it: {}", payment_key, order.order_number, e);
+            }
+            true
+        }
+        Err(e) => {
+            tracing::error!("Could not reverse charge {} for order {}: {}", payment_key, order.order_number, e);
+            false
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{constants::payment, models::OrderNumber};
+
+use super::gateway::{
+    CancelRequest, CheckoutWidget, ConfirmRequest, PaymentError, PaymentGateway, PaymentRecord,
+    ProviderPaymentStatus,
+};
+
+pub struct TossGateway {
+    client: reqwest::Client,
+    client_key: String,
+    secret_key: String,
+}
+
+impl TossGateway {
+    pub fn new(client_key: String, secret_key: String) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(payment::TOSS_API_TIMEOUT_SECS))
+            .build()
+            .expect("Failed to build HTTP client for Toss Payments");
+
+        Self {
+            client,
+            client_key,
+            secret_key,
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<PaymentRecord, PaymentError> {
+        let response = request
+            .basic_auth(&self.secret_key, Some(""))
+            .send()
+            .await
+            .map_err(map_transport_error)?;
+
+        if !response.status().is_success() {
+            let error_body = response.text().await.map_err(map_transport_error)?;
+            tracing::error!("Toss API returned an error: {}", error_body);
+            return Err(match serde_json::from_str::<TossErrorResponse>(&error_body) {
+                Ok(error) => PaymentError::Declined {
+                    code: error.code,
+                    message: error.message,
+                },
+                Err(_) => PaymentError::InvalidResponse(error_body),
+            });
+        }
+
+        let body = response.text().await.map_err(map_transport_error)?;
+        let payment: TossPayment =
+            serde_json::from_str(&body).map_err(|e| PaymentError::InvalidResponse(e.to_string()))?;
+
+        Ok(payment.into_record(body))
+    }
+}
+
+fn map_transport_error(e: reqwest::Error) -> PaymentError {
+    if e.is_timeout() {
+        PaymentError::Timeout
+    } else {
+        PaymentError::Transport(e.to_string())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TossConfirmRequest {
+    payment_key: String,
+    order_id: String,
+    amount: i32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TossCancelRequest {
+    cancel_reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cancel_amount: Option<i32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TossPayment {
+    payment_key: String,
+    order_id: String,
+    status: ProviderPaymentStatus,
+    total_amount: i32,
+    balance_amount: i32,
+}
+
+impl TossPayment {
+    fn into_record(self, raw: String) -> PaymentRecord {
+        PaymentRecord {
+            payment_key: self.payment_key,
+            order_number: OrderNumber::from(self.order_id),
+            status: self.status,
+            total_amount: self.total_amount,
+            balance_amount: self.balance_amount,
+            raw,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TossErrorResponse {
+    code: String,
+    message: String,
+}
+
+#[async_trait]
+impl PaymentGateway for TossGateway {
+    async fn confirm(&self, request: ConfirmRequest) -> Result<PaymentRecord, PaymentError> {
+        let expected = request.amount;
+        let record = self
+            .send(self.client.post(payment::TOSS_API_CONFIRM_URL).json(&TossConfirmRequest {
+                payment_key: request.payment_key,
+                order_id: request.order_number.to_string(),
+                amount: request.amount,
+            }))
+            .await?;
+
+        if record.total_amount != expected {
+            return Err(PaymentError::AmountMismatch {
+                expected,
+                actual: record.total_amount,
+            });
+        }
+
+        Ok(record)
+    }
+
+    async fn cancel(&self, request: CancelRequest) -> Result<PaymentRecord, PaymentError> {
+        let url = format!("{}/{}/cancel", payment::TOSS_API_PAYMENTS_URL, request.payment_key);
+        self.send(self.client.post(url).json(&TossCancelRequest {
+            cancel_reason: request.reason,
+            cancel_amount: request.amount,
+        }))
+        .await
+    }
+
+    async fn lookup(&self, payment_key: &str) -> Result<PaymentRecord, PaymentError> {
+        let url = format!("{}/{}", payment::TOSS_API_PAYMENTS_URL, payment_key);
+        self.send(self.client.get(url)).await
+    }
+
+    fn checkout_widget(&self) -> CheckoutWidget {
+        CheckoutWidget::Toss {
+            client_key: self.client_key.clone(),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+
+use crate::constants::payment;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Envelope Toss posts for `PAYMENT_STATUS_CHANGED` and related events.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEvent {
+    pub event_type: String,
+    pub data: WebhookPaymentData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPaymentData {
+    pub payment_key: String,
+    pub order_id: String,
+}
+
+/// Checks a Toss signature header (`v1:<base64>,v1:<base64>`) against
+/// HMAC-SHA256 of `{payload}:{transmission_time}`. Any matching entry passes.
+pub fn verify_signature(secret: &str, payload: &str, transmission_time: &str, signature_header: &str) -> bool {
+    let message = format!("{}:{}", payload, transmission_time);
+
+    signature_header
+        .split(',')
+        .filter_map(|entry| entry.trim().strip_prefix(payment::WEBHOOK_SIGNATURE_VERSION_PREFIX))
+        .filter_map(|encoded| STANDARD.decode(encoded).ok())
+        .any(|signature| {
+            let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+            mac.update(message.as_bytes());
+            mac.verify_slice(&signature).is_ok()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, payload: &str, transmission_time: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{}:{}", payload, transmission_time).as_bytes());
+        format!("v1:{}", STANDARD.encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_verify_signature_accepts_any_matching_entry() {
+        let header = format!("v1:bm90LWEtc2lnbmF0dXJl,{}", sign("secret", "{}", "1700000000"));
+        assert!(verify_signature("secret", "{}", "1700000000", &header));
+    }
+
+    #[test]
+    fn test_verify_signature_rejects_tampering() {
+        let header = sign("secret", "{}", "1700000000");
+        assert!(!verify_signature("other-secret", "{}", "1700000000", &header));
+        assert!(!verify_signature("secret", "{\"x\":1}", "1700000000", &header));
+        assert!(!verify_signature("secret", "{}", "1700000001", &header));
+        assert!(!verify_signature("secret", "{}", "1700000000", "garbage"));
+    }
+}
@@ -0,0 +1,109 @@
+//! Turns a published `PricingRules` version into prices for an order's files.
+//!
+//! The rate comes from the order's total character count: the highest tier the
+//! order reaches, or the base rate below every tier. Each file is charged its
+//! characters at that rate, rounded up to the won, plus any surcharge for its
+//! detected type; the order pays the sum of its files or the minimum,
+//! whichever is more.
+
+use crate::{extraction::FileKind, models::pricing::PricingRules};
+
+pub struct PricedOrder {
+    /// In the same order as the files passed in.
+    pub item_prices: Vec<i32>,
+    pub total: i32,
+}
+
+/// `files` are `(kind, text_length)` pairs, the kind as detected from the
+/// content: a renamed file pays for what it is, not what it claims to be.
+pub fn price_order(rules: &PricingRules, files: &[(FileKind, i32)]) -> PricedOrder {
+    let total_characters: i64 = files.iter().map(|&(_, length)| i64::from(length)).sum();
+    let rate = rate_for(rules, total_characters);
+
+    let item_prices: Vec<i32> = files
+        .iter()
+        .map(|&(kind, length)| {
+            let characters = (i64::from(length) * i64::from(rate) + 999) / 1000;
+            clamp(characters + i64::from(surcharge_for(rules, kind)))
+        })
+        .collect();
+
+    let items_total: i64 = item_prices.iter().copied().map(i64::from).sum();
+    let total = clamp(items_total).max(rules.minimum_order_amount);
+
+    PricedOrder { item_prices, total }
+}
+
+fn rate_for(rules: &PricingRules, total_characters: i64) -> i32 {
+    rules
+        .tiers
+        .iter()
+        .filter(|tier| i64::from(tier.min_characters) <= total_characters)
+        .max_by_key(|tier| tier.min_characters)
+        .map_or(rules.rate_per_thousand, |tier| tier.rate_per_thousand)
+}
+
+fn surcharge_for(rules: &PricingRules, kind: FileKind) -> i32 {
+    rules
+        .surcharges
+        .iter()
+        .find(|surcharge| {
+            kind.extensions()
+                .iter()
+                .any(|extension| surcharge.extension.eq_ignore_ascii_case(extension))
+        })
+        .map_or(0, |surcharge| surcharge.amount)
+}
+
+fn clamp(amount: i64) -> i32 {
+    i32::try_from(amount).unwrap_or(i32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::pricing::{FileTypeSurcharge, PricingTier};
+
+    fn rules() -> PricingRules {
+        PricingRules {
+            rate_per_thousand: 1000,
+            minimum_order_amount: 100,
+            tiers: vec![
+                PricingTier { min_characters: 10_000, rate_per_thousand: 800 },
+                PricingTier { min_characters: 100_000, rate_per_thousand: 500 },
+            ],
+            surcharges: vec![FileTypeSurcharge { extension: "pdf".to_string(), amount: 300 }],
+        }
+    }
+
+    #[test]
+    fn test_base_rate_and_minimum() {
+        let priced = price_order(&rules(), &[(FileKind::PlainText, 40), (FileKind::Markdown, 30)]);
+        assert_eq!(priced.item_prices, [40, 30]);
+        assert_eq!(priced.total, 100);
+    }
+
+    #[test]
+    fn test_tier_from_order_total_applies_to_every_file() {
+        let priced = price_order(&rules(), &[(FileKind::PlainText, 6_000), (FileKind::PlainText, 4_001)]);
+        assert_eq!(priced.item_prices, [4_800, 3_201]);
+        assert_eq!(priced.total, 8_001);
+
+        let priced = price_order(&rules(), &[(FileKind::PlainText, 100_000)]);
+        assert_eq!(priced.total, 50_000);
+    }
+
+    #[test]
+    fn test_surcharge_follows_detected_kind() {
+        let priced = price_order(&rules(), &[(FileKind::Pdf, 500), (FileKind::PlainText, 500)]);
+        assert_eq!(priced.item_prices, [800, 500]);
+    }
+
+    #[test]
+    fn test_surcharge_covers_every_extension_of_a_kind() {
+        let mut rules = rules();
+        rules.surcharges = vec![FileTypeSurcharge { extension: "htm".to_string(), amount: 200 }];
+        let priced = price_order(&rules, &[(FileKind::Html, 500)]);
+        assert_eq!(priced.item_prices, [700]);
+    }
+}
@@ -0,0 +1,222 @@
+//! In-memory sliding-window rate limiting for forms that send email.
+//! State is per process; a multi-instance deploy would need a shared store.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::http::{HeaderMap, HeaderName};
+
+use crate::config::RateLimitConfig;
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+pub type SharedRateLimiter = Arc<RateLimiter>;
+
+/// The client's address: the peer's, or behind `hops` trusted proxies the
+/// `X-Forwarded-For` entry the outermost of them appended. Proxies append the
+/// address they were reached from, so entries further left are whatever the
+/// client sent. A chain shorter than `hops` falls back to the peer address.
+pub fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, hops: usize) -> Option<IpAddr> {
+    if hops == 0 {
+        return peer;
+    }
+
+    let forwarded: Vec<&str> = headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    forwarded
+        .len()
+        .checked_sub(hops)
+        .and_then(|index| forwarded[index].parse().ok())
+        .or(peer)
+}
+
+/// Separate budgets per form, so contact spam can't lock a user out of sign-in.
+#[derive(Debug, Clone, Copy)]
+pub enum RateLimitScope {
+    SignIn,
+    Contact,
+}
+
+impl RateLimitScope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::SignIn => "sign_in",
+            Self::Contact => "contact",
+        }
+    }
+}
+
+pub struct RateLimiter {
+    by_ip: SlidingWindow,
+    by_email: SlidingWindow,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            by_ip: SlidingWindow::new(config.max_per_ip(), config.window()),
+            by_email: SlidingWindow::new(config.max_per_email(), config.window()),
+        }
+    }
+
+    /// Records an attempt against the IP and (if given) the email. A blocked
+    /// attempt is not recorded; the error is how long until the next one is allowed.
+    pub fn check(&self, scope: RateLimitScope, ip: Option<IpAddr>, email: Option<&str>) -> Result<(), Duration> {
+        let ip_key = ip.map(|ip| format!("{}:{}", scope.as_str(), ip));
+        let email_key = email
+            .map(|email| email.trim().to_lowercase())
+            .filter(|email| !email.is_empty())
+            .map(|email| format!("{}:{}", scope.as_str(), email));
+
+        let now = Instant::now();
+        let retry_after = [
+            ip_key.as_deref().and_then(|key| self.by_ip.retry_after(key, now)),
+            email_key.as_deref().and_then(|key| self.by_email.retry_after(key, now)),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+
+        if let Some(retry_after) = retry_after {
+            return Err(retry_after);
+        }
+
+        if let Some(key) = ip_key {
+            self.by_ip.record(key, now);
+        }
+        if let Some(key) = email_key {
+            self.by_email.record(key, now);
+        }
+        Ok(())
+    }
+
+    /// Drops keys with no attempts inside the window. Returns how many were dropped.
+    pub fn prune(&self) -> usize {
+        let now = Instant::now();
+        self.by_ip.prune(now) + self.by_email.prune(now)
+    }
+}
+
+struct SlidingWindow {
+    max_attempts: usize,
+    window: Duration,
+    attempts: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl SlidingWindow {
+    fn new(max_attempts: usize, window: Duration) -> Self {
+        Self {
+            max_attempts,
+            window,
+            attempts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn retry_after(&self, key: &str, now: Instant) -> Option<Duration> {
+        let mut attempts = self.attempts.lock().expect("rate limiter lock poisoned");
+        let timestamps = attempts.get_mut(key)?;
+        self.expire(timestamps, now);
+
+        if timestamps.len() < self.max_attempts {
+            return None;
+        }
+        timestamps
+            .front()
+            .map(|oldest| (*oldest + self.window).saturating_duration_since(now))
+    }
+
+    fn record(&self, key: String, now: Instant) {
+        let mut attempts = self.attempts.lock().expect("rate limiter lock poisoned");
+        attempts.entry(key).or_default().push_back(now);
+    }
+
+    fn prune(&self, now: Instant) -> usize {
+        let mut attempts = self.attempts.lock().expect("rate limiter lock poisoned");
+        let before = attempts.len();
+        attempts.retain(|_, timestamps| {
+            self.expire(timestamps, now);
+            !timestamps.is_empty()
+        });
+        before - attempts.len()
+    }
+
+    fn expire(&self, timestamps: &mut VecDeque<Instant>, now: Instant) {
+        while timestamps
+            .front()
+            .is_some_and(|oldest| now.duration_since(*oldest) >= self.window)
+        {
+            timestamps.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_sliding_window_blocks_after_max_attempts() {
+        let window = SlidingWindow::new(2, WINDOW);
+        let start = Instant::now();
+
+        assert_eq!(window.retry_after("key", start), None);
+        window.record("key".to_string(), start);
+        window.record("key".to_string(), start + Duration::from_secs(10));
+
+        assert_eq!(window.retry_after("key", start + Duration::from_secs(20)), Some(Duration::from_secs(40)));
+        assert_eq!(window.retry_after("other", start + Duration::from_secs(20)), None);
+    }
+
+    #[test]
+    fn test_sliding_window_frees_slots_as_attempts_age_out() {
+        let window = SlidingWindow::new(2, WINDOW);
+        let start = Instant::now();
+        window.record("key".to_string(), start);
+        window.record("key".to_string(), start + Duration::from_secs(10));
+
+        assert_eq!(window.retry_after("key", start + WINDOW), None);
+        assert_eq!(window.prune(start + WINDOW), 0);
+        assert_eq!(window.prune(start + WINDOW + Duration::from_secs(10)), 1);
+    }
+
+    fn forwarded_for(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(X_FORWARDED_FOR, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn ip(addr: &str) -> Option<IpAddr> {
+        Some(addr.parse().unwrap())
+    }
+
+    #[test]
+    fn test_client_ip_ignores_forwarded_for_without_trusted_proxies() {
+        assert_eq!(client_ip(&forwarded_for(&["203.0.113.7"]), ip("10.0.0.1"), 0), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_client_ip_counts_trusted_hops_from_the_right() {
+        let headers = forwarded_for(&["198.51.100.9, 203.0.113.7", "10.0.0.2"]);
+        assert_eq!(client_ip(&headers, ip("10.0.0.1"), 1), ip("10.0.0.2"));
+        assert_eq!(client_ip(&headers, ip("10.0.0.1"), 2), ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn test_client_ip_falls_back_to_the_peer_for_short_or_bad_chains() {
+        assert_eq!(client_ip(&forwarded_for(&["203.0.113.7"]), ip("10.0.0.1"), 2), ip("10.0.0.1"));
+        assert_eq!(client_ip(&forwarded_for(&["not-an-ip"]), ip("10.0.0.1"), 1), ip("10.0.0.1"));
+    }
+}
@@ -5,6 +5,8 @@ use crate::{config::AppState, handlers::actions, paths::actions::relative};
 pub fn public_action_routes() -> Router<AppState> {
     Router::new()
         .route(relative::VERIFY_MAGIC_LINK, get(actions::get_actions_auth_verify))
+        .route(relative::PASSKEYS_AUTHENTICATE_START, post(actions::post_actions_passkeys_authenticate_start))
+        .route(relative::PASSKEYS_AUTHENTICATE_FINISH, post(actions::post_actions_passkeys_authenticate_finish))
 }
 
 pub fn protected_action_routes() -> Router<AppState> {
@@ -13,5 +15,11 @@ pub fn protected_action_routes() -> Router<AppState> {
         .route(relative::TODOS_TODO_ID, delete(actions::delete_actions_todos_todo_id))
         .route(relative::TODOS_TODO_ID_TOGGLE, patch(actions::patch_actions_todos_todo_id_toggle))
         .route(relative::PAYMENT_INITIATE, post(actions::post_actions_payment_initiate))
+        .route(relative::PAYMENT_RETRY, post(actions::post_actions_payment_retry))
         .route(relative::PAYMENT_VERIFY, get(actions::get_actions_payment_verify))
+        .route(relative::CREDITS_PAY, post(actions::post_actions_credits_pay))
+        .route(relative::CREDITS_VERIFY, get(actions::get_actions_credits_verify))
+        .route(relative::PASSKEYS_REGISTER_START, post(actions::post_actions_passkeys_register_start))
+        .route(relative::PASSKEYS_REGISTER_FINISH, post(actions::post_actions_passkeys_register_finish))
+        .route(relative::PASSKEYS_PASSKEY_ID, delete(actions::delete_actions_passkeys_passkey_id))
 }
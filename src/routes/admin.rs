@@ -1,14 +1,23 @@
 use crate::{config::AppState, handlers, middlewares, paths};
 use axum::{middleware, Router, routing::{delete, get, post}};
 
-pub fn admin_routes() -> Router<AppState> {
+pub fn admin_routes(state: AppState) -> Router<AppState> {
     Router::new()
         .route(paths::pages::admin::HOME, get(handlers::pages::admin::get_admin_home))
         .route(paths::pages::admin::USERS, get(handlers::pages::admin::get_admin_users))
         .route(paths::pages::admin::USER_DETAIL, get(handlers::pages::admin::get_admin_user_detail))
         .route(paths::pages::admin::ORDERS, get(handlers::pages::admin::get_admin_orders))
         .route(paths::pages::admin::ORDER_DETAIL, get(handlers::pages::admin::get_admin_order_detail))
+        .route(paths::pages::admin::ANALYSIS_JOBS, get(handlers::pages::admin::get_admin_analysis_jobs))
+        .route(paths::pages::admin::PRICING, get(handlers::pages::admin::get_admin_pricing))
+        .route(paths::pages::admin::COUPONS, get(handlers::pages::admin::get_admin_coupons))
+        .route(paths::forms::admin::ADJUST_CREDITS, post(handlers::forms::admin::post_forms_admin_users_user_id_credits))
         .route(paths::forms::admin::GRANT_ROLE, post(handlers::forms::admin::post_forms_admin_users_user_id_grant_role))
+        .route(paths::forms::admin::REFUND_ORDER, post(handlers::forms::admin::post_forms_admin_orders_order_id_refund))
+        .route(paths::forms::admin::RETRY_ANALYSIS_JOB, post(handlers::forms::admin::post_forms_admin_analysis_jobs_job_id_retry))
+        .route(paths::forms::admin::PUBLISH_PRICING, post(handlers::forms::admin::post_forms_admin_pricing))
+        .route(paths::forms::admin::CREATE_COUPON, post(handlers::forms::admin::post_forms_admin_coupons))
+        .route(paths::forms::admin::DEACTIVATE_COUPON, post(handlers::forms::admin::post_forms_admin_coupons_coupon_id_deactivate))
         .route(paths::actions::admin::REVOKE_ROLE, delete(handlers::actions::admin::delete_actions_admin_users_user_id_revoke_role))
-        .layer(middleware::from_fn(middlewares::require_admin))
+        .layer(middleware::from_fn_with_state(state, middlewares::require_admin))
 }
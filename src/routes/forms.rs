@@ -1,15 +1,38 @@
-use axum::{Router, routing::post};
+use axum::{Router, extract::DefaultBodyLimit, middleware, routing::post};
 
-use crate::{config::AppState, handlers::forms, paths::forms::relative};
+use crate::{config::AppState, constants::file_upload, handlers::forms, middlewares, paths::forms::relative};
 
-pub fn public_form_routes() -> Router<AppState> {
+/// Both forms send email, so each is rate limited per IP and per email address.
+pub fn public_form_routes(state: AppState) -> Router<AppState> {
     Router::new()
-        .route(relative::SIGN_IN, post(forms::post_forms_sign_in))
-        .route(relative::CONTACT, post(forms::post_forms_contact))
+        .route(
+            relative::SIGN_IN,
+            post(forms::post_forms_sign_in)
+                .layer(middleware::from_fn_with_state(state.clone(), middlewares::rate_limit_sign_in)),
+        )
+        .route(
+            relative::CONTACT,
+            post(forms::post_forms_contact)
+                .layer(middleware::from_fn_with_state(state, middlewares::rate_limit_contact)),
+        )
 }
 
 pub fn protected_form_routes() -> Router<AppState> {
     Router::new()
         .route(relative::TODOS, post(forms::post_forms_todos))
-        .route(relative::TEXT_ANALYZER, post(forms::post_forms_text_analyzer))
+        .route(
+            relative::TEXT_ANALYZER,
+            post(forms::post_forms_text_analyzer).layer(DefaultBodyLimit::max(file_upload::MAX_REQUEST_BYTES)),
+        )
+        .route(relative::CREDITS_TOP_UPS, post(forms::post_forms_credits_top_ups))
+        .route(relative::ORDERS_ORDER_ID_COUPON, post(forms::post_forms_orders_order_id_coupon))
+        .route(relative::ORDERS_ORDER_ID_COUPON_REMOVE, post(forms::post_forms_orders_order_id_coupon_remove))
+        .route(relative::ORDERS_ORDER_ID_SHARE_LINKS, post(forms::post_forms_orders_order_id_share_links))
+        .route(
+            relative::SHARE_LINKS_SHARE_LINK_ID_REVOKE,
+            post(forms::post_forms_share_links_share_link_id_revoke),
+        )
+        .route(relative::TWO_FACTOR_ENABLE, post(forms::post_forms_two_factor_enable))
+        .route(relative::TWO_FACTOR_DISABLE, post(forms::post_forms_two_factor_disable))
+        .route(relative::TWO_FACTOR_VERIFY, post(forms::post_forms_two_factor_verify))
 }
@@ -4,6 +4,7 @@ mod actions;
 mod admin;
 mod forms;
 mod pages;
+mod webhooks;
 
 use axum::{Router, middleware};
 use tower_http::services::ServeDir;
@@ -23,27 +24,31 @@ fn app_routes(state: AppState, session_layer: SessionManagerLayer<SurrealSession
     let state_clone = state.clone();
 
     Router::new()
-        .merge(public_routes())
+        .merge(public_routes(state.clone()))
         .merge(protected_routes())
-        .merge(admin_routes())
+        .merge(admin_routes(state.clone()))
         .fallback(handlers::fallback::handle_404)
         .with_state(state)
-        // CRITICAL: Layers apply bottom-to-top. session_layer → session_context → handler.
+        // CRITICAL: Layers apply bottom-to-top. session_layer → session_context → csrf_protection → handler.
         // session_context loads CurrentUser from session — must run after session_layer.
+        // csrf_protection renders its 403 page with CurrentUser, so it runs after session_context.
+        .layer(middleware::from_fn_with_state(state_clone.clone(), middlewares::csrf_protection))
         .layer(middleware::from_fn_with_state(state_clone, middlewares::session_context))
         .layer(session_layer)
 }
 
-fn admin_routes() -> Router<AppState> {
-    admin::admin_routes()
+/// require_admin reads config for ADMIN_TOTP_REQUIRED, hence the state.
+fn admin_routes(state: AppState) -> Router<AppState> {
+    admin::admin_routes(state)
         .layer(middleware::from_fn(middlewares::require_authentication))
 }
 
-fn public_routes() -> Router<AppState> {
+fn public_routes(state: AppState) -> Router<AppState> {
     Router::new()
         .merge(pages::public_page_routes())
-        .nest(paths::forms::BASE, forms::public_form_routes())
+        .nest(paths::forms::BASE, forms::public_form_routes(state))
         .nest(paths::actions::BASE, actions::public_action_routes())
+        .nest(paths::webhooks::BASE, webhooks::webhook_routes())
 }
 
 /// require_authentication redirects guests to sign-in.
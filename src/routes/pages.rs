@@ -6,14 +6,22 @@ pub fn public_page_routes() -> Router<AppState> {
     Router::new()
         .route(paths::pages::ROOT, get(pages::get_root))
         .route(paths::pages::SIGN_IN, get(pages::get_sign_in))
+        .route(paths::pages::SHARED_REPORT, get(pages::get_shared_report))
 }
 
 pub fn protected_page_routes() -> Router<AppState> {
     Router::new()
+        .route(paths::pages::ACCOUNT, get(pages::get_account))
+        .route(paths::pages::ACCOUNT_TWO_FACTOR, get(pages::get_account_two_factor))
+        .route(paths::pages::TWO_FACTOR, get(pages::get_two_factor))
         .route(paths::pages::DASHBOARD, get(pages::get_dashboard))
         .route(paths::pages::TODOS, get(pages::get_todos))
         .route(paths::pages::TEXT_ANALYZER, get(pages::get_text_analyzer))
         .route(paths::pages::QUOTE, get(pages::get_quote))
         .route(paths::pages::CHECKOUT, get(pages::get_checkout))
+        .route(paths::pages::INVOICE, get(pages::get_invoice))
+        .route(paths::pages::TOP_UP_CHECKOUT, get(pages::get_top_up_checkout))
         .route(paths::pages::PAYMENT_CONFIRMATION, get(pages::get_payment_confirmation))
+        .route(paths::pages::PAYMENT_CONFIRMATION_EVENTS, get(pages::get_payment_confirmation_events))
+        .route(paths::pages::REPORT_DOWNLOAD, get(pages::get_report_download))
 }
@@ -0,0 +1,9 @@
+use axum::{Router, routing::post};
+
+use crate::{config::AppState, handlers::webhooks, paths::webhooks::relative};
+
+/// Provider callbacks — public, authenticated by signature rather than session.
+pub fn webhook_routes() -> Router<AppState> {
+    Router::new()
+        .route(relative::TOSS, post(webhooks::post_webhooks_toss))
+}
@@ -0,0 +1,89 @@
+use std::{io::ErrorKind, path::PathBuf};
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::{BlobKey, BlobStore, StorageError};
+
+/// Blobs as files under `root`, fanned out by the first two hex digits of the
+/// key so no single directory grows too large: `root/ab/abcdef…`.
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn path(&self, key: &BlobKey) -> PathBuf {
+        self.root.join(&key.as_str()[..2]).join(key.as_str())
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, data: Vec<u8>) -> Result<BlobKey, StorageError> {
+        let key = BlobKey::for_content(&data);
+        let path = self.path(&key);
+        if tokio::fs::try_exists(&path).await? {
+            return Ok(key);
+        }
+
+        // Write beside the final path and rename, so a reader never sees half a blob.
+        let dir = path.parent().expect("blob path has a fan-out directory");
+        tokio::fs::create_dir_all(dir).await?;
+        let temp_path = dir.join(format!(".{}.{}.tmp", key, Uuid::new_v4()));
+        tokio::fs::write(&temp_path, &data).await?;
+        if let Err(e) = tokio::fs::rename(&temp_path, &path).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e.into());
+        }
+
+        Ok(key)
+    }
+
+    async fn get(&self, key: &BlobKey) -> Result<Vec<u8>, StorageError> {
+        let data = match tokio::fs::read(self.path(key)).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Err(StorageError::NotFound(key.clone())),
+            Err(e) => return Err(e.into()),
+        };
+
+        if BlobKey::for_content(&data) != *key {
+            return Err(StorageError::Corrupt(key.clone()));
+        }
+        Ok(data)
+    }
+
+    async fn delete(&self, key: &BlobKey) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(self.path(key)).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_get_round_trip_and_dedupe() {
+        let root = std::env::temp_dir().join(format!("blob-store-test-{}", Uuid::new_v4()));
+        let store = LocalBlobStore::new(root.clone());
+
+        let key = store.put(b"hello".to_vec()).await.unwrap();
+        assert_eq!(store.put(b"hello".to_vec()).await.unwrap(), key);
+        assert_eq!(store.get(&key).await.unwrap(), b"hello");
+
+        let missing = BlobKey::for_content(b"missing");
+        assert!(matches!(store.get(&missing).await, Err(StorageError::NotFound(_))));
+
+        store.delete(&key).await.unwrap();
+        assert!(matches!(store.get(&key).await, Err(StorageError::NotFound(_))));
+        store.delete(&key).await.unwrap();
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,38 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use async_trait::async_trait;
+
+use super::{BlobKey, BlobStore, StorageError};
+
+/// Keeps blobs in process memory. For development and tests only.
+#[derive(Default)]
+pub struct MemoryBlobStore {
+    blobs: RwLock<HashMap<BlobKey, Vec<u8>>>,
+}
+
+impl MemoryBlobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl BlobStore for MemoryBlobStore {
+    async fn put(&self, data: Vec<u8>) -> Result<BlobKey, StorageError> {
+        let key = BlobKey::for_content(&data);
+        let mut blobs = self.blobs.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        blobs.entry(key.clone()).or_insert(data);
+        Ok(key)
+    }
+
+    async fn get(&self, key: &BlobKey) -> Result<Vec<u8>, StorageError> {
+        let blobs = self.blobs.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        blobs.get(key).cloned().ok_or_else(|| StorageError::NotFound(key.clone()))
+    }
+
+    async fn delete(&self, key: &BlobKey) -> Result<(), StorageError> {
+        let mut blobs = self.blobs.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        blobs.remove(key);
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! Content-addressed blob storage for uploaded text. Blobs are keyed by the
+//! SHA-256 of their content, so storing the same upload twice keeps one copy.
+//! Handlers and jobs talk to `SharedBlobStore`, never to a backend directly.
+
+mod local;
+mod memory;
+
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub use local::LocalBlobStore;
+pub use memory::MemoryBlobStore;
+
+use crate::config::{StorageBackend, StorageConfig};
+
+pub type SharedBlobStore = Arc<dyn BlobStore>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Blob {0} not found")]
+    NotFound(BlobKey),
+    #[error("Blob {0} does not match its key")]
+    Corrupt(BlobKey),
+    #[error("Blob {0} is not valid UTF-8 text")]
+    NotText(BlobKey),
+    #[error("Blob storage I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Lowercase hex SHA-256 of a blob's content. Only ever built by hashing or by
+/// checking a stored key, so it is always safe to use as a file name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BlobKey(String);
+
+impl BlobKey {
+    pub fn for_content(data: &[u8]) -> Self {
+        Self(format!("{:x}", Sha256::digest(data)))
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let valid = s.len() == 64 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+        valid.then(|| Self(s.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for BlobKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for BlobKey {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s).ok_or_else(|| format!("invalid blob key '{}'", s))
+    }
+}
+
+impl From<BlobKey> for String {
+    fn from(key: BlobKey) -> Self {
+        key.0
+    }
+}
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Stores `data` under the hash of its content and returns that key.
+    /// Content that is already stored is left as it is.
+    async fn put(&self, data: Vec<u8>) -> Result<BlobKey, StorageError>;
+
+    async fn get(&self, key: &BlobKey) -> Result<Vec<u8>, StorageError>;
+
+    /// Removes the blob. Deleting one that isn't stored is not an error.
+    async fn delete(&self, key: &BlobKey) -> Result<(), StorageError>;
+}
+
+pub async fn put_text(store: &dyn BlobStore, text: String) -> Result<BlobKey, StorageError> {
+    store.put(text.into_bytes()).await
+}
+
+pub async fn get_text(store: &dyn BlobStore, key: &BlobKey) -> Result<String, StorageError> {
+    let data = store.get(key).await?;
+    String::from_utf8(data).map_err(|_| StorageError::NotText(key.clone()))
+}
+
+/// An order item's text, wherever it is kept; `None` once it was purged.
+pub async fn get_item_text(
+    store: &dyn BlobStore,
+    text_blob: Option<&BlobKey>,
+    text_content: Option<String>,
+) -> Result<Option<String>, StorageError> {
+    match text_blob {
+        Some(key) => get_text(store, key).await.map(Some),
+        None => Ok(text_content),
+    }
+}
+
+pub fn create_blob_store(config: &StorageConfig) -> SharedBlobStore {
+    match config.backend() {
+        StorageBackend::Local { root } => Arc::new(LocalBlobStore::new(root.clone())),
+        StorageBackend::Memory => {
+            tracing::warn!("Using in-memory blob storage — uploaded text is lost on restart");
+            Arc::new(MemoryBlobStore::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_key_is_sha256_hex() {
+        let key = BlobKey::for_content(b"abc");
+        assert_eq!(key.as_str(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(BlobKey::parse(key.as_str()), Some(key));
+    }
+
+    #[test]
+    fn test_blob_key_rejects_anything_but_lowercase_hex() {
+        assert!(BlobKey::parse("../../etc/passwd").is_none());
+        assert!(BlobKey::parse(&"A".repeat(64)).is_none());
+        assert!(BlobKey::parse(&"a".repeat(63)).is_none());
+    }
+}
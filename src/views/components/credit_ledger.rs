@@ -0,0 +1,56 @@
+use maud::{html, Markup};
+
+use crate::{
+    models::{credit::CreditTransaction, OrderId},
+    views::helpers as formatting,
+};
+
+/// Most recent credit movements, newest first. `order_path` links order payments
+/// to the customer's quote page or the admin order page.
+pub fn credit_ledger(transactions: &[CreditTransaction], order_path: fn(&OrderId) -> String) -> Markup {
+    html! {
+        @if transactions.is_empty() {
+            p class="text-gray-500 py-4" { "No credit activity yet" }
+        } @else {
+            table class="w-full text-sm" {
+                thead class="border-b" {
+                    tr {
+                        th class="text-left py-2 px-2" { "Date" }
+                        th class="text-left py-2 px-2" { "Type" }
+                        th class="text-left py-2 px-2" { "Details" }
+                        th class="text-right py-2 px-2" { "Amount" }
+                        th class="text-right py-2 px-2" { "Balance" }
+                    }
+                }
+                tbody {
+                    @for transaction in transactions {
+                        (transaction_row(transaction, order_path))
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn transaction_row(transaction: &CreditTransaction, order_path: fn(&OrderId) -> String) -> Markup {
+    let amount_class = if transaction.amount < 0 { "text-red-600" } else { "text-green-600" };
+    let sign = if transaction.amount < 0 { "−" } else { "+" };
+
+    html! {
+        tr class="border-b" {
+            td class="py-2 px-2 text-gray-600" { (formatting::format_datetime(transaction.created_at)) }
+            td class="py-2 px-2" { (transaction.kind.display_text()) }
+            td class="py-2 px-2 text-gray-600" {
+                @if let Some(order_id) = &transaction.order {
+                    a href=(order_path(order_id)) class="text-indigo-600 hover:underline" { "View order" }
+                } @else if let Some(note) = &transaction.note {
+                    (note)
+                }
+            }
+            td class={"py-2 px-2 text-right " (amount_class)} {
+                (sign) "₩" (formatting::format_price(transaction.amount.abs()))
+            }
+            td class="py-2 px-2 text-right" { "₩" (formatting::format_price(transaction.balance_after)) }
+        }
+    }
+}
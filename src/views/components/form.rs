@@ -1,5 +1,21 @@
 use maud::{Markup, html};
 
+use crate::auth::csrf::{self, CSRF_FORM_FIELD};
+
+/// Hidden CSRF token for plain (non-HTMX) forms. `submit_button` includes it,
+/// so only forms with a custom submit button need to add it themselves.
+pub fn csrf_field() -> Markup {
+    csrf_field_with(&csrf::current_token())
+}
+
+/// For fragments rendered outside the csrf middleware's scope, such as those
+/// sent over server-sent events, which pass the session's token in.
+pub fn csrf_field_with(token: &str) -> Markup {
+    html! {
+        input type="hidden" name=(CSRF_FORM_FIELD) value=(token);
+    }
+}
+
 pub fn input(
     input_type: &str,
     name: &str,
@@ -51,6 +67,7 @@ pub fn input_with_label(
 
 pub fn submit_button(text: &str) -> Markup {
     html! {
+        (csrf_field())
         button type="submit" class="w-full bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700" {
             (text)
         }
@@ -1,3 +1,4 @@
 pub mod admin;
+pub mod credit_ledger;
 pub mod flash;
 pub mod form;
@@ -12,6 +12,17 @@ pub fn format_price(amount: i32) -> String {
         .join(",")
 }
 
+pub fn format_file_size(bytes: i32) -> String {
+    let bytes = bytes as f64;
+    if bytes < 1024.0 {
+        format!("{} B", bytes)
+    } else if bytes < 1024.0 * 1024.0 {
+        format!("{:.2} KB", bytes / 1024.0)
+    } else {
+        format!("{:.2} MB", bytes / (1024.0 * 1024.0))
+    }
+}
+
 pub fn format_datetime(datetime: DateTime<Utc>) -> String {
     datetime.format("%Y-%m-%d %H:%M:%S").to_string()
 }
@@ -1,8 +1,15 @@
 use super::navigation;
-use crate::{auth::CurrentUser, constants::cdn, session::FlashMessage, paths, views::components};
+use crate::{auth::{csrf, CurrentUser}, constants::cdn, session::FlashMessage, paths, views::components};
 use maud::{html, Markup, DOCTYPE};
 
 pub fn base_layout(current_user: &CurrentUser, flash: Option<&FlashMessage>, site_name: &str, title: &str, meta_description: &str, content: Markup) -> Markup {
+    let navbar = navigation::navbar(current_user);
+    // Read once the navbar and content have rendered, so the token exists
+    // only if something on the page can submit it.
+    let csrf_token = csrf::issued_token().unwrap_or_default();
+    // Every HTMX request inherits hx-headers from <body>; fetch() callers read the meta tag.
+    let hx_headers = serde_json::json!({ csrf::CSRF_HEADER: &csrf_token }).to_string();
+
     html! {
         (DOCTYPE)
         html lang="en" {
@@ -11,6 +18,7 @@ pub fn base_layout(current_user: &CurrentUser, flash: Option<&FlashMessage>, sit
                 meta name="viewport" content="width=device-width, initial-scale=1.0";
                 title { (title) " - " (site_name) }
                 meta name="description" content=(meta_description);
+                meta name="csrf-token" content=(csrf_token);
 
                 link rel="icon" type="image/svg+xml" href=(paths::static_files::FAVICON);
 
@@ -22,8 +30,8 @@ pub fn base_layout(current_user: &CurrentUser, flash: Option<&FlashMessage>, sit
 
                 script src=(cdn::HYPERSCRIPT_URL) {}
             }
-            body class="min-h-screen flex flex-col" {
-                (navigation::navbar(current_user))
+            body class="min-h-screen flex flex-col" hx-headers=(hx_headers) {
+                (navbar)
                 main class="flex-grow container mx-auto px-4 py-8" {
                     (components::flash::flash(flash))
                     (content)
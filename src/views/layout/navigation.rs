@@ -1,9 +1,9 @@
-use crate::{auth::CurrentUser, paths};
+use crate::{auth::CurrentUser, paths, views::components::form};
 use maud::{html, Markup};
 
 pub fn navbar(current_user: &CurrentUser) -> Markup {
     html! {
-        header class="border-b" {
+        header class="border-b print:hidden" {
             nav class="container mx-auto px-4 py-4" {
                 div class="flex justify-between items-center" {
                     div class="flex gap-4" {
@@ -23,7 +23,9 @@ pub fn navbar(current_user: &CurrentUser) -> Markup {
                                 @if current_user.is_admin() {
                                     a href=(paths::pages::admin::HOME) class="hover:text-indigo-600" { "Admin" }
                                 }
+                                a href=(paths::pages::ACCOUNT) class="hover:text-indigo-600" { "Account" }
                                 form method="post" action=(paths::actions::SIGN_OUT) class="inline" {
+                                    (form::csrf_field())
                                     button type="submit" class="hover:text-indigo-600" { "Sign Out" }
                                 }
                             }
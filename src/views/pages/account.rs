@@ -0,0 +1,100 @@
+use crate::{
+    auth::CurrentUser,
+    models::passkey::{FIELD_NAME, Passkey},
+    paths,
+    session::FlashMessage,
+    views::{components::form, helpers as formatting, layout::base::base_layout},
+};
+use maud::{html, Markup};
+
+pub fn account(
+    current_user: &CurrentUser,
+    flash: Option<&FlashMessage>,
+    site_name: &str,
+    passkeys: Vec<Passkey>,
+) -> Markup {
+    let email = match current_user {
+        CurrentUser::Authenticated { email, .. } => email.as_str(),
+        CurrentUser::Guest => "",
+    };
+
+    let content = html! {
+        div class="max-w-2xl mx-auto space-y-6" {
+            div {
+                h1 class="text-xl mb-1" { "Account" }
+                p class="text-gray-600" { (email) }
+            }
+
+            @if current_user.is_admin() {
+                section {
+                    h2 class="text-lg mb-2" { "Two-Factor Authentication" }
+                    p class="text-sm text-gray-600 mb-3" {
+                        "Admin access requires a code from an authenticator app after each sign-in."
+                    }
+                    a href=(paths::pages::ACCOUNT_TWO_FACTOR) class="text-indigo-600 hover:underline" {
+                        "Manage two-factor authentication"
+                    }
+                }
+            }
+
+            section {
+                h2 class="text-lg mb-2" { "Passkeys" }
+                p class="text-sm text-gray-600 mb-3" {
+                    "Sign in with your fingerprint, face or device PIN instead of waiting for a magic link."
+                }
+
+                @if passkeys.is_empty() {
+                    p class="text-gray-500 py-2" { "No passkeys yet" }
+                } @else {
+                    ul class="mb-4" {
+                        @for passkey in &passkeys {
+                            (passkey_item(passkey))
+                        }
+                    }
+                }
+
+                form id="passkey-register" hidden
+                    data-start=(paths::actions::PASSKEYS_REGISTER_START)
+                    data-finish=(paths::actions::PASSKEYS_REGISTER_FINISH)
+                    class="space-y-3"
+                {
+                    (form::input("text", FIELD_NAME, "Passkey name, e.g. \"Work laptop\"", None, None))
+                    (form::submit_button("Add Passkey"))
+                }
+                p data-passkey-unsupported hidden class="text-sm text-gray-500" {
+                    "This browser does not support passkeys."
+                }
+            }
+        }
+        script src=(paths::static_files::PASSKEYS_JS) {}
+    };
+
+    base_layout(current_user, flash, site_name, "Account", "Manage your account", content)
+}
+
+fn passkey_item(passkey: &Passkey) -> Markup {
+    let last_used = passkey
+        .last_used_at
+        .map(formatting::format_datetime)
+        .unwrap_or_else(|| "Never".to_string());
+
+    html! {
+        li class="flex items-center gap-3 py-2 border-b" id={"passkey-" (passkey.id)} {
+            div class="flex-1" {
+                p { (passkey.name) }
+                p class="text-xs text-gray-500" {
+                    "Added " (formatting::format_datetime(passkey.created_at)) " · Last used " (last_used)
+                }
+            }
+
+            form
+                hx-delete={(paths::with_param(paths::actions::PASSKEYS_PASSKEY_ID, "passkey_id", &passkey.id))}
+                hx-confirm="Remove this passkey? You will no longer be able to sign in with it."
+                hx-target={"#passkey-" (passkey.id)}
+                hx-swap="outerHTML"
+            {
+                button type="submit" class="text-red-600 hover:text-red-700" { "Remove" }
+            }
+        }
+    }
+}
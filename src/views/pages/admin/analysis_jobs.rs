@@ -0,0 +1,99 @@
+use crate::{
+    auth::CurrentUser,
+    session::FlashMessage,
+    views::helpers as formatting,
+    models::{admin::PaginatedResult, analysis_job::{AnalysisJobListItem, AnalysisJobStatus}},
+    paths,
+    views::{components::{admin::pagination, form}, layout::base::base_layout},
+};
+use maud::{html, Markup};
+
+pub fn analysis_jobs(
+    current_user: &CurrentUser,
+    flash: Option<&FlashMessage>,
+    site_name: &str,
+    paginated: PaginatedResult<AnalysisJobListItem>,
+) -> Markup {
+    let content = html! {
+        div class="max-w-6xl mx-auto" {
+            h1 class="text-xl mb-2" { "Failed Analysis Jobs" }
+            p class="text-sm text-gray-600 mb-6" {
+                "Jobs that hit an error. Queued jobs retry on their own at the time shown; "
+                "failed jobs are out of attempts and only run again when retried here."
+            }
+
+            @if paginated.items.is_empty() {
+                p class="text-gray-500 py-4" { "No failing jobs" }
+            } @else {
+                table class="w-full text-sm" {
+                    thead class="border-b" {
+                        tr {
+                            th class="text-left py-2 px-2" { "Order #" }
+                            th class="text-left py-2 px-2" { "User" }
+                            th class="text-center py-2 px-2" { "Status" }
+                            th class="text-center py-2 px-2" { "Attempts" }
+                            th class="text-left py-2 px-2" { "Last Error" }
+                            th class="text-center py-2 px-2" { "Next Run" }
+                            th class="text-center py-2 px-2" { "Actions" }
+                        }
+                    }
+                    tbody {
+                        @for job in &paginated.items {
+                            (job_row(job))
+                        }
+                    }
+                }
+
+                (pagination(
+                    paths::pages::admin::ANALYSIS_JOBS,
+                    paginated.page,
+                    paginated.total_pages,
+                    paginated.has_prev(),
+                    paginated.has_next(),
+                ))
+            }
+        }
+    };
+
+    base_layout(current_user, flash, site_name, "Failed Analysis Jobs", "Background analysis jobs that hit errors", content)
+}
+
+fn job_row(job: &AnalysisJobListItem) -> Markup {
+    html! {
+        tr class="border-b align-top" {
+            td class="py-2 px-2" {
+                a href=(paths::helpers::order_detail_path(&job.order))
+                    class="text-indigo-600 hover:underline"
+                {
+                    (job.order_number)
+                }
+            }
+            td class="py-2 px-2 text-gray-600" { (job.user_email) }
+            td class="py-2 px-2 text-center" {
+                span class={"px-2 py-1 text-xs " (job.status.css_class())} {
+                    (job.status.display_text())
+                }
+            }
+            td class="py-2 px-2 text-center" { (job.attempts) }
+            td class="py-2 px-2 text-gray-600 break-all" { (job.last_error.as_deref().unwrap_or("")) }
+            td class="py-2 px-2 text-center text-gray-600" {
+                @if job.status == AnalysisJobStatus::Queued {
+                    (formatting::format_datetime(job.run_at))
+                } @else {
+                    "—"
+                }
+            }
+            td class="py-2 px-2 text-center" {
+                // A running job belongs to a worker; it can only be retried once it stops.
+                @if job.status != AnalysisJobStatus::Running {
+                    form method="post" action=(paths::helpers::retry_analysis_job_path(&job.id)) {
+                        (form::csrf_field())
+                        button type="submit" class="text-indigo-600 hover:underline text-sm" {
+                            @if job.status == AnalysisJobStatus::Failed { "Retry" } @else { "Retry Now" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    auth::CurrentUser,
+    session::FlashMessage,
+    views::helpers as formatting,
+    models::coupon::{
+        Coupon, DiscountKind, FIELD_CODE, FIELD_ENDS_ON, FIELD_KIND, FIELD_MAX_REDEMPTIONS,
+        FIELD_MAX_REDEMPTIONS_PER_USER, FIELD_MIN_ORDER_AMOUNT, FIELD_STARTS_ON, FIELD_VALUE,
+    },
+    paths,
+    views::{components::form, layout::base::base_layout},
+};
+use maud::{html, Markup};
+
+const INPUT_CLASS: &str = "w-full px-3 py-2 border focus:outline-none focus:border-indigo-600";
+
+pub fn coupons(
+    current_user: &CurrentUser,
+    flash: Option<&FlashMessage>,
+    site_name: &str,
+    coupons: &[Coupon],
+) -> Markup {
+    let now = Utc::now();
+
+    let content = html! {
+        div class="max-w-6xl mx-auto" {
+            h1 class="text-xl mb-2" { "Coupons" }
+            p class="text-sm text-gray-600 mb-6" {
+                "Customers enter a code on the quote page. Uses count pending and paid orders, "
+                "so a code on an abandoned order is freed once the order fails or is cancelled."
+            }
+
+            div class="mb-8 border p-4" {
+                h2 class="text-lg mb-3" { "New Coupon" }
+                (create_form())
+            }
+
+            @if coupons.is_empty() {
+                p class="text-gray-500 py-4" { "No coupons yet" }
+            } @else {
+                table class="w-full text-sm" {
+                    thead class="border-b" {
+                        tr {
+                            th class="text-left py-2 px-2" { "Code" }
+                            th class="text-right py-2 px-2" { "Discount" }
+                            th class="text-left py-2 px-2" { "Valid" }
+                            th class="text-right py-2 px-2" { "Min. Order" }
+                            th class="text-right py-2 px-2" { "Uses" }
+                            th class="text-right py-2 px-2" { "Per User" }
+                            th class="text-center py-2 px-2" { "Status" }
+                            th class="text-center py-2 px-2" { "Actions" }
+                        }
+                    }
+                    tbody {
+                        @for coupon in coupons {
+                            (coupon_row(coupon, now))
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    base_layout(current_user, flash, site_name, "Coupons", "Discount codes", content)
+}
+
+fn create_form() -> Markup {
+    html! {
+        form method="post" action=(paths::forms::admin::CREATE_COUPON) class="space-y-3 text-sm" {
+            (form::csrf_field())
+            div class="grid grid-cols-3 gap-4" {
+                div {
+                    label for=(FIELD_CODE) class="block mb-1" { "Code" }
+                    input type="text" name=(FIELD_CODE) id=(FIELD_CODE) required class=(INPUT_CLASS) placeholder="SPRING25";
+                }
+                div {
+                    label for=(FIELD_KIND) class="block mb-1" { "Type" }
+                    select name=(FIELD_KIND) id=(FIELD_KIND) class=(INPUT_CLASS) {
+                        option value="percent" { "Percent off" }
+                        option value="fixed" { "Amount off (₩)" }
+                    }
+                }
+                div {
+                    label for=(FIELD_VALUE) class="block mb-1" { "Value" }
+                    input type="number" name=(FIELD_VALUE) id=(FIELD_VALUE) min="1" required class=(INPUT_CLASS) placeholder="25";
+                }
+            }
+            div class="grid grid-cols-3 gap-4" {
+                div {
+                    label for=(FIELD_STARTS_ON) class="block mb-1" { "First day (UTC)" }
+                    input type="date" name=(FIELD_STARTS_ON) id=(FIELD_STARTS_ON) class=(INPUT_CLASS);
+                }
+                div {
+                    label for=(FIELD_ENDS_ON) class="block mb-1" { "Last day (UTC)" }
+                    input type="date" name=(FIELD_ENDS_ON) id=(FIELD_ENDS_ON) class=(INPUT_CLASS);
+                }
+                div {
+                    label for=(FIELD_MIN_ORDER_AMOUNT) class="block mb-1" { "Minimum order (₩)" }
+                    input type="number" name=(FIELD_MIN_ORDER_AMOUNT) id=(FIELD_MIN_ORDER_AMOUNT) min="0" class=(INPUT_CLASS) placeholder="0";
+                }
+            }
+            div class="grid grid-cols-3 gap-4" {
+                div {
+                    label for=(FIELD_MAX_REDEMPTIONS) class="block mb-1" { "Total uses" }
+                    input type="number" name=(FIELD_MAX_REDEMPTIONS) id=(FIELD_MAX_REDEMPTIONS) min="1" class=(INPUT_CLASS) placeholder="Unlimited";
+                }
+                div {
+                    label for=(FIELD_MAX_REDEMPTIONS_PER_USER) class="block mb-1" { "Uses per customer" }
+                    input type="number" name=(FIELD_MAX_REDEMPTIONS_PER_USER) id=(FIELD_MAX_REDEMPTIONS_PER_USER) min="1" class=(INPUT_CLASS) placeholder="Unlimited";
+                }
+            }
+            button type="submit" class="bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700" { "Create Coupon" }
+        }
+    }
+}
+
+fn coupon_row(coupon: &Coupon, now: DateTime<Utc>) -> Markup {
+    let (status, status_class) = if !coupon.active {
+        ("Inactive", "text-gray-600")
+    } else if coupon.ends_at.is_some_and(|ends_at| ends_at <= now) {
+        ("Expired", "text-gray-600")
+    } else if coupon.starts_at.is_some_and(|starts_at| starts_at > now) {
+        ("Scheduled", "text-yellow-600")
+    } else {
+        ("Active", "text-green-600")
+    };
+
+    html! {
+        tr class="border-b" {
+            td class="py-2 px-2 font-mono" { (coupon.code) }
+            td class="py-2 px-2 text-right" {
+                @match coupon.kind {
+                    DiscountKind::Percent => { (coupon.value) "%" }
+                    DiscountKind::Fixed => { "₩" (formatting::format_price(coupon.value)) }
+                }
+            }
+            td class="py-2 px-2 text-gray-600" { (validity_text(coupon)) }
+            td class="py-2 px-2 text-right" { "₩" (formatting::format_price(coupon.min_order_amount)) }
+            td class="py-2 px-2 text-right" {
+                (coupon.redemption_count)
+                @if let Some(max) = coupon.max_redemptions {
+                    " / " (max)
+                }
+            }
+            td class="py-2 px-2 text-right" {
+                @match coupon.max_redemptions_per_user {
+                    Some(max) => (max),
+                    None => "—",
+                }
+            }
+            td class="py-2 px-2 text-center" {
+                span class={"px-2 py-1 text-xs " (status_class)} { (status) }
+            }
+            td class="py-2 px-2 text-center" {
+                @if coupon.active {
+                    form method="post" action=(paths::helpers::deactivate_coupon_path(&coupon.id))
+                        onsubmit="return confirm('Deactivate this coupon?')"
+                    {
+                        (form::csrf_field())
+                        button type="submit" class="text-red-600 hover:underline" { "Deactivate" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Dates as entered: `ends_at` is midnight after the last day.
+fn validity_text(coupon: &Coupon) -> String {
+    let day = |datetime: DateTime<Utc>| datetime.format("%Y-%m-%d").to_string();
+
+    match (coupon.starts_at, coupon.ends_at) {
+        (None, None) => "Always".to_string(),
+        (Some(starts_at), None) => format!("From {}", day(starts_at)),
+        (None, Some(ends_at)) => format!("Until {}", day(ends_at - Duration::days(1))),
+        (Some(starts_at), Some(ends_at)) => format!("{} – {}", day(starts_at), day(ends_at - Duration::days(1))),
+    }
+}
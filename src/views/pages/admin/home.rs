@@ -41,6 +41,27 @@ pub fn home(
                         "View All Orders"
                     }
                 }
+                div {
+                    a href=(paths::pages::admin::ANALYSIS_JOBS)
+                        class="text-indigo-600 hover:underline"
+                    {
+                        "Failed Analysis Jobs"
+                    }
+                }
+                div {
+                    a href=(paths::pages::admin::PRICING)
+                        class="text-indigo-600 hover:underline"
+                    {
+                        "Pricing"
+                    }
+                }
+                div {
+                    a href=(paths::pages::admin::COUPONS)
+                        class="text-indigo-600 hover:underline"
+                    {
+                        "Coupons"
+                    }
+                }
             }
         }
     };
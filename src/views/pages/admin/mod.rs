@@ -1,11 +1,17 @@
+mod analysis_jobs;
+mod coupons;
 mod home;
 mod orders;
 mod order_detail;
+mod pricing;
 mod users;
 mod user_detail;
 
+pub use analysis_jobs::analysis_jobs;
+pub use coupons::coupons;
 pub use home::home;
 pub use orders::orders;
 pub use order_detail::order_detail;
+pub use pricing::pricing;
 pub use users::users;
 pub use user_detail::user_detail;
@@ -2,9 +2,15 @@ use crate::{
     auth::CurrentUser,
     session::FlashMessage,
     views::helpers as formatting,
-    models::admin::OrderDetail,
+    models::{
+        admin::OrderDetail,
+        order::OrderItemSummary,
+        order_event::OrderEvent,
+        payment_event::PaymentEvent,
+        refund::{FIELD_AMOUNT, FIELD_REASON},
+    },
     paths,
-    views::layout::base::base_layout,
+    views::{components::form, layout::base::base_layout},
 };
 use maud::{html, Markup};
 
@@ -13,6 +19,9 @@ pub fn order_detail(
     flash: Option<&FlashMessage>,
     site_name: &str,
     order: OrderDetail,
+    items: Vec<OrderItemSummary>,
+    order_events: Vec<OrderEvent>,
+    payment_events: Vec<PaymentEvent>,
 ) -> Markup {
     let content = html! {
         div class="max-w-6xl mx-auto" {
@@ -47,6 +56,23 @@ pub fn order_detail(
                         span class="text-gray-600" { "Amount: " }
                         span { "₩" (formatting::format_price(order.price_amount)) }
                     }
+                    @if let Some(code) = &order.coupon_code {
+                        div {
+                            span class="text-gray-600" { "Coupon: " }
+                            span class="font-mono" { (code) }
+                            span { " (−₩" (formatting::format_price(order.discount_amount)) ")" }
+                        }
+                    }
+                    @if let Some(version) = order.pricing_version {
+                        div {
+                            span class="text-gray-600" { "Pricing: " }
+                            a href=(paths::pages::admin::PRICING)
+                                class="text-indigo-600 hover:underline"
+                            {
+                                "Version " (version)
+                            }
+                        }
+                    }
                     div {
                         span class="text-gray-600" { "Created: " }
                         span { (formatting::format_datetime(order.created_at)) }
@@ -55,6 +81,15 @@ pub fn order_detail(
                         div {
                             span class="text-gray-600" { "Paid: " }
                             span { (formatting::format_datetime(paid_at)) }
+                            @if order.paid_with_credits {
+                                span class="text-gray-600" { " (credits)" }
+                            }
+                        }
+                    }
+                    @if let Some(expired_at) = order.expired_at {
+                        div {
+                            span class="text-gray-600" { "Expired: " }
+                            span { (formatting::format_datetime(expired_at)) }
                         }
                     }
                 }
@@ -92,23 +127,38 @@ pub fn order_detail(
                 }
             }
 
-            div class="border p-4" {
-                h2 class="text-lg mb-3" { "File Information" }
-                div class="space-y-2 text-sm" {
-                    div {
-                        span class="text-gray-600" { "Filename: " }
-                        span { (order.filename) }
-                    }
-                    div {
-                        span class="text-gray-600" { "Character Count: " }
-                        span { (order.text_length) }
+            div class="mb-8 border p-4" {
+                h2 class="text-lg mb-3" { "Files" }
+                table class="w-full text-sm" {
+                    thead {
+                        tr class="text-left text-gray-600 border-b" {
+                            th class="py-1 font-normal" { "Filename" }
+                            th class="py-1 font-normal" { "Encoding" }
+                            th class="py-1 font-normal text-right" { "Size" }
+                            th class="py-1 font-normal text-right" { "Characters" }
+                            th class="py-1 font-normal text-right" { "Price" }
+                        }
                     }
-                    div {
-                        span class="text-gray-600" { "Price Calculation: " }
-                        span { (order.text_length) " characters × ₩1 = ₩" (formatting::format_price(order.price_amount)) }
+                    tbody {
+                        @for item in &items {
+                            tr class="border-b" {
+                                td class="py-1 break-all" { (item.filename) }
+                                td class="py-1 text-gray-600" { (item.text_encoding.as_deref().unwrap_or("—")) }
+                                td class="py-1 text-right" { (formatting::format_file_size(item.file_size)) }
+                                td class="py-1 text-right" { (item.text_length) }
+                                td class="py-1 text-right" { "₩" (formatting::format_price(item.price_amount)) }
+                            }
+                        }
                     }
                 }
+                p class="text-sm text-gray-600 mt-2" {
+                    (order.text_length) " characters in total, charged ₩" (formatting::format_price(order.price_amount))
+                }
             }
+
+            (refunds_section(&order))
+            (timeline_section(&order_events))
+            (payment_events_section(&payment_events))
         }
     };
 
@@ -121,3 +171,178 @@ pub fn order_detail(
         content,
     )
 }
+
+fn refunds_section(order: &OrderDetail) -> Markup {
+    let refundable = order.refundable_amount();
+
+    html! {
+        div class="mb-8 border p-4" {
+            h2 class="text-lg mb-3" { "Refunds" }
+
+            @if order.refunds.is_empty() {
+                p class="text-sm text-gray-500 mb-3" { "No refunds issued" }
+            } @else {
+                table class="w-full text-sm mb-3" {
+                    thead class="border-b" {
+                        tr {
+                            th class="text-left py-2 px-2" { "Date" }
+                            th class="text-right py-2 px-2" { "Amount" }
+                            th class="text-left py-2 px-2" { "Reason" }
+                            th class="text-left py-2 px-2" { "Issued By" }
+                        }
+                    }
+                    tbody {
+                        @for refund in &order.refunds {
+                            tr class="border-b" {
+                                td class="py-2 px-2 text-gray-600" { (formatting::format_datetime(refund.refunded_at)) }
+                                td class="py-2 px-2 text-right" { "₩" (formatting::format_price(refund.amount)) }
+                                td class="py-2 px-2" { (refund.reason) }
+                                td class="py-2 px-2" {
+                                    a href=(paths::helpers::user_detail_path(&refund.refunded_by))
+                                        class="text-indigo-600 hover:underline"
+                                    {
+                                        (refund.refunded_by_email)
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                p class="text-sm mb-3" {
+                    span class="text-gray-600" { "Total Refunded: " }
+                    span { "₩" (formatting::format_price(order.refunded_amount)) }
+                }
+            }
+
+            @if refundable > 0 {
+                form method="post"
+                    action=(paths::with_param(paths::forms::admin::REFUND_ORDER, "order_id", &order.id))
+                    class="space-y-3 text-sm"
+                    onsubmit="return confirm('Issue this refund? This cannot be undone.')"
+                {
+                    (form::csrf_field())
+                    @if order.paid_with_credits {
+                        p class="text-gray-600" { "Paid from credits: the refund goes back to the customer's credit balance." }
+                    }
+                    div {
+                        label for=(FIELD_AMOUNT) class="block mb-1" { "Amount (₩)" }
+                        input type="number" name=(FIELD_AMOUNT) id=(FIELD_AMOUNT)
+                            min="1" max=(refundable)
+                            class="w-full px-3 py-2 border focus:outline-none focus:border-indigo-600"
+                            placeholder={"Leave empty to refund the remaining ₩" (formatting::format_price(refundable))};
+                    }
+                    div {
+                        label for=(FIELD_REASON) class="block mb-1" { "Reason" }
+                        input type="text" name=(FIELD_REASON) id=(FIELD_REASON) required
+                            class="w-full px-3 py-2 border focus:outline-none focus:border-indigo-600"
+                            placeholder="Customer request";
+                    }
+                    button type="submit" class="text-red-600 hover:underline" { "Issue Refund" }
+                }
+            }
+        }
+    }
+}
+
+/// Every payment status change, oldest first.
+fn timeline_section(events: &[OrderEvent]) -> Markup {
+    html! {
+        div class="mb-8 border p-4" {
+            h2 class="text-lg mb-3" { "Status Timeline" }
+            @if events.is_empty() {
+                p class="text-sm text-gray-500" { "No status changes recorded" }
+            } @else {
+                ol class="text-sm space-y-3 border-l pl-4" {
+                    @for event in events {
+                        li {
+                            div class="flex flex-wrap gap-2" {
+                                span class="text-gray-600" { (formatting::format_datetime(event.created_at)) }
+                                span {
+                                    @if let Some(from) = event.from_status.filter(|from| *from != event.to_status) {
+                                        span class=(from.css_class()) { (from.display_text()) }
+                                        " → "
+                                    }
+                                    span class=(event.to_status.css_class()) { (event.to_status.display_text()) }
+                                }
+                                span class="text-gray-600" {
+                                    "by " (event.actor_kind.display_text())
+                                    @if let (Some(actor), Some(email)) = (&event.actor, &event.actor_email) {
+                                        " "
+                                        a href=(paths::helpers::user_detail_path(actor))
+                                            class="text-indigo-600 hover:underline"
+                                        {
+                                            (email)
+                                        }
+                                    }
+                                }
+                            }
+                            @if let Some(note) = &event.note {
+                                p class="text-gray-600" { (note) }
+                            }
+                            @if let Some(payload) = &event.payload {
+                                details {
+                                    summary class="cursor-pointer text-indigo-600" { "Provider payload" }
+                                    pre class="mt-2 text-xs whitespace-pre-wrap break-all" { (payload) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn payment_events_section(events: &[PaymentEvent]) -> Markup {
+    html! {
+        div class="border p-4" {
+            h2 class="text-lg mb-3" { "Payment Events" }
+            @if events.is_empty() {
+                p class="text-sm text-gray-500" { "No webhook events received" }
+            } @else {
+                table class="w-full text-sm" {
+                    thead class="border-b" {
+                        tr {
+                            th class="text-left py-2 px-2" { "Received" }
+                            th class="text-left py-2 px-2" { "Event" }
+                            th class="text-left py-2 px-2" { "Provider Status" }
+                            th class="text-center py-2 px-2" { "Outcome" }
+                            th class="text-left py-2 px-2" { "Signature" }
+                            th class="text-left py-2 px-2" { "Payload" }
+                        }
+                    }
+                    tbody {
+                        @for event in events {
+                            tr class="border-b align-top" {
+                                td class="py-2 px-2 text-gray-600" { (formatting::format_datetime(event.received_at)) }
+                                td class="py-2 px-2" { (event.event_type) }
+                                td class="py-2 px-2" { (event.provider_status.as_deref().unwrap_or("—")) }
+                                td class="py-2 px-2 text-center" {
+                                    span class={"px-2 py-1 text-xs " (event.outcome.css_class())} {
+                                        (event.outcome.display_text())
+                                    }
+                                }
+                                td class="py-2 px-2 text-gray-600" {
+                                    @match event.signature_valid {
+                                        Some(true) => "Valid",
+                                        Some(false) => "Invalid",
+                                        None => "Unsigned",
+                                    }
+                                }
+                                td class="py-2 px-2" {
+                                    details {
+                                        summary class="cursor-pointer text-indigo-600" { "View" }
+                                        pre class="mt-2 text-xs whitespace-pre-wrap break-all" { (event.payload) }
+                                        @if event.payload_truncated {
+                                            p class="mt-1 text-xs text-gray-500" { "Truncated" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -24,6 +24,8 @@ pub fn orders(
                 (filter_tab("Paid", &paths::with_query_param(paths::pages::admin::ORDERS, "status", "paid"), matches!(filter, Some(PaymentStatus::Paid))))
                 (filter_tab("Pending", &paths::with_query_param(paths::pages::admin::ORDERS, "status", "pending"), matches!(filter, Some(PaymentStatus::Pending))))
                 (filter_tab("Failed", &paths::with_query_param(paths::pages::admin::ORDERS, "status", "failed"), matches!(filter, Some(PaymentStatus::Failed))))
+                (filter_tab("Partially Refunded", &paths::with_query_param(paths::pages::admin::ORDERS, "status", "partially_refunded"), matches!(filter, Some(PaymentStatus::PartiallyRefunded))))
+                (filter_tab("Refunded", &paths::with_query_param(paths::pages::admin::ORDERS, "status", "refunded"), matches!(filter, Some(PaymentStatus::Refunded))))
             }
 
             @if paginated.items.is_empty() {
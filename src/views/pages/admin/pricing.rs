@@ -0,0 +1,136 @@
+use crate::{
+    auth::CurrentUser,
+    constants::coupons::MIN_PAYABLE_AMOUNT,
+    session::FlashMessage,
+    views::helpers as formatting,
+    models::pricing::{
+        PricingRuleSet, PricingRules, FIELD_MINIMUM_ORDER_AMOUNT, FIELD_RATE_PER_THOUSAND, FIELD_SURCHARGES,
+        FIELD_TIERS,
+    },
+    paths,
+    views::{components::form, layout::base::base_layout},
+};
+use maud::{html, Markup};
+
+pub fn pricing(
+    current_user: &CurrentUser,
+    flash: Option<&FlashMessage>,
+    site_name: &str,
+    current: &PricingRuleSet,
+    history: &[PricingRuleSet],
+) -> Markup {
+    let content = html! {
+        div class="max-w-6xl mx-auto" {
+            h1 class="text-xl mb-2" { "Pricing" }
+            p class="text-sm text-gray-600 mb-6" {
+                "Publishing creates a new version for orders created from then on. "
+                "Existing orders keep the version they were quoted with."
+            }
+
+            div class="mb-8 border p-4" {
+                h2 class="text-lg mb-3" { "Edit Version " (current.version) }
+                (editor(&current.rules))
+            }
+
+            div class="border p-4" {
+                h2 class="text-lg mb-3" { "History" }
+                table class="w-full text-sm" {
+                    thead class="border-b" {
+                        tr {
+                            th class="text-left py-2 px-2" { "Version" }
+                            th class="text-left py-2 px-2" { "Published" }
+                            th class="text-left py-2 px-2" { "By" }
+                            th class="text-right py-2 px-2" { "Rate / 1,000" }
+                            th class="text-right py-2 px-2" { "Minimum" }
+                            th class="text-left py-2 px-2" { "Tiers" }
+                            th class="text-left py-2 px-2" { "Surcharges" }
+                        }
+                    }
+                    tbody {
+                        @for rule_set in history {
+                            (history_row(rule_set))
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    base_layout(current_user, flash, site_name, "Pricing", "Pricing rules and their history", content)
+}
+
+fn editor(rules: &PricingRules) -> Markup {
+    html! {
+        form method="post" action=(paths::forms::admin::PUBLISH_PRICING)
+            class="space-y-3 text-sm"
+            onsubmit="return confirm('Publish these prices for all new orders?')"
+        {
+            (form::csrf_field())
+            div class="grid grid-cols-2 gap-4" {
+                div {
+                    label for=(FIELD_RATE_PER_THOUSAND) class="block mb-1" { "Base rate (₩ per 1,000 characters)" }
+                    input type="number" name=(FIELD_RATE_PER_THOUSAND) id=(FIELD_RATE_PER_THOUSAND)
+                        min="0" required value=(rules.rate_per_thousand)
+                        class="w-full px-3 py-2 border focus:outline-none focus:border-indigo-600";
+                }
+                div {
+                    label for=(FIELD_MINIMUM_ORDER_AMOUNT) class="block mb-1" { "Minimum order (₩)" }
+                    input type="number" name=(FIELD_MINIMUM_ORDER_AMOUNT) id=(FIELD_MINIMUM_ORDER_AMOUNT)
+                        min=(MIN_PAYABLE_AMOUNT) required value=(rules.minimum_order_amount)
+                        class="w-full px-3 py-2 border focus:outline-none focus:border-indigo-600";
+                }
+            }
+            div class="grid grid-cols-2 gap-4" {
+                div {
+                    label for=(FIELD_TIERS) class="block mb-1" { "Volume tiers" }
+                    textarea name=(FIELD_TIERS) id=(FIELD_TIERS) rows="4"
+                        class="w-full px-3 py-2 border font-mono focus:outline-none focus:border-indigo-600"
+                        placeholder="10000 = 800"
+                    { (rules.tiers_text()) }
+                    p class="text-xs text-gray-500 mt-1" {
+                        "One per line: characters in the order = ₩ per 1,000 characters. "
+                        "The highest tier an order reaches sets the rate for all of it."
+                    }
+                }
+                div {
+                    label for=(FIELD_SURCHARGES) class="block mb-1" { "File type surcharges" }
+                    textarea name=(FIELD_SURCHARGES) id=(FIELD_SURCHARGES) rows="4"
+                        class="w-full px-3 py-2 border font-mono focus:outline-none focus:border-indigo-600"
+                        placeholder="pdf = 300"
+                    { (rules.surcharges_text()) }
+                    p class="text-xs text-gray-500 mt-1" {
+                        "One per line: extension = ₩ added to each file detected as that type, whatever it is named."
+                    }
+                }
+            }
+            button type="submit" class="bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700" { "Publish New Version" }
+        }
+    }
+}
+
+fn history_row(rule_set: &PricingRuleSet) -> Markup {
+    let rules = &rule_set.rules;
+
+    html! {
+        tr class="border-b align-top" {
+            td class="py-2 px-2" { (rule_set.version) }
+            td class="py-2 px-2 text-gray-600" { (formatting::format_datetime(rule_set.created_at)) }
+            td class="py-2 px-2 text-gray-600" {
+                @match (&rule_set.created_by, &rule_set.created_by_email) {
+                    (Some(user_id), Some(email)) => {
+                        a href=(paths::helpers::user_detail_path(user_id))
+                            class="text-indigo-600 hover:underline"
+                        {
+                            (email)
+                        }
+                    }
+                    _ => "Initial",
+                }
+            }
+            td class="py-2 px-2 text-right" { "₩" (formatting::format_price(rules.rate_per_thousand)) }
+            td class="py-2 px-2 text-right" { "₩" (formatting::format_price(rules.minimum_order_amount)) }
+            td class="py-2 px-2 font-mono text-xs whitespace-pre" { (rules.tiers_text()) }
+            td class="py-2 px-2 font-mono text-xs whitespace-pre" { (rules.surcharges_text()) }
+        }
+    }
+}
@@ -2,9 +2,15 @@ use crate::{
     auth::CurrentUser,
     session::FlashMessage,
     views::helpers as formatting,
-    models::admin::{OrderListItem, PaginatedResult, UserDetail},
+    models::{
+        admin::{OrderListItem, PaginatedResult, UserDetail},
+        credit::{CreditTransaction, FIELD_AMOUNT, FIELD_NOTE},
+    },
     paths,
-    views::{components::admin::{order_row, pagination}, layout::base::base_layout},
+    views::{
+        components::{admin::{order_row, pagination}, credit_ledger::credit_ledger, form},
+        layout::base::base_layout,
+    },
 };
 use maud::{html, Markup};
 
@@ -14,6 +20,8 @@ pub fn user_detail(
     site_name: &str,
     user: UserDetail,
     paginated_orders: PaginatedResult<OrderListItem>,
+    credit_balance: i32,
+    credit_transactions: &[CreditTransaction],
 ) -> Markup {
     let content = html! {
         div class="max-w-6xl mx-auto" {
@@ -29,6 +37,7 @@ pub fn user_detail(
 
             (user_info_section(&user))
             (admin_role_section(&user))
+            (credits_section(&user, credit_balance, credit_transactions))
             (user_orders_section(&user, &paginated_orders))
         }
     };
@@ -101,6 +110,7 @@ fn admin_role_section(user: &UserDetail) -> Markup {
                 form method="post"
                     action=(paths::with_param(paths::forms::admin::GRANT_ROLE, "user_id", &user.id))
                 {
+                    (form::csrf_field())
                     button type="submit"
                         class="text-sm text-indigo-600 hover:underline"
                     {
@@ -112,6 +122,32 @@ fn admin_role_section(user: &UserDetail) -> Markup {
     }
 }
 
+fn credits_section(user: &UserDetail, credit_balance: i32, credit_transactions: &[CreditTransaction]) -> Markup {
+    html! {
+        div class="mb-8 border p-4" {
+            h2 class="text-lg mb-3" { "Credits" }
+            div class="text-sm mb-3" {
+                span class="text-gray-600" { "Balance: " }
+                span { "₩" (formatting::format_price(credit_balance)) }
+            }
+
+            form method="post"
+                action=(paths::with_param(paths::forms::admin::ADJUST_CREDITS, "user_id", &user.id))
+                class="flex gap-2 text-sm mb-4"
+            {
+                (form::csrf_field())
+                input type="number" name=(FIELD_AMOUNT) required placeholder="Amount (negative to deduct)"
+                    class="w-56 px-3 py-2 border focus:outline-none focus:border-indigo-600";
+                input type="text" name=(FIELD_NOTE) required placeholder="Reason"
+                    class="flex-1 px-3 py-2 border focus:outline-none focus:border-indigo-600";
+                button type="submit" class="bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700" { "Adjust" }
+            }
+
+            (credit_ledger(credit_transactions, paths::helpers::order_detail_path))
+        }
+    }
+}
+
 fn user_orders_section(user: &UserDetail, paginated_orders: &PaginatedResult<OrderListItem>) -> Markup {
     html! {
         div {
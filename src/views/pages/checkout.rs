@@ -2,14 +2,24 @@ use maud::{html, Markup, PreEscaped};
 
 use crate::{
     auth::CurrentUser,
-    constants::{cdn, payment},
-    models::order::Order,
+    constants::{cdn, credits, payment},
+    models::{credit::CreditTopUp, order::Order, OrderNumber},
     paths,
+    payment::CheckoutWidget,
     session::FlashMessage,
     views::{helpers::format_price, layout::base},
 };
 
-fn toss_payment_script(client_key: &str, order: &Order, success_url: &str, fail_url: &str) -> Markup {
+/// What the payment widget charges for and where the provider sends the customer back to.
+struct PaymentRequest<'a> {
+    order_number: &'a OrderNumber,
+    amount: i32,
+    order_name: String,
+    success_url: &'a str,
+    fail_url: &'a str,
+}
+
+fn toss_payment_script(client_key: &str, request: &PaymentRequest) -> Markup {
     html! {
         script src=(cdn::TOSS_PAYMENTS_SDK_URL) {}
         script {
@@ -23,7 +33,7 @@ fn toss_payment_script(client_key: &str, order: &Order, success_url: &str, fail_
                     const paymentParams = {{
                         amount: {amount},
                         orderId: '{order_id}',
-                        orderName: '{prefix} - {filename}',
+                        orderName: '{order_name}',
                         successUrl: window.location.origin + '{success_url}',
                         failUrl: window.location.origin + '{fail_url}'
                     }};
@@ -44,26 +54,79 @@ fn toss_payment_script(client_key: &str, order: &Order, success_url: &str, fail_
                 }}
             "#,
                 client_key = client_key,
-                amount = order.price_amount,
-                order_id = order.order_number,
-                prefix = payment::ORDER_NAME_PREFIX,
-                filename = order.filename,
-                success_url = success_url,
-                fail_url = fail_url
+                amount = request.amount,
+                order_id = request.order_number,
+                order_name = request.order_name,
+                success_url = request.success_url,
+                fail_url = request.fail_url
             )))
         }
     }
 }
 
+/// Offline checkout: skips the provider UI and returns to the verify endpoint directly.
+fn mock_payment_form(request: &PaymentRequest, payment_key: &str) -> Markup {
+    html! {
+        form method="get" action=(request.success_url) {
+            input type="hidden" name="orderId" value=(request.order_number);
+            input type="hidden" name="paymentKey" value=(payment_key);
+            input type="hidden" name="amount" value=(request.amount);
+            p class="text-sm text-yellow-700 mb-3" { "Mock payment gateway — no real charge will be made." }
+            button
+                type="submit"
+                class="w-full bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700"
+                { "Pay Now" }
+        }
+    }
+}
+
+fn payment_widget(widget: &CheckoutWidget, request: &PaymentRequest) -> Markup {
+    html! {
+        @match widget {
+            CheckoutWidget::Toss { client_key } => {
+                div id="payment-method" class="mb-3" {}
+                div id="agreement" class="mb-3" {}
+
+                button
+                    id="payment-button"
+                    class="w-full bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700 disabled:bg-gray-400 disabled:cursor-not-allowed"
+                    { "Pay Now" }
+
+                (toss_payment_script(client_key, request))
+            }
+            CheckoutWidget::Mock { payment_key } => {
+                (mock_payment_form(request, payment_key))
+            }
+        }
+    }
+}
+
+fn total_row(amount: i32) -> Markup {
+    html! {
+        div class="border-t pt-3 mb-3" {
+            div class="flex justify-between items-center" {
+                span { "Total" }
+                span class="text-xl text-indigo-600" { "₩" (format_price(amount)) }
+            }
+        }
+    }
+}
+
 pub fn checkout(
     current_user: &CurrentUser,
     flash: Option<&FlashMessage>,
     site_name: &str,
     order: &Order,
-    client_key: &str,
+    widget: &CheckoutWidget,
 ) -> Markup {
-    let success_url = "/actions/payment/verify".to_string();
     let fail_url = paths::helpers::quote_path(&order.id);
+    let request = PaymentRequest {
+        order_number: &order.order_number,
+        amount: order.price_amount,
+        order_name: format!("{} - {}", payment::ORDER_NAME_PREFIX, order.filename),
+        success_url: paths::actions::PAYMENT_VERIFY,
+        fail_url: &fail_url,
+    };
 
     let content = html! {
         div class="max-w-lg mx-auto" {
@@ -72,34 +135,58 @@ pub fn checkout(
             div class="space-y-3" {
                 div class="space-y-1 text-sm" {
                     div class="flex justify-between" {
-                        span class="text-gray-600" { "File" }
+                        span class="text-gray-600" { @if order.item_count > 1 { "Files" } @else { "File" } }
                         span { (order.filename) }
                     }
                     div class="flex justify-between" {
                         span class="text-gray-600" { "Characters" }
                         span { (order.text_length.to_string()) }
                     }
-                }
-
-                div class="border-t pt-3 mb-3" {
-                    div class="flex justify-between items-center" {
-                        span { "Total" }
-                        span class="text-xl text-indigo-600" { "₩" (format_price(order.price_amount)) }
+                    @if let Some(code) = &order.coupon_code {
+                        div class="flex justify-between" {
+                            span class="text-gray-600" { "Coupon " (code) }
+                            span { "−₩" (format_price(order.discount_amount)) }
+                        }
                     }
                 }
 
-                div id="payment-method" class="mb-3" {}
-                div id="agreement" class="mb-3" {}
-
-                button
-                    id="payment-button"
-                    class="w-full bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700 disabled:bg-gray-400 disabled:cursor-not-allowed"
-                    { "Pay Now" }
+                (total_row(order.price_amount))
+                (payment_widget(widget, &request))
             }
         }
-
-        (toss_payment_script(client_key, order, &success_url, &fail_url))
     };
 
     base::base_layout(current_user, flash, site_name, "Checkout", "Complete your payment", content)
 }
+
+pub fn top_up_checkout(
+    current_user: &CurrentUser,
+    flash: Option<&FlashMessage>,
+    site_name: &str,
+    top_up: &CreditTopUp,
+    widget: &CheckoutWidget,
+) -> Markup {
+    let request = PaymentRequest {
+        order_number: &top_up.order_number,
+        amount: top_up.amount,
+        order_name: credits::TOP_UP_ORDER_NAME.to_string(),
+        success_url: paths::actions::CREDITS_VERIFY,
+        fail_url: paths::pages::DASHBOARD,
+    };
+
+    let content = html! {
+        div class="max-w-lg mx-auto" {
+            h1 class="text-xl mb-3" { "Top Up Credit" }
+
+            div class="space-y-3" {
+                p class="text-sm text-gray-600" {
+                    "The full amount is added to your credit balance and can pay for any order."
+                }
+                (total_row(top_up.amount))
+                (payment_widget(widget, &request))
+            }
+        }
+    };
+
+    base::base_layout(current_user, flash, site_name, "Top Up Credit", "Add credit to your balance", content)
+}
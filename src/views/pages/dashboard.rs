@@ -1,10 +1,17 @@
 use crate::{
     auth::CurrentUser,
+    constants::credits,
     session::FlashMessage,
     views::helpers as formatting,
-    models::order::OrderSummary,
+    models::{
+        credit::{CreditTransaction, FIELD_AMOUNT},
+        order::OrderSummary,
+    },
     paths,
-    views::layout::base::base_layout,
+    views::{
+        components::{credit_ledger::credit_ledger, form},
+        layout::base::base_layout,
+    },
 };
 use maud::{html, Markup};
 
@@ -13,6 +20,8 @@ pub fn dashboard(
     flash: Option<&FlashMessage>,
     site_name: &str,
     recent_orders: Vec<OrderSummary>,
+    credit_balance: i32,
+    credit_transactions: &[CreditTransaction],
 ) -> Markup {
     let content = html! {
         div class="max-w-4xl mx-auto" {
@@ -39,12 +48,40 @@ pub fn dashboard(
                     }
                 }
             }
+
+            (credits_section(credit_balance, credit_transactions))
         }
     };
 
     base_layout(current_user, flash, site_name, "Orders", "Your order history", content)
 }
 
+fn credits_section(credit_balance: i32, credit_transactions: &[CreditTransaction]) -> Markup {
+    html! {
+        div class="mt-8" {
+            h2 class="text-xl mb-3" { "Credits" }
+
+            div class="flex justify-between items-end mb-3" {
+                div {
+                    span class="text-gray-600 text-sm" { "Balance " }
+                    span class="text-xl text-indigo-600" { "₩" (formatting::format_price(credit_balance)) }
+                }
+                form method="post" action=(paths::forms::CREDITS_TOP_UPS) class="flex gap-2 text-sm" {
+                    (form::csrf_field())
+                    select name=(FIELD_AMOUNT) class="px-3 py-2 border focus:outline-none focus:border-indigo-600" {
+                        @for amount in credits::TOP_UP_CHOICES {
+                            option value=(amount) { "₩" (formatting::format_price(*amount)) }
+                        }
+                    }
+                    button type="submit" class="bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700" { "Top Up" }
+                }
+            }
+
+            (credit_ledger(credit_transactions, paths::helpers::quote_path))
+        }
+    }
+}
+
 fn order_row(order: &OrderSummary) -> Markup {
     let status_class = order.payment_status.css_class();
     let status_text = order.payment_status.display_text();
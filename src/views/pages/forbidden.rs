@@ -0,0 +1,11 @@
+use crate::{auth::CurrentUser, session::FlashMessage, views::layout::base::base_layout};
+use maud::{Markup, html};
+
+pub fn forbidden(current_user: &CurrentUser, flash: Option<&FlashMessage>, site_name: &str, message: &str) -> Markup {
+    let content = html! {
+        h1 class="text-6xl mb-3" { "403" }
+        p class="text-red-600" { (message) }
+    };
+
+    base_layout(current_user, flash, site_name, "Forbidden", "Forbidden", content)
+}
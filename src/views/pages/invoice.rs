@@ -0,0 +1,108 @@
+use maud::{html, Markup};
+
+use crate::{
+    auth::CurrentUser,
+    config::BusinessConfig,
+    models::order::{Order, OrderItemSummary},
+    session::FlashMessage,
+    views::{
+        helpers::{format_datetime, format_price},
+        layout::base::base_layout,
+    },
+};
+
+pub fn invoice(
+    current_user: &CurrentUser,
+    flash: Option<&FlashMessage>,
+    site_name: &str,
+    business: &BusinessConfig,
+    order: &Order,
+    items: &[OrderItemSummary],
+) -> Markup {
+    let items_total: i32 = items.iter().map(|item| item.price_amount).sum();
+    let subtotal = order.price_amount + order.discount_amount;
+
+    let content = html! {
+        div class="max-w-2xl mx-auto" {
+            div class="flex justify-between items-start mb-6" {
+                div {
+                    h1 class="text-xl mb-1" { "Invoice" }
+                    p class="text-sm text-gray-600" { (order.order_number) }
+                }
+                button type="button" onclick="window.print()"
+                    class="print:hidden px-3 py-2 border text-sm hover:bg-gray-50"
+                    { "Print" }
+            }
+
+            div class="grid grid-cols-2 gap-6 text-sm mb-6" {
+                div class="space-y-1" {
+                    p class="text-gray-600" { "From" }
+                    p { (business.name()) }
+                    p { "Business registration no. " (business.registration_number()) }
+                    p { (business.address()) }
+                    p { (business.contact_email()) }
+                }
+                div class="space-y-1" {
+                    p class="text-gray-600" { "Billed to" }
+                    p { (order.user_email) }
+                    @if let Some(paid_at) = order.paid_at {
+                        p class="text-gray-600 pt-2" { "Paid" }
+                        p { (format_datetime(paid_at)) " UTC" }
+                    }
+                    p class="text-gray-600 pt-2" { "Payment method" }
+                    p { @if order.paid_with_credits { "Credit balance" } @else { "Card" } }
+                }
+            }
+
+            table class="w-full text-sm mb-3" {
+                thead {
+                    tr class="text-left text-gray-600 border-b" {
+                        th class="py-1 font-normal" { "Text analysis" }
+                        th class="py-1 font-normal text-right" { "Characters" }
+                        th class="py-1 font-normal text-right" { "Amount" }
+                    }
+                }
+                tbody {
+                    @for item in items {
+                        tr class="border-b" {
+                            td class="py-1 break-all" { (item.filename) }
+                            td class="py-1 text-right" { (item.text_length) }
+                            td class="py-1 text-right whitespace-nowrap" { "₩" (format_price(item.price_amount)) }
+                        }
+                    }
+                }
+            }
+
+            div class="space-y-1 text-sm" {
+                @if subtotal > items_total {
+                    (amount_row("Minimum order adjustment", format!("₩{}", format_price(subtotal - items_total))))
+                }
+                @if let Some(code) = &order.coupon_code {
+                    (amount_row(&format!("Coupon {}", code), format!("−₩{}", format_price(order.discount_amount))))
+                }
+                div class="flex justify-between border-t pt-2 text-base" {
+                    span { "Total paid" }
+                    span { "₩" (format_price(order.price_amount)) }
+                }
+                @if order.refunded_amount > 0 {
+                    (amount_row("Refunded", format!("−₩{}", format_price(order.refunded_amount))))
+                    div class="flex justify-between" {
+                        span { "Net" }
+                        span { "₩" (format_price(order.price_amount - order.refunded_amount)) }
+                    }
+                }
+            }
+        }
+    };
+
+    base_layout(current_user, flash, site_name, "Invoice", &format!("Invoice for order {}", order.order_number), content)
+}
+
+fn amount_row(label: &str, amount: String) -> Markup {
+    html! {
+        div class="flex justify-between text-gray-600" {
+            span { (label) }
+            span { (amount) }
+        }
+    }
+}
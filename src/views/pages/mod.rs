@@ -1,23 +1,35 @@
 pub mod admin;
 
+mod account;
 mod checkout;
 mod dashboard;
+mod forbidden;
+mod invoice;
 mod not_found;
 mod payment_confirmation;
 mod quote;
 mod root;
 mod server_error;
+mod shared_report;
 mod sign_in;
 mod text_analyzer;
 mod todos;
+mod two_factor;
 
-pub use checkout::checkout;
+pub use account::account;
+pub use checkout::{checkout, top_up_checkout};
 pub use dashboard::dashboard;
+pub use forbidden::forbidden;
+pub use invoice::invoice;
 pub use not_found::not_found;
-pub use payment_confirmation::payment_confirmation;
-pub use quote::quote;
+pub use payment_confirmation::{
+    analysis_failed, analysis_results, analysis_running, payment_confirmation, AnalysisProgress, ShareLinks,
+};
+pub use quote::{quote, quote_expired};
 pub use root::root;
 pub use server_error::server_error;
+pub use shared_report::shared_report;
 pub use sign_in::sign_in;
 pub use text_analyzer::text_analyzer;
-pub use todos::{todo_item, todos};
\ No newline at end of file
+pub use todos::{todo_item, todos};
+pub use two_factor::{recovery_codes, two_factor_challenge, two_factor_setup, TwoFactorSetup};
\ No newline at end of file
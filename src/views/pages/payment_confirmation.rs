@@ -1,39 +1,275 @@
-use crate::{auth::CurrentUser, session::FlashMessage, models::order::Order, paths, views::layout::base::base_layout};
+use chrono::{DateTime, Utc};
 use maud::{Markup, html};
 
+use crate::{
+    auth::CurrentUser,
+    constants::{cdn, share_links::EXPIRY_CHOICES_DAYS},
+    export::ExportFormat,
+    models::{
+        analysis::{AnalysisReport, FileReport, TermCount},
+        analysis_job::{AnalysisJob, AnalysisJobStatus},
+        share_link::{ShareLink, FIELD_EXPIRES_IN_DAYS},
+        OrderId,
+    },
+    paths,
+    session::FlashMessage,
+    views::{components::form, helpers::format_datetime, layout::base::base_layout},
+};
+
+/// Where the order's background analysis stands when the page is rendered.
+pub enum AnalysisProgress<'a> {
+    /// One report per file, in upload order.
+    Complete(&'a [FileReport], ShareLinks<'a>),
+    Running(&'a AnalysisJob),
+    Failed,
+}
+
+/// The order's share links, and the origin their URLs are built on.
+pub struct ShareLinks<'a> {
+    pub base_url: &'a str,
+    pub links: &'a [ShareLink],
+    /// The results also arrive over the progress stream, which runs outside
+    /// the csrf middleware, so the forms can't mint a token themselves.
+    pub csrf_token: &'a str,
+}
+
 pub fn payment_confirmation(
     current_user: &CurrentUser,
     flash: Option<&FlashMessage>,
     site_name: &str,
-    order: &Order,
+    order_id: &OrderId,
+    progress: AnalysisProgress,
 ) -> Markup {
-    let word_count = order.text_content.split_whitespace().count();
-
     let content = html! {
-        div class="max-w-lg mx-auto" {
+        div class="max-w-2xl mx-auto" {
             p class="text-green-700 mb-3" { "✓ Payment successful" }
 
-            h1 class="text-xl mb-3" { "Analysis Complete" }
-
-            div class="space-y-3" {
-                div class="grid grid-cols-2 gap-3 text-sm" {
-                    div class="text-center py-3 border" {
-                        p class="text-2xl" { (order.text_length) }
-                        p class="text-gray-600 mt-1" { "Characters" }
+            div class="space-y-8" {
+                @match progress {
+                    AnalysisProgress::Complete(reports, share_links) => {
+                        div { (analysis_results(order_id, reports, &share_links)) }
                     }
-                    div class="text-center py-3 border" {
-                        p class="text-2xl" { (word_count) }
-                        p class="text-gray-600 mt-1" { "Words" }
+                    AnalysisProgress::Failed => {
+                        div { (analysis_failed()) }
+                    }
+                    // The stream sends "progress" while the job runs and one
+                    // final "done" with the results, which also closes it.
+                    AnalysisProgress::Running(job) => {
+                        script src=(cdn::HTMX_SSE_URL)
+                            integrity=(cdn::HTMX_SSE_INTEGRITY)
+                            crossorigin="anonymous" {}
+                        div
+                            hx-ext="sse"
+                            sse-connect=(paths::helpers::payment_confirmation_events_path(order_id))
+                            sse-swap="progress,done"
+                            sse-close="done"
+                        {
+                            (analysis_running(job))
+                        }
                     }
                 }
 
                 a
                     href=(paths::pages::TEXT_ANALYZER)
                     class="block w-full bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700 text-center"
-                    { "Analyze Another File" }
+                    { "Analyze More Files" }
             }
         }
     };
 
     base_layout(current_user, flash, site_name, "Payment Confirmation", "Text analysis results", content)
 }
+
+pub fn analysis_running(job: &AnalysisJob) -> Markup {
+    html! {
+        h1 class="text-xl mb-3" { "Analyzing your files…" }
+        progress class="w-full" value=(job.items_done) max=(job.items_total.max(1)) {
+            (job.progress_percent()) "%"
+        }
+        p class="text-sm text-gray-600 mt-2" {
+            @if job.status == AnalysisJobStatus::Queued && job.last_error.is_some() {
+                "Something went wrong; we'll try again shortly."
+            } @else {
+                (job.items_done) " of " (job.items_total) " files analyzed. This page updates on its own."
+            }
+        }
+    }
+}
+
+pub fn analysis_failed() -> Markup {
+    html! {
+        h1 class="text-xl mb-3" { "Analysis Delayed" }
+        p class="text-sm text-gray-600" {
+            "We couldn't finish analyzing your files. Our team has been notified and will "
+            "rerun the analysis; check back on this page later."
+        }
+    }
+}
+
+pub fn analysis_results(order_id: &OrderId, reports: &[FileReport], share_links: &ShareLinks) -> Markup {
+    html! {
+        h1 class="text-xl mb-3" { "Analysis Complete" }
+
+        p class="text-sm mb-6" {
+            span class="text-gray-600" { "Download: " }
+            @for (index, format) in ExportFormat::ALL.iter().enumerate() {
+                @if index > 0 { " · " }
+                a href=(paths::helpers::report_download_path(order_id, format.extension()))
+                    class="text-indigo-600 hover:underline"
+                    download
+                    { (format.display_text()) }
+            }
+        }
+
+        (share_panel(order_id, share_links))
+
+        (file_reports(reports))
+    }
+}
+
+fn share_panel(order_id: &OrderId, share_links: &ShareLinks) -> Markup {
+    let now = Utc::now();
+    let has_active = share_links.links.iter().any(|link| link.is_active(now));
+
+    html! {
+        details class="border p-3 mb-6" open[has_active] {
+            summary class="text-sm cursor-pointer" { "Share these results" }
+
+            p class="text-xs text-gray-500 mt-2" {
+                "Anyone with a link can view these results without signing in. "
+                "Downloads stay private, and every visit is logged."
+            }
+
+            form method="post" action=(paths::helpers::create_share_link_path(order_id))
+                class="flex items-center gap-2 text-sm mt-3"
+            {
+                (form::csrf_field_with(share_links.csrf_token))
+                label for=(FIELD_EXPIRES_IN_DAYS) class="text-gray-600" { "Expires" }
+                select id=(FIELD_EXPIRES_IN_DAYS) name=(FIELD_EXPIRES_IN_DAYS) class="border px-2 py-1" {
+                    @for days in EXPIRY_CHOICES_DAYS {
+                        option value=(days) selected[*days == 7] {
+                            "In " (days) @if *days == 1 { " day" } @else { " days" }
+                        }
+                    }
+                    option value="" { "Never" }
+                }
+                button type="submit" class="bg-indigo-600 text-white px-3 py-1 hover:bg-indigo-700" { "Create Link" }
+            }
+
+            @if !share_links.links.is_empty() {
+                ul class="mt-4 space-y-3" {
+                    @for link in share_links.links {
+                        (share_link_item(share_links, link, now))
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn share_link_item(share_links: &ShareLinks, link: &ShareLink, now: DateTime<Utc>) -> Markup {
+    let active = link.is_active(now);
+    let url = format!("{}{}", share_links.base_url, paths::helpers::shared_report_path(&link.token));
+
+    html! {
+        li class="text-sm border-t pt-3" {
+            @if active {
+                input type="text" readonly value=(url) aria-label="Share link"
+                    class="w-full border px-2 py-1 text-xs font-mono";
+            }
+            div class="flex items-center justify-between gap-2 mt-1 text-xs text-gray-600" {
+                span {
+                    span class=(if active { "text-green-600" } else { "text-gray-400" }) { (link.status_text(now)) }
+                    " · Created " (format_datetime(link.created_at))
+                    @match (link.revoked_at, link.expires_at) {
+                        (Some(revoked_at), _) => { " · Revoked " (format_datetime(revoked_at)) }
+                        (None, Some(expires_at)) => {
+                            @if active { " · Expires " } @else { " · Expired " }
+                            (format_datetime(expires_at))
+                        }
+                        (None, None) => { " · Never expires" }
+                    }
+                    " · " (link.access_count) @if link.access_count == 1 { " view" } @else { " views" }
+                    @if let Some(last_accessed_at) = link.last_accessed_at {
+                        ", last " (format_datetime(last_accessed_at))
+                    }
+                }
+                @if active {
+                    form method="post" action=(paths::helpers::revoke_share_link_path(&link.id)) {
+                        (form::csrf_field_with(share_links.csrf_token))
+                        button type="submit" class="text-red-600 hover:underline" { "Revoke" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// One section per file; the filename is only shown when there is more than one.
+pub(super) fn file_reports(reports: &[FileReport]) -> Markup {
+    html! {
+        div class="space-y-8" {
+            @for file in reports {
+                section class="space-y-6" {
+                    @if reports.len() > 1 {
+                        h2 class="text-lg border-b pb-1 break-all" { (file.filename) }
+                    }
+                    (report_section(&file.report))
+                }
+            }
+        }
+    }
+}
+
+fn report_section(report: &AnalysisReport) -> Markup {
+    html! {
+        div class="grid grid-cols-2 sm:grid-cols-4 gap-3 text-sm" {
+            (stat(&report.character_count.to_string(), "Characters"))
+            (stat(&report.word_count.to_string(), "Words"))
+            (stat(&report.sentence_count.to_string(), "Sentences"))
+            (stat(&report.paragraph_count.to_string(), "Paragraphs"))
+            (stat(&format!("{:.1}", report.average_word_length), "Avg. word length"))
+            (stat(&format!("{:.1}", report.average_sentence_length), "Avg. words / sentence"))
+            (stat(&format!("{} min", report.reading_time_minutes()), "Reading time"))
+            (stat(&format!("{:.0}", report.flesch_reading_ease), report.readability_label()))
+        }
+        p class="text-xs text-gray-500" {
+            "Readability is the Flesch reading ease score: higher is easier, 60–70 is plain English."
+        }
+
+        div class="grid sm:grid-cols-3 gap-6" {
+            (term_list("Top words", &report.top_words))
+            (term_list("Top phrases", &report.top_bigrams))
+            (term_list("Top 3-word phrases", &report.top_trigrams))
+        }
+    }
+}
+
+fn stat(value: &str, label: &str) -> Markup {
+    html! {
+        div class="text-center py-3 border" {
+            p class="text-2xl" { (value) }
+            p class="text-gray-600 mt-1" { (label) }
+        }
+    }
+}
+
+fn term_list(title: &str, terms: &[TermCount]) -> Markup {
+    html! {
+        div {
+            h2 class="text-sm text-gray-600 mb-2" { (title) }
+            @if terms.is_empty() {
+                p class="text-sm text-gray-400" { "None repeated" }
+            } @else {
+                ol class="text-sm space-y-1" {
+                    @for term in terms {
+                        li class="flex justify-between border-b py-1" {
+                            span { (term.term) }
+                            span class="text-gray-500" { (term.count) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{auth::CurrentUser, session::FlashMessage, views::helpers::format_price, models::order::Order, paths, views::layout::base::base_layout};
+use crate::{auth::CurrentUser, session::FlashMessage, views::helpers::{format_file_size, format_price}, models::{analysis::AnalysisPreview, coupon::FIELD_CODE, order::{Order, OrderItemSummary, PaymentStatus}}, paths, views::{components::form, layout::base::base_layout}};
 use maud::{Markup, html};
 
 pub fn quote(
@@ -6,33 +6,63 @@ pub fn quote(
     flash: Option<&FlashMessage>,
     site_name: &str,
     order: &Order,
+    items: &[OrderItemSummary],
+    preview: &AnalysisPreview,
+    credit_balance: i32,
 ) -> Markup {
+    let paid = order.payment_status.is_paid();
+
     let content = html! {
         div class="max-w-lg mx-auto" {
             h1 class="text-xl mb-3" { "Quote" }
 
             div class="space-y-3" {
-                div class="space-y-1 text-sm" {
-                    div class="flex justify-between" {
-                        span class="text-gray-600" { "File" }
-                        span { (order.filename) }
+                table class="w-full text-sm" {
+                    thead {
+                        tr class="text-left text-gray-600 border-b" {
+                            th class="py-1 font-normal" { "File" }
+                            th class="py-1 font-normal text-right" { "Size" }
+                            th class="py-1 font-normal text-right" { "Characters" }
+                            th class="py-1 font-normal text-right" { "Price" }
+                        }
                     }
-                    div class="flex justify-between" {
-                        span class="text-gray-600" { "Size" }
-                        span {
-                            @let bytes = order.file_size as f64;
-                            @if bytes < 1024.0 {
-                                (format!("{} B", bytes))
-                            } @else if bytes < 1024.0 * 1024.0 {
-                                (format!("{:.2} KB", bytes / 1024.0))
-                            } @else {
-                                (format!("{:.2} MB", bytes / (1024.0 * 1024.0)))
+                    tbody {
+                        @for item in items {
+                            tr class="border-b" {
+                                td class="py-1 break-all" {
+                                    (item.filename)
+                                    @if let Some(encoding) = &item.text_encoding {
+                                        span class="block text-xs text-gray-500" { (encoding) }
+                                    }
+                                }
+                                td class="py-1 text-right whitespace-nowrap" { (format_file_size(item.file_size)) }
+                                td class="py-1 text-right" { (item.text_length) }
+                                td class="py-1 text-right whitespace-nowrap" { "₩" (format_price(item.price_amount)) }
                             }
                         }
                     }
-                    div class="flex justify-between" {
-                        span class="text-gray-600" { "Characters" }
-                        span { (order.text_length.to_string()) }
+                }
+
+                @let items_total: i32 = items.iter().map(|item| item.price_amount).sum();
+                @let subtotal = order.price_amount + order.discount_amount;
+                @if subtotal > items_total {
+                    div class="flex justify-between text-sm text-gray-600" {
+                        span { "Minimum order adjustment" }
+                        span { "₩" (format_price(subtotal - items_total)) }
+                    }
+                }
+                @if let Some(code) = &order.coupon_code {
+                    div class="flex justify-between items-center text-sm text-gray-600" {
+                        span {
+                            "Coupon " span class="font-mono" { (code) }
+                            @if !paid {
+                                form method="post" action=(paths::helpers::remove_coupon_path(&order.id)) class="inline ml-2" {
+                                    (form::csrf_field())
+                                    button type="submit" class="text-indigo-600 hover:underline" { "Remove" }
+                                }
+                            }
+                        }
+                        span { "−₩" (format_price(order.discount_amount)) }
                     }
                 }
 
@@ -43,12 +73,49 @@ pub fn quote(
                     }
                 }
 
-                form method="post" action=(paths::actions::PAYMENT_INITIATE) {
-                    input type="hidden" name="order_id" value=(order.id.to_string());
-                    button
-                        type="submit"
-                        class="w-full bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700"
-                        { "Pay Now" }
+                @if matches!(order.payment_status, PaymentStatus::Pending) {
+                    form method="post" action=(paths::helpers::apply_coupon_path(&order.id)) class="flex gap-2" {
+                        (form::csrf_field())
+                        input type="text" name=(FIELD_CODE) required
+                            class="flex-1 px-3 py-2 border text-sm uppercase focus:outline-none focus:border-indigo-600"
+                            placeholder="Coupon code";
+                        button type="submit" class="px-3 py-2 border text-sm hover:bg-gray-50" {
+                            @if order.coupon_code.is_some() { "Replace" } @else { "Apply" }
+                        }
+                    }
+                }
+
+                (preview_panel(preview, paid))
+
+                @if paid {
+                    a href=(paths::helpers::payment_confirmation_path(&order.id))
+                        class="block w-full text-center bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700"
+                        { "View Full Report" }
+                    a href=(paths::helpers::invoice_path(&order.id))
+                        class="block text-center text-sm text-indigo-600 hover:underline"
+                        { "Invoice" }
+                } @else if matches!(order.payment_status, PaymentStatus::Pending) {
+                    (credit_payment(order, credit_balance))
+                    form method="post" action=(paths::actions::PAYMENT_INITIATE) {
+                        (form::csrf_field())
+                        input type="hidden" name="order_id" value=(order.id.to_string());
+                        button
+                            type="submit"
+                            class="w-full bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700"
+                            { "Pay Now" }
+                    }
+                } @else if matches!(order.payment_status, PaymentStatus::Failed) {
+                    p class="text-sm text-red-600" { "The last payment attempt failed. You have not been charged." }
+                    form method="post" action=(paths::actions::PAYMENT_RETRY) {
+                        (form::csrf_field())
+                        input type="hidden" name="order_id" value=(order.id.to_string());
+                        button
+                            type="submit"
+                            class="w-full bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700"
+                            { "Retry Payment" }
+                    }
+                } @else {
+                    p class="text-sm text-gray-600" { "This order is " (order.payment_status.display_text().to_lowercase()) "." }
                 }
             }
         }
@@ -56,3 +123,88 @@ pub fn quote(
 
     base_layout(current_user, flash, site_name, "Quote", "Review your quote", content)
 }
+
+/// The order's text is gone, so there is nothing left to pay for or preview.
+pub fn quote_expired(current_user: &CurrentUser, flash: Option<&FlashMessage>, site_name: &str, order: &Order) -> Markup {
+    let content = html! {
+        div class="max-w-lg mx-auto" {
+            h1 class="text-xl mb-3" { "Quote Expired" }
+
+            div class="space-y-3" {
+                p class="text-sm text-gray-600" {
+                    "This quote for " (order.filename) " expired before it was paid, and the uploaded text has been deleted. "
+                    "Upload your files again for a new quote."
+                }
+                a href=(paths::pages::TEXT_ANALYZER)
+                    class="block w-full text-center bg-indigo-600 text-white px-3 py-2 hover:bg-indigo-700"
+                    { "Re-upload Files" }
+            }
+        }
+    };
+
+    base_layout(current_user, flash, site_name, "Quote Expired", "This quote has expired", content)
+}
+
+/// Offered only when the balance covers the whole order; there is no split payment.
+fn credit_payment(order: &Order, credit_balance: i32) -> Markup {
+    html! {
+        @if credit_balance >= order.price_amount {
+            form method="post" action=(paths::actions::CREDITS_PAY) class="mb-2" {
+                (form::csrf_field())
+                input type="hidden" name="order_id" value=(order.id.to_string());
+                button
+                    type="submit"
+                    class="w-full border border-indigo-600 text-indigo-600 px-3 py-2 hover:bg-indigo-50"
+                    { "Pay ₩" (format_price(order.price_amount)) " from credits (balance ₩" (format_price(credit_balance)) ")" }
+            }
+        } @else if credit_balance > 0 {
+            p class="text-sm text-gray-600 mb-2" {
+                "Credit balance ₩" (format_price(credit_balance)) " doesn't cover this order. "
+                a href=(paths::pages::DASHBOARD) class="text-indigo-600 hover:underline" { "Top up" }
+            }
+        }
+    }
+}
+
+/// Counts across every file in the order; the locked list is what payment adds.
+fn preview_panel(preview: &AnalysisPreview, paid: bool) -> Markup {
+    html! {
+        div class="border p-3 space-y-3" {
+            h2 class="text-sm text-gray-600" { "Free preview" }
+
+            div class="flex justify-between text-sm" {
+                span { "Words" }
+                span { (preview.word_count) }
+            }
+
+            div {
+                p class="text-sm mb-1" { "Top keywords" }
+                @if preview.top_words.is_empty() {
+                    p class="text-sm text-gray-400" { "None repeated" }
+                } @else {
+                    ol class="text-sm space-y-1" {
+                        @for term in &preview.top_words {
+                            li class="flex justify-between border-b py-1" {
+                                span { (term.term) }
+                                span class="text-gray-500" { (term.count) }
+                            }
+                        }
+                    }
+                }
+            }
+
+            @if !paid {
+                div class="text-sm text-gray-500" {
+                    p class="mb-1" { "Unlocked after payment:" }
+                    ul class="list-disc list-inside" {
+                        li { "Readability score" }
+                        li { "Sentence and paragraph statistics" }
+                        li { "Reading time" }
+                        li { "Full keyword list and repeated phrases, per file" }
+                        li { "JSON, CSV and PDF downloads" }
+                    }
+                }
+            }
+        }
+    }
+}
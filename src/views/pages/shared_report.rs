@@ -0,0 +1,32 @@
+use crate::{
+    auth::CurrentUser,
+    models::analysis::FileReport,
+    views::layout::base::base_layout,
+};
+use maud::{Markup, html};
+
+use super::payment_confirmation::file_reports;
+
+/// Read-only results behind a share link. `None` while the analysis is unfinished.
+pub fn shared_report(current_user: &CurrentUser, site_name: &str, reports: Option<&[FileReport]>) -> Markup {
+    let content = html! {
+        div class="max-w-2xl mx-auto" {
+            p class="text-sm text-gray-500 mb-3" { "Shared analysis results · read-only" }
+
+            @match reports {
+                Some(reports) => {
+                    h1 class="text-xl mb-6" { "Text Analysis Results" }
+                    (file_reports(reports))
+                }
+                None => {
+                    h1 class="text-xl mb-3" { "Results Not Ready" }
+                    p class="text-sm text-gray-600" {
+                        "These files are still being analyzed. Check this link again later."
+                    }
+                }
+            }
+        }
+    };
+
+    base_layout(current_user, None, site_name, "Shared Results", "Shared text analysis results", content)
+}
@@ -22,6 +22,15 @@ pub fn sign_in(
                 (form::input("email", FIELD_EMAIL, "Email", email_value, email_error))
                 (form::submit_button("Send Magic Link"))
             }
+
+            button id="passkey-sign-in" type="button" hidden
+                data-start=(paths::actions::PASSKEYS_AUTHENTICATE_START)
+                data-finish=(paths::actions::PASSKEYS_AUTHENTICATE_FINISH)
+                class="w-full mt-3 border border-indigo-600 text-indigo-600 px-3 py-2 hover:bg-indigo-50"
+            {
+                "Sign in with a Passkey"
+            }
+            script src=(paths::static_files::PASSKEYS_JS) {}
         }
     };
 
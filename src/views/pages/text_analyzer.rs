@@ -1,4 +1,4 @@
-use crate::{auth::CurrentUser, session::FlashMessage, paths, views::layout::base::base_layout};
+use crate::{auth::CurrentUser, constants::file_upload, session::FlashMessage, paths, views::{components::form, layout::base::base_layout}};
 use maud::{Markup, html};
 
 pub fn text_analyzer(
@@ -11,17 +11,22 @@ pub fn text_analyzer(
             h1 class="text-xl mb-3" { "Text Analyzer" }
 
             form method="post" action=(paths::forms::TEXT_ANALYZER) enctype="multipart/form-data" class="space-y-3" {
+                (form::csrf_field())
                 div {
                     label for="file" class="block text-sm mb-1" {
-                        "Text File"
+                        "Documents"
                     }
                     input
                         type="file"
                         id="file"
                         name="file"
-                        accept=".txt"
+                        accept=(file_upload::ACCEPTED_EXTENSIONS)
+                        multiple
                         required
                         class="w-full px-3 py-2 border";
+                    p class="text-xs text-gray-500 mt-1" {
+                        "Plain text, Markdown, HTML, Word (.docx) or PDF — up to " (file_upload::MAX_FILES_PER_ORDER) " files per order. Pricing is based on the extracted text."
+                    }
                 }
 
                 button
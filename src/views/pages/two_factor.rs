@@ -0,0 +1,122 @@
+use crate::{
+    auth::CurrentUser,
+    models::two_factor::FIELD_CODE,
+    paths,
+    session::FlashMessage,
+    views::{components::form, layout::base::base_layout},
+};
+use maud::{html, Markup, PreEscaped};
+use qrcode::{render::svg, QrCode};
+
+const QR_CODE_SIZE: u32 = 200;
+
+pub enum TwoFactorSetup<'a> {
+    Pending { secret: &'a str, otpauth_uri: &'a str },
+    Enabled { unused_recovery_codes: i64 },
+}
+
+pub fn two_factor_setup(
+    current_user: &CurrentUser,
+    flash: Option<&FlashMessage>,
+    site_name: &str,
+    setup: TwoFactorSetup,
+    code_error: Option<&str>,
+) -> Markup {
+    let content = html! {
+        div class="max-w-sm mx-auto space-y-4" {
+            h1 class="text-xl" { "Two-Factor Authentication" }
+
+            @match setup {
+                TwoFactorSetup::Pending { secret, otpauth_uri } => {
+                    p class="text-sm text-gray-600" {
+                        "Scan this code with an authenticator app, then enter the 6-digit code it shows."
+                    }
+                    div class="flex justify-center" { (qr_code(otpauth_uri)) }
+                    p class="text-xs text-gray-500 break-all" { "Or enter this key manually: " code { (secret) } }
+
+                    form method="POST" action=(paths::forms::TWO_FACTOR_ENABLE) class="space-y-3" {
+                        (form::input("text", FIELD_CODE, "123456", None, code_error))
+                        (form::submit_button("Enable"))
+                    }
+                }
+                TwoFactorSetup::Enabled { unused_recovery_codes } => {
+                    p class="text-green-700" { "Two-factor authentication is enabled." }
+                    p class="text-sm text-gray-600" { (unused_recovery_codes) " unused recovery codes left." }
+
+                    form method="POST" action=(paths::forms::TWO_FACTOR_DISABLE) class="space-y-3" {
+                        (form::csrf_field())
+                        (form::input("text", FIELD_CODE, "Authenticator or recovery code", None, code_error))
+                        button type="submit" class="w-full border border-red-600 text-red-600 px-3 py-2 hover:bg-red-50" {
+                            "Disable"
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    base_layout(current_user, flash, site_name, "Two-Factor Authentication", "Manage two-factor authentication", content)
+}
+
+/// Shown once, right after enrollment. Only hashes are stored.
+pub fn recovery_codes(
+    current_user: &CurrentUser,
+    flash: Option<&FlashMessage>,
+    site_name: &str,
+    codes: &[String],
+) -> Markup {
+    let content = html! {
+        div class="max-w-sm mx-auto space-y-4" {
+            h1 class="text-xl" { "Recovery Codes" }
+            p class="text-sm text-gray-600" {
+                "Two-factor authentication is enabled. Save these codes somewhere safe — each can be used once "
+                "if you lose your authenticator. They will not be shown again."
+            }
+            ul class="grid grid-cols-2 gap-2 font-mono" {
+                @for code in codes {
+                    li class="border px-2 py-1 text-center" { (code) }
+                }
+            }
+            a href=(paths::pages::ACCOUNT) class="block text-center text-indigo-600 hover:underline" { "Done" }
+        }
+    };
+
+    base_layout(current_user, flash, site_name, "Recovery Codes", "Two-factor recovery codes", content)
+}
+
+pub fn two_factor_challenge(
+    current_user: &CurrentUser,
+    flash: Option<&FlashMessage>,
+    site_name: &str,
+    code_error: Option<&str>,
+) -> Markup {
+    let content = html! {
+        div class="max-w-sm mx-auto" {
+            h1 class="text-xl mb-3" { "Two-Factor Authentication" }
+            p class="text-sm text-gray-600 mb-3" {
+                "Enter the code from your authenticator app, or one of your recovery codes."
+            }
+
+            form method="POST" action=(paths::forms::TWO_FACTOR_VERIFY) class="space-y-3" {
+                (form::input("text", FIELD_CODE, "123456", None, code_error))
+                (form::submit_button("Verify"))
+            }
+        }
+    };
+
+    base_layout(current_user, flash, site_name, "Two-Factor Authentication", "Verify your identity", content)
+}
+
+fn qr_code(data: &str) -> Markup {
+    match QrCode::new(data.as_bytes()) {
+        Ok(code) => PreEscaped(
+            code.render::<svg::Color>()
+                .min_dimensions(QR_CODE_SIZE, QR_CODE_SIZE)
+                .build(),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to render TOTP QR code: {}", e);
+            html! { p class="text-sm text-red-600" { "QR code unavailable — enter the key manually." } }
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use axum::{
+    Json,
     http::StatusCode,
     response::{IntoResponse, Response},
 };
 use maud::Markup;
+use serde_json::json;
 
 pub fn empty_ok_response() -> Response {
     StatusCode::OK.into_response()
@@ -11,3 +13,8 @@ pub fn empty_ok_response() -> Response {
 pub fn html_fragment(markup: Markup) -> Response {
     markup.into_response()
 }
+
+/// Navigation target for fetch()-driven flows (passkeys.js) that can't follow a redirect.
+pub fn client_redirect(path: &str) -> Response {
+    Json(json!({ "redirect": path })).into_response()
+}
@@ -0,0 +1,114 @@
+// WebAuthn glue for the account page (#passkey-register) and sign-in page
+// (#passkey-sign-in). The server answers every step with either WebAuthn
+// options or {"redirect": path}; failures are reported via flash messages.
+(function () {
+  const csrfToken = document.querySelector('meta[name="csrf-token"]').content;
+
+  function toBytes(base64url) {
+    const base64 = base64url.replace(/-/g, '+').replace(/_/g, '/');
+    const padded = base64 + '='.repeat((4 - (base64.length % 4)) % 4);
+    return Uint8Array.from(atob(padded), (c) => c.charCodeAt(0));
+  }
+
+  function toBase64url(buffer) {
+    const bytes = new Uint8Array(buffer);
+    let binary = '';
+    bytes.forEach((b) => { binary += String.fromCharCode(b); });
+    return btoa(binary).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+  }
+
+  async function post(url, body) {
+    const response = await fetch(url, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', 'X-CSRF-Token': csrfToken },
+      body: body === undefined ? undefined : JSON.stringify(body),
+    });
+    const data = await response.json();
+    if (data.redirect) {
+      window.location.assign(data.redirect);
+      return null;
+    }
+    return data;
+  }
+
+  async function register(form) {
+    const options = await post(form.dataset.start);
+    if (!options) return;
+
+    const publicKey = options.publicKey;
+    publicKey.challenge = toBytes(publicKey.challenge);
+    publicKey.user.id = toBytes(publicKey.user.id);
+    (publicKey.excludeCredentials || []).forEach((c) => { c.id = toBytes(c.id); });
+
+    const credential = await navigator.credentials.create({ publicKey });
+    await post(form.dataset.finish, {
+      name: form.elements.name.value,
+      credential: {
+        id: credential.id,
+        rawId: toBase64url(credential.rawId),
+        type: credential.type,
+        response: {
+          attestationObject: toBase64url(credential.response.attestationObject),
+          clientDataJSON: toBase64url(credential.response.clientDataJSON),
+        },
+        extensions: credential.getClientExtensionResults(),
+      },
+    });
+  }
+
+  async function signIn(button) {
+    const options = await post(button.dataset.start);
+    if (!options) return;
+
+    const publicKey = options.publicKey;
+    publicKey.challenge = toBytes(publicKey.challenge);
+    (publicKey.allowCredentials || []).forEach((c) => { c.id = toBytes(c.id); });
+
+    // The server offers conditional (autofill) mediation; an explicit button
+    // press wants the regular account picker instead.
+    const credential = await navigator.credentials.get({ publicKey });
+    const response = credential.response;
+    await post(button.dataset.finish, {
+      id: credential.id,
+      rawId: toBase64url(credential.rawId),
+      type: credential.type,
+      response: {
+        authenticatorData: toBase64url(response.authenticatorData),
+        clientDataJSON: toBase64url(response.clientDataJSON),
+        signature: toBase64url(response.signature),
+        userHandle: response.userHandle ? toBase64url(response.userHandle) : null,
+      },
+      extensions: credential.getClientExtensionResults(),
+    });
+  }
+
+  function reportCancelled(error) {
+    // NotAllowedError = the user dismissed the browser prompt; nothing to report.
+    if (error.name !== 'NotAllowedError') {
+      alert('Passkey error: ' + error.message);
+    }
+  }
+
+  document.addEventListener('DOMContentLoaded', () => {
+    const supported = !!window.PublicKeyCredential;
+    document.querySelectorAll('[data-passkey-unsupported]').forEach((el) => {
+      el.hidden = supported;
+    });
+    if (!supported) return;
+
+    const form = document.getElementById('passkey-register');
+    if (form) {
+      form.hidden = false;
+      form.addEventListener('submit', (event) => {
+        event.preventDefault();
+        register(form).catch(reportCancelled);
+      });
+    }
+
+    const button = document.getElementById('passkey-sign-in');
+    if (button) {
+      button.hidden = false;
+      button.addEventListener('click', () => signIn(button).catch(reportCancelled));
+    }
+  });
+})();